@@ -0,0 +1,452 @@
+/*
+  src/archive.rs
+*/
+//! Reading projects from `.zip`, `.tar`, and `.tar.gz` archives.
+//!
+//! Build artifacts often arrive as an archive (for example a CI artifact download). This module
+//! reads the entries of such an archive in memory, applies the same `.surgeignore` and junk rules
+//! used for project directories, and repacks the remaining files as the `.tar.gz` upload expected
+//! by the Surge API, so the archive never has to be extracted to disk.
+//!
+//! The format is detected from the archive's magic bytes, falling back to its file extension.
+
+use crate::{
+    error::SurgeError,
+    plan::{PlannedFile, PublishPlan, plan_files},
+    stream::{PublishOptions, append_file, archived_mode, build_ignore_matcher, build_tarball},
+};
+use flate2::read::GzDecoder;
+use log::{debug, trace};
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::{Component, Path, PathBuf},
+};
+
+/// The archive formats that can be published directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+/// A regular file read from an archive.
+struct ArchiveEntry {
+    /// The path relative to the archive root.
+    path: PathBuf,
+    /// The unix mode recorded in the archive.
+    mode: u32,
+    /// The modification time in seconds since the Unix epoch.
+    mtime: u64,
+    /// The file contents.
+    data: Vec<u8>,
+}
+
+/// Reads an archive and repacks its files as the `.tar.gz` upload for a publish.
+///
+/// # Arguments
+/// * `archive` - Path to the `.zip`, `.tar`, or `.tar.gz` archive.
+/// * `options` - The publish options that determine which files are included.
+/// * `preserve_permissions` - Whether to keep each entry's unix mode instead of `0o644`.
+/// * `compress` - Whether to gzip the repacked tarball.
+/// * `max_unpacked` - The most bytes the archive's files may add up to once decompressed.
+///
+/// # Returns
+/// A `Result` containing the `PublishPlan` and the tarball, or a `SurgeError` if the archive
+/// cannot be read or its format is not supported, or `SurgeError::InvalidProject` if its files
+/// unpack to more than `max_unpacked` bytes.
+pub(crate) fn repack(
+    archive: &Path,
+    options: &PublishOptions,
+    preserve_permissions: bool,
+    compress: bool,
+    max_unpacked: u64,
+) -> Result<(PublishPlan, Vec<u8>), SurgeError> {
+    debug!("Repacking archive: {:?}", archive);
+
+    let mut file = File::open(archive).map_err(|e| {
+        SurgeError::Io(format!(
+            "Failed to open archive {}: {}",
+            archive.display(),
+            e
+        ))
+    })?;
+    let format = detect_format(archive, &mut file)?;
+    debug!("Detected archive format: {:?}", format);
+
+    let mut budget = UnpackBudget {
+        archive,
+        remaining: max_unpacked,
+        max: max_unpacked,
+    };
+    let entries = match format {
+        ArchiveFormat::Zip => read_zip(BufReader::new(file), &mut budget),
+        ArchiveFormat::Tar => read_tar(BufReader::new(file), &mut budget),
+        ArchiveFormat::TarGz => read_tar(GzDecoder::new(BufReader::new(file)), &mut budget),
+    }?;
+
+    let surgeignore = entries
+        .iter()
+        .find(|entry| entry.path == Path::new(".surgeignore"))
+        .map(|entry| String::from_utf8_lossy(&entry.data).into_owned());
+    let ignore_matcher = build_ignore_matcher(Path::new(""), surgeignore.as_deref(), options)?;
+
+    let entries: Vec<ArchiveEntry> = entries
+        .into_iter()
+        .filter(|entry| {
+            let is_ignored = ignore_matcher
+                .matched_path_or_any_parents(&entry.path, false)
+                .is_ignore();
+            if is_ignored {
+                trace!("Ignored archive entry: {}", entry.path.display());
+            }
+            !is_ignored
+        })
+        .collect();
+
+    let files = entries
+        .iter()
+        .map(|entry| PlannedFile {
+            path: entry.path.clone(),
+            size: entry.data.len() as u64,
+        })
+        .collect();
+    let plan = plan_files(files, options)?;
+
+    let root = options.archive_root.resolve(&project_name(archive));
+    let tarball = build_tarball(compress, |tar| {
+        for entry in &entries {
+            let tar_path = root.join(&entry.path);
+            append_file(
+                tar,
+                &tar_path,
+                entry.data.len() as u64,
+                archived_mode(entry.mode, preserve_permissions),
+                entry.mtime,
+                &entry.data[..],
+                options.tar_format,
+            )?;
+        }
+        Ok(())
+    })?;
+
+    Ok((plan, tarball))
+}
+
+/// Detects the format of an archive from its magic bytes, falling back to its extension.
+///
+/// The file is rewound to the start before returning.
+fn detect_format(archive: &Path, file: &mut File) -> Result<ArchiveFormat, SurgeError> {
+    let mut header = Vec::with_capacity(512);
+    file.by_ref().take(512).read_to_end(&mut header)?;
+    file.seek(SeekFrom::Start(0))?;
+
+    if header.starts_with(b"PK\x03\x04") || header.starts_with(b"PK\x05\x06") {
+        return Ok(ArchiveFormat::Zip);
+    }
+    if header.starts_with(&[0x1f, 0x8b]) {
+        return Ok(ArchiveFormat::TarGz);
+    }
+    if header.get(257..262) == Some(b"ustar") {
+        return Ok(ArchiveFormat::Tar);
+    }
+
+    let name = archive
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if name.ends_with(".zip") {
+        Ok(ArchiveFormat::Zip)
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Ok(ArchiveFormat::TarGz)
+    } else if name.ends_with(".tar") {
+        Ok(ArchiveFormat::Tar)
+    } else {
+        Err(SurgeError::InvalidProject(format!(
+            "Unsupported archive format: {}",
+            archive.display()
+        )))
+    }
+}
+
+/// The decompressed bytes an archive's files may still take up.
+struct UnpackBudget<'a> {
+    /// The archive being read, for error messages.
+    archive: &'a Path,
+    /// Bytes left before the archive is rejected.
+    remaining: u64,
+    /// The limit for the whole archive.
+    max: u64,
+}
+
+impl UnpackBudget<'_> {
+    /// Reads an entry's contents, spending their size from the budget.
+    ///
+    /// At most one byte past the remaining budget is decompressed, whatever size the entry
+    /// declares, so a zip bomb is stopped before it fills memory.
+    ///
+    /// # Arguments
+    /// * `entry` - The entry's decompressing reader.
+    /// * `declared` - The size recorded in the archive, checked before anything is read.
+    ///
+    /// # Returns
+    /// A `Result` containing the contents, or `SurgeError::InvalidProject` once the budget is
+    /// exceeded.
+    fn read(&mut self, entry: impl Read, declared: u64) -> Result<Vec<u8>, SurgeError> {
+        if declared > self.remaining {
+            return Err(self.exceeded());
+        }
+        let mut data = Vec::new();
+        entry.take(self.remaining + 1).read_to_end(&mut data)?;
+        let size = data.len() as u64;
+        if size > self.remaining {
+            return Err(self.exceeded());
+        }
+        self.remaining -= size;
+        Ok(data)
+    }
+
+    fn exceeded(&self) -> SurgeError {
+        SurgeError::InvalidProject(format!(
+            "Archive {} unpacks to more than {} bytes",
+            self.archive.display(),
+            self.max
+        ))
+    }
+}
+
+/// Reads the regular files of a zip archive.
+fn read_zip(
+    reader: impl Read + Seek,
+    budget: &mut UnpackBudget,
+) -> Result<Vec<ArchiveEntry>, SurgeError> {
+    let mut zip = zip::ZipArchive::new(reader)
+        .map_err(|e| SurgeError::Io(format!("Failed to read zip archive: {}", e)))?;
+    let mut entries = Vec::with_capacity(zip.len());
+
+    for index in 0..zip.len() {
+        let mut file = zip
+            .by_index(index)
+            .map_err(|e| SurgeError::Io(format!("Failed to read zip archive: {}", e)))?;
+        if !file.is_file() {
+            continue;
+        }
+        let Some(path) = file.enclosed_name() else {
+            return Err(SurgeError::InvalidProject(format!(
+                "Archive entry escapes the archive root: {}",
+                file.name()
+            )));
+        };
+
+        let mode = file.unix_mode().unwrap_or(0o644);
+        let mtime = file.last_modified().map(zip_mtime).unwrap_or(0);
+        let declared = file.size();
+        let data = budget.read(&mut file, declared)?;
+        entries.push(ArchiveEntry {
+            path,
+            mode,
+            mtime,
+            data,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Reads the regular files of an uncompressed tar stream.
+fn read_tar(reader: impl Read, budget: &mut UnpackBudget) -> Result<Vec<ArchiveEntry>, SurgeError> {
+    let mut tar = tar::Archive::new(reader);
+    let mut entries = Vec::new();
+
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let raw_path = entry.path()?.into_owned();
+        let Some(path) = enclosed_path(&raw_path) else {
+            return Err(SurgeError::InvalidProject(format!(
+                "Archive entry escapes the archive root: {}",
+                raw_path.display()
+            )));
+        };
+
+        let mode = entry.header().mode().unwrap_or(0o644);
+        let mtime = entry.header().mtime().unwrap_or(0);
+        let declared = entry.size();
+        let data = budget.read(&mut entry, declared)?;
+        entries.push(ArchiveEntry {
+            path,
+            mode,
+            mtime,
+            data,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Returns `path` without `.` components, or `None` if it is absolute or contains `..`.
+fn enclosed_path(path: &Path) -> Option<PathBuf> {
+    let mut enclosed = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => enclosed.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!enclosed.as_os_str().is_empty()).then_some(enclosed)
+}
+
+/// Converts a zip timestamp, which has no time zone, to seconds since the Unix epoch as UTC.
+fn zip_mtime(time: zip::DateTime) -> u64 {
+    chrono::NaiveDate::from_ymd_opt(time.year().into(), time.month().into(), time.day().into())
+        .and_then(|date| {
+            date.and_hms_opt(
+                time.hour().into(),
+                time.minute().into(),
+                time.second().into(),
+            )
+        })
+        .and_then(|datetime| u64::try_from(datetime.and_utc().timestamp()).ok())
+        .unwrap_or(0)
+}
+
+/// Returns the archive file name without its archive extension, e.g. `site` for `site.tar.gz`.
+fn project_name(archive: &Path) -> String {
+    let name = archive
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("project");
+    [".tar.gz", ".tgz", ".tar", ".zip"]
+        .iter()
+        .find_map(|ext| name.strip_suffix(ext))
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or(name)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::MAX_ARCHIVE_UNPACKED_BYTES;
+    use flate2::{Compression, write::GzEncoder};
+
+    /// Writes a `.tar.gz` with the given entries to `path`.
+    fn write_tar_gz(path: &Path, entries: &[(&str, &str)]) {
+        let encoder = GzEncoder::new(File::create(path).unwrap(), Compression::default());
+        let mut tar = tar::Builder::new(encoder);
+        for (name, contents) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, name, contents.as_bytes())
+                .unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
+    }
+
+    /// Returns the entry paths of a `.tar.gz` held in memory.
+    fn tarball_entries(tarball: &[u8]) -> Vec<String> {
+        let mut archive = tar::Archive::new(GzDecoder::new(tarball));
+        archive
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_repack_tar_gz_detected_by_magic() {
+        let dir = tempfile::tempdir().unwrap();
+        // No extension, so the format must come from the magic bytes
+        let archive = dir.path().join("artifact");
+        write_tar_gz(
+            &archive,
+            &[
+                ("./index.html", "hello"),
+                ("css/site.css", "body {}"),
+                ("app.js.map", "{}"),
+                (".DS_Store", "junk"),
+                (".surgeignore", "*.map\n"),
+            ],
+        );
+
+        let (plan, tarball) = repack(
+            &archive,
+            &PublishOptions::default(),
+            false,
+            true,
+            MAX_ARCHIVE_UNPACKED_BYTES,
+        )
+        .unwrap();
+        assert_eq!(plan.file_count(), 3);
+        assert_eq!(plan.total_size, 18);
+        assert_eq!(
+            tarball_entries(&tarball),
+            vec![
+                "artifact/index.html",
+                "artifact/css/site.css",
+                "artifact/.surgeignore"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_repack_rejects_oversized_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("site.tar.gz");
+        write_tar_gz(&archive, &[("a.html", "aaaa"), ("b.html", "bbbb")]);
+        let repack_within = |max| repack(&archive, &PublishOptions::default(), false, true, max);
+
+        assert!(repack_within(8).is_ok());
+        assert!(matches!(
+            repack_within(7),
+            Err(SurgeError::InvalidProject(message)) if message.contains("more than 7 bytes")
+        ));
+
+        // A highly compressible zip entry is cut off at the limit, not decompressed in full
+        let bomb = dir.path().join("bomb.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&bomb).unwrap());
+        zip.start_file("zeros.bin", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        std::io::Write::write_all(&mut zip, &vec![0u8; 1 << 20]).unwrap();
+        zip.finish().unwrap();
+        let result = repack(&bomb, &PublishOptions::default(), false, true, 1024);
+        assert!(matches!(result, Err(SurgeError::InvalidProject(_))));
+        let mut budget = UnpackBudget {
+            archive: &bomb,
+            remaining: 1024,
+            max: 1024,
+        };
+        // Even when the declared size understates it
+        let result = budget.read(&vec![0u8; 1 << 20][..], 0);
+        assert!(matches!(result, Err(SurgeError::InvalidProject(_))));
+    }
+
+    #[test]
+    fn test_repack_rejects_unsafe_input() {
+        assert_eq!(
+            enclosed_path(Path::new("./a/b.html")),
+            Some(PathBuf::from("a/b.html"))
+        );
+        assert_eq!(enclosed_path(Path::new("../etc/passwd")), None);
+        assert_eq!(enclosed_path(Path::new("/etc/passwd")), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("site.bin");
+        std::fs::write(&archive, "not an archive").unwrap();
+        let result = repack(
+            &archive,
+            &PublishOptions::default(),
+            false,
+            true,
+            MAX_ARCHIVE_UNPACKED_BYTES,
+        );
+        assert!(matches!(result, Err(SurgeError::InvalidProject(_))));
+    }
+}
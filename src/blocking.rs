@@ -0,0 +1,170 @@
+/*
+  src/blocking.rs
+*/
+//! # Blocking Client
+//!
+//! A synchronous wrapper around the async [`crate::SurgeSdk`], for scripts and applications that
+//! do not run an async runtime of their own.
+//!
+//! Each call is driven to completion with `block_on`, either on a current-thread runtime owned
+//! by the client ([`SurgeSdk::new`]) or on an existing runtime ([`SurgeSdk::with_handle`]).
+//! Methods without a blocking wrapper can be driven with [`SurgeSdk::block_on`] on the async
+//! client returned by [`SurgeSdk::inner`].
+//!
+//! # Example
+//! ```rust,no_run
+//! use surge_sdk::{Auth, Config, SURGE_API, blocking::SurgeSdk};
+//!
+//! # fn example() -> Result<(), surge_sdk::SurgeError> {
+//! let sdk = SurgeSdk::new(Config::new(SURGE_API, "0.1.0")?)?;
+//! let auth = Auth::Token("your-api-token".to_string());
+//! let account = sdk.account(&auth)?;
+//! let certs = sdk.block_on(sdk.inner().certs("example.surge.sh", &auth))?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{future::Future, path::Path};
+
+use tokio::runtime::{Builder, Handle, Runtime};
+
+use crate::{
+    config::Config,
+    error::SurgeError,
+    responses::{AccountResponse, CertsResponse, ListResult, MetadataResponse, TeardownResponse},
+    stream::{PublishOptions, summarize},
+    types::{Auth, DeploySummary},
+};
+
+/// The runtime a blocking client drives its calls on.
+#[derive(Debug)]
+enum Executor {
+    /// A current-thread runtime owned by the client.
+    Owned(Runtime),
+    /// A handle to a runtime owned by the application.
+    Handle(Handle),
+}
+
+/// A blocking client for the Surge API.
+pub struct SurgeSdk {
+    inner: crate::SurgeSdk,
+    executor: Executor,
+}
+
+impl SurgeSdk {
+    /// Creates a blocking client that drives its calls on a new current-thread runtime.
+    ///
+    /// # Arguments
+    /// * `config` - The SDK configuration.
+    ///
+    /// # Returns
+    /// A `Result` containing the client, or a `SurgeError` if the HTTP client or the runtime
+    /// cannot be created.
+    pub fn new(config: Config) -> Result<Self, SurgeError> {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| SurgeError::Io(format!("Failed to create runtime: {}", e)))?;
+        Ok(Self {
+            inner: crate::SurgeSdk::new(config)?,
+            executor: Executor::Owned(runtime),
+        })
+    }
+
+    /// Creates a blocking client that drives its calls on an existing runtime.
+    ///
+    /// Calls block the current thread with `Handle::block_on`. Do not call them from an async
+    /// context, such as a task running on that runtime's worker threads: `block_on` panics
+    /// there, and blocking a worker of a current-thread runtime would deadlock it. Call them from
+    /// plain threads, or from `tokio::task::spawn_blocking`.
+    ///
+    /// # Arguments
+    /// * `config` - The SDK configuration.
+    /// * `handle` - A handle to the runtime the calls are driven on.
+    ///
+    /// # Returns
+    /// A `Result` containing the client, or a `SurgeError` if the HTTP client cannot be created.
+    pub fn with_handle(config: Config, handle: Handle) -> Result<Self, SurgeError> {
+        Ok(Self {
+            inner: crate::SurgeSdk::new(config)?,
+            executor: Executor::Handle(handle),
+        })
+    }
+
+    /// Returns the async client wrapped by this client.
+    pub fn inner(&self) -> &crate::SurgeSdk {
+        &self.inner
+    }
+
+    /// Runs a future to completion on the client's runtime.
+    ///
+    /// # Arguments
+    /// * `future` - The future to run, typically a call on [`inner`](Self::inner).
+    ///
+    /// # Returns
+    /// The output of the future.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        match &self.executor {
+            Executor::Owned(runtime) => runtime.block_on(future),
+            Executor::Handle(handle) => handle.block_on(future),
+        }
+    }
+
+    /// Fetches account information. See [`crate::SurgeSdk::account`].
+    pub fn account(&self, auth: &Auth) -> Result<AccountResponse, SurgeError> {
+        self.block_on(self.inner.account(auth))
+    }
+
+    /// Lists domains, optionally filtered by a specific domain. See [`crate::SurgeSdk::list`].
+    pub fn list(&self, domain: Option<&str>, auth: &Auth) -> Result<ListResult, SurgeError> {
+        self.block_on(self.inner.list(domain, auth))
+    }
+
+    /// Removes a domain. See [`crate::SurgeSdk::teardown`].
+    pub fn teardown(&self, domain: &str, auth: &Auth) -> Result<TeardownResponse, SurgeError> {
+        self.block_on(self.inner.teardown(domain, auth))
+    }
+
+    /// Fetches metadata for a domain or specific revision. See [`crate::SurgeSdk::metadata`].
+    pub fn metadata(
+        &self,
+        domain: &str,
+        revision: Option<&str>,
+        auth: &Auth,
+    ) -> Result<MetadataResponse, SurgeError> {
+        self.block_on(self.inner.metadata(domain, revision, auth))
+    }
+
+    /// Fetches the certificates of a domain. See [`crate::SurgeSdk::certs`].
+    pub fn certs(&self, domain: &str, auth: &Auth) -> Result<CertsResponse, SurgeError> {
+        self.block_on(self.inner.certs(domain, auth))
+    }
+
+    /// Publishes a project directory and waits for the upload to finish.
+    ///
+    /// See [`crate::SurgeSdk::publish_with`]; the event stream is drained into a summary.
+    ///
+    /// # Arguments
+    /// * `project_path` - Path to the project directory.
+    /// * `domain` - Target domain for publishing.
+    /// * `auth` - Authentication credentials.
+    /// * `options` - Options controlling packaging and request headers.
+    ///
+    /// # Returns
+    /// A `Result` containing the `DeploySummary` or a `SurgeError`.
+    pub fn publish_with(
+        &self,
+        project_path: &Path,
+        domain: &str,
+        auth: &Auth,
+        options: &PublishOptions,
+    ) -> Result<DeploySummary, SurgeError> {
+        self.block_on(async {
+            let events = self
+                .inner
+                .publish_with(project_path, domain, auth, options)
+                .await?;
+            summarize(domain, events).await
+        })
+    }
+}
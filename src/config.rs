@@ -12,8 +12,15 @@
 //! This module ensures that configuration is easy to construct, validate, and extend with
 //! builder-style methods for convenience.
 
+#[cfg(feature = "metrics")]
+use std::sync::Arc;
+
 use url::Url;
 
+use crate::error::SurgeError;
+#[cfg(feature = "metrics")]
+use crate::metrics::{MetricsRecorder, NoopRecorder};
+
 /// Configuration settings for the SDK.
 ///
 /// Holds the API endpoint, version, timeout duration, and security settings.
@@ -22,8 +29,19 @@ use url::Url;
 /// - `endpoint`: Base URL for the Surge API (must be a valid `Url`)
 /// - `version`: SDK or client version string
 /// - `insecure`: Whether to allow insecure HTTP connections (default is `false`)
+/// - `accept_invalid_hostnames`: Whether to tolerate TLS hostname mismatches (default is `false`)
 /// - `timeout_secs`: Timeout in seconds for network operations (default is `30`)
-#[derive(Debug)]
+/// - `minimal_publish_headers`: Whether to omit non-essential publish headers (default is `false`)
+/// - `max_retries`: How many times read-only requests are retried (default is `0`)
+/// - `lossy_event_decoding`: Whether invalid UTF-8 in event streams is replaced (default is `false`)
+/// - `preserve_permissions`: Whether published files keep their unix mode (default is `false`)
+/// - `compression_enabled`: Whether the publish tarball is gzip-compressed (default is `true`)
+/// - `upload_mode`: How the publish tarball is sent (default is `UploadMode::RawGzip`)
+/// - `allow_heavy_dirs`: Whether `node_modules`, `.git`, and `target` directories are published
+///   (default is `false`)
+/// - `metrics_recorder`: Receives per-request metrics, with the `metrics` feature (default is a
+///   `NoopRecorder`)
+#[derive(Debug, Clone)]
 pub struct Config {
     /// The base API endpoint URL.
     pub endpoint: Url,
@@ -34,8 +52,61 @@ pub struct Config {
     /// Whether to allow insecure HTTP connections (e.g., without TLS).
     pub insecure: bool,
 
+    /// Whether to accept TLS certificates whose hostname does not match the endpoint.
+    ///
+    /// Unlike `insecure`, the certificate chain is still validated.
+    pub accept_invalid_hostnames: bool,
+
     /// Timeout duration for API calls, in seconds.
     pub timeout_secs: u64,
+
+    /// Whether publish requests send only the essential headers.
+    ///
+    /// When `true`, the `version`, `timestamp`, `stage`, `ssl`, and `argv` headers are omitted.
+    pub minimal_publish_headers: bool,
+
+    /// How many times read-only (`GET`) requests are retried after a `429`, a `502`/`503`/`504`,
+    /// or a connection failure.
+    pub max_retries: u32,
+
+    /// Whether NDJSON event streams replace invalid UTF-8 with `U+FFFD` instead of failing.
+    pub lossy_event_decoding: bool,
+
+    /// Whether published files keep their unix mode instead of being archived as `0o644`.
+    ///
+    /// Only the permission bits are kept, minus group/other write; setuid, setgid, and sticky
+    /// bits are dropped. Has no effect on platforms without unix modes.
+    pub preserve_permissions: bool,
+
+    /// Whether the publish tarball is gzip-compressed.
+    ///
+    /// When `false`, a raw tar is uploaded with `Content-Type: application/x-tar`.
+    pub compression_enabled: bool,
+
+    /// How the publish tarball is sent in the request body.
+    pub upload_mode: UploadMode,
+
+    /// Whether files inside [`HEAVY_DIRS`](crate::plan::HEAVY_DIRS), such as `node_modules`,
+    /// are published.
+    ///
+    /// When `true`, [`PublishOptions::allow_heavy_dirs`](crate::PublishOptions::allow_heavy_dirs)
+    /// is enabled for every publish and status check of the client.
+    pub allow_heavy_dirs: bool,
+
+    /// Receives the method, path, status, and latency of every request.
+    #[cfg(feature = "metrics")]
+    pub metrics_recorder: Arc<dyn MetricsRecorder>,
+}
+
+/// How a publish sends the project tarball.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UploadMode {
+    /// The tarball is the raw request body, as the Surge API expects.
+    #[default]
+    RawGzip,
+    /// The tarball is the `file` part of a `multipart/form-data` body, for Surge-compatible
+    /// servers that expect a form upload.
+    Multipart,
 }
 
 impl Config {
@@ -65,6 +136,16 @@ impl Config {
             version: version.into(),
             timeout_secs: 30,
             insecure: false,
+            accept_invalid_hostnames: false,
+            minimal_publish_headers: false,
+            max_retries: 0,
+            lossy_event_decoding: false,
+            preserve_permissions: false,
+            compression_enabled: true,
+            upload_mode: UploadMode::RawGzip,
+            allow_heavy_dirs: false,
+            #[cfg(feature = "metrics")]
+            metrics_recorder: Arc::new(NoopRecorder),
         })
     }
 
@@ -93,6 +174,34 @@ impl Config {
         self
     }
 
+    /// Sets the `accept_invalid_hostnames` flag to tolerate TLS hostname mismatches.
+    ///
+    /// **Warning**: This is still dangerous, as a valid certificate issued for any other host
+    /// will be accepted. It is, however, narrower than `insecure`: the certificate chain must
+    /// still be signed by a trusted CA. Useful for staging servers that present a production
+    /// certificate. Independent of `insecure`.
+    ///
+    /// # Arguments
+    /// * `val` - Whether to accept certificates with a mismatched hostname.
+    ///
+    /// # Returns
+    /// The modified `Config` instance for method chaining.
+    ///
+    /// # Example
+    /// ```
+    /// use surge_sdk::{Config, SURGE_API};
+    ///
+    /// let config = Config::new(SURGE_API, "0.1.0")
+    ///     .unwrap()
+    ///     .with_accept_invalid_hostnames(true);
+    /// assert!(config.accept_invalid_hostnames);
+    /// assert!(!config.insecure);
+    /// ```
+    pub fn with_accept_invalid_hostnames(mut self, val: bool) -> Self {
+        self.accept_invalid_hostnames = val;
+        self
+    }
+
     /// Sets the timeout duration in seconds.
     ///
     /// # Arguments
@@ -114,13 +223,272 @@ impl Config {
         self.timeout_secs = secs;
         self
     }
+
+    /// Sets the `minimal_publish_headers` flag.
+    ///
+    /// The official Surge CLI sends several custom headers (`version`, `timestamp`, `stage`,
+    /// `ssl`, `argv`) with every publish. Some Surge-compatible backends, and proxies that
+    /// strictly allow-list request headers, reject these. When enabled, publish requests only
+    /// carry `Content-Type`, `Accept`, authentication, `file-count`, and `project-size`.
+    /// surge.sh itself accepts either form.
+    ///
+    /// # Arguments
+    /// * `val` - Whether to send only the essential publish headers.
+    ///
+    /// # Returns
+    /// The modified `Config` instance for method chaining.
+    ///
+    /// # Example
+    /// ```
+    /// use surge_sdk::{Config, SURGE_API};
+    ///
+    /// let config = Config::new(SURGE_API, "0.1.0")
+    ///     .unwrap()
+    ///     .with_minimal_publish_headers(true);
+    /// assert!(config.minimal_publish_headers);
+    /// ```
+    pub fn with_minimal_publish_headers(mut self, val: bool) -> Self {
+        self.minimal_publish_headers = val;
+        self
+    }
+
+    /// Sets how many times read-only requests are retried.
+    ///
+    /// Retries use exponential backoff starting at 500ms. When the server sends a
+    /// `Retry-After` header, the longer of that value and the backoff is used; a `Retry-After`
+    /// of more than two minutes is not waited for, and the response is returned instead.
+    ///
+    /// # Arguments
+    /// * `retries` - The maximum number of retries; `0` disables retrying.
+    ///
+    /// # Returns
+    /// The modified `Config` instance for method chaining.
+    ///
+    /// # Example
+    /// ```
+    /// use surge_sdk::{Config, SURGE_API};
+    ///
+    /// let config = Config::new(SURGE_API, "0.1.0")
+    ///     .unwrap()
+    ///     .with_max_retries(3);
+    /// assert_eq!(config.max_retries, 3);
+    /// ```
+    pub fn with_max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Sets the `lossy_event_decoding` flag.
+    ///
+    /// Some servers emit non-UTF-8 (e.g. latin-1) bytes in error messages. With lossy decoding
+    /// enabled those bytes are replaced with `U+FFFD` and the event stream continues, instead of
+    /// yielding an error.
+    ///
+    /// # Arguments
+    /// * `val` - Whether to decode event streams lossily.
+    ///
+    /// # Returns
+    /// The modified `Config` instance for method chaining.
+    ///
+    /// # Example
+    /// ```
+    /// use surge_sdk::{Config, SURGE_API};
+    ///
+    /// let config = Config::new(SURGE_API, "0.1.0")
+    ///     .unwrap()
+    ///     .with_lossy_event_decoding(true);
+    /// assert!(config.lossy_event_decoding);
+    /// ```
+    pub fn with_lossy_event_decoding(mut self, val: bool) -> Self {
+        self.lossy_event_decoding = val;
+        self
+    }
+
+    /// Sets the `preserve_permissions` flag.
+    ///
+    /// By default every file is archived with mode `0o644`, so executable scripts lose their
+    /// executable bit. With this enabled, each file's own mode is used instead (masked to
+    /// `0o755`, and always readable).
+    ///
+    /// # Arguments
+    /// * `val` - Whether to preserve file permissions.
+    ///
+    /// # Returns
+    /// The modified `Config` instance for method chaining.
+    ///
+    /// # Example
+    /// ```
+    /// use surge_sdk::{Config, SURGE_API};
+    ///
+    /// let config = Config::new(SURGE_API, "0.1.0")
+    ///     .unwrap()
+    ///     .with_preserve_permissions(true);
+    /// assert!(config.preserve_permissions);
+    /// ```
+    pub fn with_preserve_permissions(mut self, val: bool) -> Self {
+        self.preserve_permissions = val;
+        self
+    }
+
+    /// Sets the `compression_enabled` flag.
+    ///
+    /// Some servers and test setups expect an uncompressed tar. With this disabled, publishes
+    /// upload a raw tar with `Content-Type: application/x-tar`; the `file-count` and
+    /// `project-size` headers are unaffected.
+    ///
+    /// # Arguments
+    /// * `val` - Whether to gzip the publish tarball.
+    ///
+    /// # Returns
+    /// The modified `Config` instance for method chaining.
+    ///
+    /// # Example
+    /// ```
+    /// use surge_sdk::{Config, SURGE_API};
+    ///
+    /// let config = Config::new(SURGE_API, "0.1.0")
+    ///     .unwrap()
+    ///     .with_compression_enabled(false);
+    /// assert!(!config.compression_enabled);
+    /// ```
+    pub fn with_compression_enabled(mut self, val: bool) -> Self {
+        self.compression_enabled = val;
+        self
+    }
+
+    /// Sets how publishes send the project tarball.
+    ///
+    /// With `UploadMode::Multipart`, the tarball is sent as the `file` part of a
+    /// `multipart/form-data` body, named `project.tar.gz` (or `project.tar` without
+    /// compression); the `file-count`, `project-size`, and other publish headers are unchanged.
+    ///
+    /// # Arguments
+    /// * `mode` - The upload mode.
+    ///
+    /// # Returns
+    /// The modified `Config` instance for method chaining.
+    ///
+    /// # Example
+    /// ```
+    /// use surge_sdk::{Config, SURGE_API, UploadMode};
+    ///
+    /// let config = Config::new(SURGE_API, "0.1.0")
+    ///     .unwrap()
+    ///     .with_upload_mode(UploadMode::Multipart);
+    /// assert_eq!(config.upload_mode, UploadMode::Multipart);
+    /// ```
+    pub fn with_upload_mode(mut self, mode: UploadMode) -> Self {
+        self.upload_mode = mode;
+        self
+    }
+
+    /// Sets the `allow_heavy_dirs` flag.
+    ///
+    /// Pointing a publish at a project root rather than its build output would upload
+    /// dependencies, repository history, or build artifacts. By default, files inside the
+    /// [`HEAVY_DIRS`](crate::plan::HEAVY_DIRS) are left out of directory publishes, and each
+    /// excluded directory is logged with its size; with this enabled they are published, as
+    /// with `PublishOptions::with_allow_heavy_dirs(true)`.
+    ///
+    /// # Arguments
+    /// * `val` - Whether to publish heavy directories.
+    ///
+    /// # Returns
+    /// The modified `Config` instance for method chaining.
+    ///
+    /// # Example
+    /// ```
+    /// use surge_sdk::{Config, SURGE_API};
+    ///
+    /// let config = Config::new(SURGE_API, "0.1.0")
+    ///     .unwrap()
+    ///     .with_allow_heavy_dirs(true);
+    /// assert!(config.allow_heavy_dirs);
+    /// ```
+    pub fn with_allow_heavy_dirs(mut self, val: bool) -> Self {
+        self.allow_heavy_dirs = val;
+        self
+    }
+
+    /// Sets the recorder that receives per-request metrics.
+    ///
+    /// Only available with the `metrics` feature; see the [`metrics`](crate::metrics) module.
+    ///
+    /// # Arguments
+    /// * `recorder` - The recorder, e.g. a bridge to a Prometheus registry.
+    ///
+    /// # Returns
+    /// The modified `Config` instance for method chaining.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics_recorder(mut self, recorder: Arc<dyn MetricsRecorder>) -> Self {
+        self.metrics_recorder = recorder;
+        self
+    }
+
+    /// Checks the configuration for values that cannot work.
+    ///
+    /// Called by `SurgeSdk::new`, so an invalid configuration fails when the client is built
+    /// rather than on the first request.
+    ///
+    /// # Returns
+    /// `Ok(())`, or `SurgeError::Config` describing the first problem found: a zero timeout,
+    /// an empty version, or an endpoint that is not an `http`/`https` URL with a host.
+    ///
+    /// # Example
+    /// ```
+    /// use surge_sdk::{Config, SURGE_API};
+    ///
+    /// assert!(Config::new(SURGE_API, "0.1.0").unwrap().validate().is_ok());
+    /// let config = Config::new(SURGE_API, "0.1.0").unwrap().with_timeout(0);
+    /// assert!(config.validate().is_err());
+    /// ```
+    pub fn validate(&self) -> Result<(), SurgeError> {
+        if self.timeout_secs == 0 {
+            return Err(SurgeError::Config(
+                "timeout_secs must be greater than zero".to_string(),
+            ));
+        }
+        if self.version.trim().is_empty() {
+            return Err(SurgeError::Config("version must not be empty".to_string()));
+        }
+        if !matches!(self.endpoint.scheme(), "http" | "https") {
+            return Err(SurgeError::Config(format!(
+                "endpoint scheme must be http or https, got {}",
+                self.endpoint.scheme()
+            )));
+        }
+        if self.endpoint.host_str().is_none_or(str::is_empty) {
+            return Err(SurgeError::Config(format!(
+                "endpoint {} has no host",
+                self.endpoint
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&str> for Config {
+    type Error = url::ParseError;
+
+    /// Creates a `Config` for an endpoint, with the crate version as `version`.
+    ///
+    /// # Example
+    /// ```
+    /// use surge_sdk::Config;
+    ///
+    /// let config = Config::try_from("https://surge.surge.sh").unwrap();
+    /// assert_eq!(config.version, env!("CARGO_PKG_VERSION"));
+    /// ```
+    fn try_from(endpoint: &str) -> Result<Self, Self::Error> {
+        Config::new(endpoint, env!("CARGO_PKG_VERSION"))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use url::Url;
 
-    use crate::SURGE_API;
+    use crate::{SURGE_API, SurgeError};
 
     use super::Config;
 
@@ -135,6 +503,28 @@ mod test {
         assert_eq!(config.version, "0.1.0");
         assert_eq!(config.timeout_secs, 30);
         assert!(!config.insecure);
+        assert!(!config.accept_invalid_hostnames);
+        assert!(!config.minimal_publish_headers);
+        assert_eq!(config.max_retries, 0);
+        assert!(!config.lossy_event_decoding);
+        assert!(!config.preserve_permissions);
+        assert!(config.compression_enabled);
+        assert_eq!(config.upload_mode, super::UploadMode::RawGzip);
+        assert!(!config.allow_heavy_dirs);
+    }
+
+    /// Tests creating a `Config` from an endpoint string.
+    #[test]
+    fn test_config_try_from() {
+        let config = Config::try_from("https://surge.surge.sh").unwrap();
+        assert_eq!(config.endpoint, Url::parse(SURGE_API).unwrap());
+        assert_eq!(config.version, env!("CARGO_PKG_VERSION"));
+
+        let result = Config::try_from("not a url");
+        assert!(matches!(
+            result.unwrap_err(),
+            url::ParseError::RelativeUrlWithoutBase
+        ));
     }
 
     /// Tests that an invalid URL results in a parsing error.
@@ -147,4 +537,38 @@ mod test {
             url::ParseError::RelativeUrlWithoutBase
         ));
     }
+
+    /// Tests that a zero timeout fails validation.
+    #[test]
+    fn test_validate_zero_timeout() {
+        let config = Config::new(SURGE_API, "0.1.0").unwrap().with_timeout(0);
+        assert!(
+            matches!(config.validate(), Err(SurgeError::Config(message)) if message.contains("timeout"))
+        );
+    }
+
+    /// Tests that an empty version fails validation.
+    #[test]
+    fn test_validate_empty_version() {
+        let config = Config::new(SURGE_API, " ").unwrap();
+        assert!(
+            matches!(config.validate(), Err(SurgeError::Config(message)) if message.contains("version"))
+        );
+    }
+
+    /// Tests that only http and https endpoints pass validation.
+    #[test]
+    fn test_validate_endpoint_scheme() {
+        assert!(Config::new(SURGE_API, "0.1.0").unwrap().validate().is_ok());
+        assert!(
+            Config::new("http://localhost:8080", "0.1.0")
+                .unwrap()
+                .validate()
+                .is_ok()
+        );
+        let config = Config::new("ftp://surge.sh", "0.1.0").unwrap();
+        assert!(matches!(config.validate(), Err(SurgeError::Config(_))));
+        let config = Config::new("file:///tmp/surge", "0.1.0").unwrap();
+        assert!(matches!(config.validate(), Err(SurgeError::Config(_))));
+    }
 }
@@ -20,9 +20,10 @@ use thiserror::Error;
 /// - URL parsing issues
 /// - JSON (de)serialization issues
 /// - File system or I/O errors
+/// - Files that cannot be read while building the upload tarball
 /// - Ignore rules and directory walking issues
 /// - Other unexpected or miscellaneous errors
-#[derive(Error, Debug, Deserialize, Serialize)]
+#[derive(Error, Debug, Clone, Deserialize, Serialize)]
 pub enum SurgeError {
     /// HTTP-related errors from the `reqwest` crate.
     #[error("HTTP error: {0}")]
@@ -48,6 +49,16 @@ pub enum SurgeError {
     #[error("IO error: {0}")]
     Io(String),
 
+    /// A project file could not be read while building the upload tarball, e.g. because a
+    /// network-mounted project directory disconnected.
+    #[error("Failed to read {path} during tarball creation: {message}")]
+    Archive {
+        /// The path of the file, relative to the project directory.
+        path: String,
+        /// The underlying I/O error.
+        message: String,
+    },
+
     /// Directory traversal or ignore rules errors
     #[error("Ignore error: {0}")]
     Ignore(String),
@@ -86,6 +97,80 @@ impl SurgeError {
             details,
         }
     }
+
+    /// Returns the process exit code a CLI should use for this error.
+    ///
+    /// Codes follow the BSD `sysexits.h` conventions where one fits:
+    ///
+    /// | Variant                                       | Code | `sysexits.h`     |
+    /// |-----------------------------------------------|------|------------------|
+    /// | `Api`                                         | 1    |                  |
+    /// | `Auth`, `Api` with status `401`/`403`         | 77   | `EX_NOPERM`      |
+    /// | `InvalidProject`, `Ignore`                    | 65   | `EX_DATAERR`     |
+    /// | `Http`, `Network`                             | 69   | `EX_UNAVAILABLE` |
+    /// | `Unknown`                                     | 70   | `EX_SOFTWARE`    |
+    /// | `Io`, `Archive`                               | 74   | `EX_IOERR`       |
+    /// | `Tls`, `Json`, `Event`                        | 76   | `EX_PROTOCOL`    |
+    /// | `Config`                                      | 78   | `EX_CONFIG`      |
+    ///
+    /// The mapping is stable: a code only changes with a breaking release.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            SurgeError::Api {
+                status: Some(401 | 403),
+                ..
+            }
+            | SurgeError::Auth(_) => 77,
+            SurgeError::Api { .. } => 1,
+            SurgeError::InvalidProject(_) | SurgeError::Ignore(_) => 65,
+            SurgeError::Http(_) | SurgeError::Network(_) => 69,
+            SurgeError::Unknown(_) => 70,
+            SurgeError::Io(_) | SurgeError::Archive { .. } => 74,
+            SurgeError::Tls(_) | SurgeError::Json(_) | SurgeError::Event(_) => 76,
+            SurgeError::Config(_) => 78,
+        }
+    }
+
+    /// Returns a remediation hint a CLI can show alongside the error, if one applies.
+    ///
+    /// The hint does not repeat the error message; `Display` is unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use surge_sdk::SurgeError;
+    ///
+    /// let error = SurgeError::Auth("token expired".to_string());
+    /// assert_eq!(error.help_hint(), Some("run `surge login` to refresh your token"));
+    /// ```
+    pub fn help_hint(&self) -> Option<&'static str> {
+        match self {
+            SurgeError::Auth(_)
+            | SurgeError::Api {
+                status: Some(401), ..
+            } => Some("run `surge login` to refresh your token"),
+            SurgeError::Api {
+                status: Some(403), ..
+            } => Some("check that your account has access to this domain"),
+            SurgeError::Api {
+                status: Some(429), ..
+            } => Some("you are being rate limited; wait a moment and try again"),
+            SurgeError::Config(message) if message.contains("URL") => {
+                Some("check your endpoint URL")
+            }
+            SurgeError::Config(_) => Some("check the SDK configuration and publish options"),
+            SurgeError::Network(_) | SurgeError::Http(_) => {
+                Some("check your network connection and the endpoint URL")
+            }
+            SurgeError::Tls(_) => Some("check the server certificate or the `insecure` setting"),
+            SurgeError::InvalidProject(_) | SurgeError::Ignore(_) => {
+                Some("check the project directory and its `.surgeignore` rules")
+            }
+            SurgeError::Archive { .. } => {
+                Some("check that the project files are readable and not changing during the upload")
+            }
+            _ => None,
+        }
+    }
 }
 
 // Implement From traits for common error types
@@ -188,6 +273,63 @@ mod tests {
         }
     }
 
+    /// Tests the remediation hints of authentication and configuration errors.
+    #[test]
+    fn test_help_hint() {
+        let auth = SurgeError::Auth("token expired".to_string());
+        assert_eq!(
+            auth.help_hint(),
+            Some("run `surge login` to refresh your token")
+        );
+        let unauthorized = SurgeError::api(Some(401), "unauthorized", Value::Null);
+        assert_eq!(unauthorized.help_hint(), auth.help_hint());
+
+        let url: SurgeError = url::Url::parse("not a url").unwrap_err().into();
+        assert!(matches!(url, SurgeError::Config(_)));
+        assert_eq!(url.help_hint(), Some("check your endpoint URL"));
+
+        let config = SurgeError::Config("timeout_secs must be greater than zero".to_string());
+        assert_eq!(
+            config.help_hint(),
+            Some("check the SDK configuration and publish options")
+        );
+
+        assert_eq!(SurgeError::Unknown("?".to_string()).help_hint(), None);
+    }
+
+    /// Tests that every variant keeps its documented exit code.
+    #[test]
+    fn test_exit_code_mapping() {
+        let message = || "message".to_string();
+        let cases = [
+            (SurgeError::api(Some(500), "failed", Value::Null), 1),
+            (SurgeError::api(None, "failed", Value::Null), 1),
+            (SurgeError::api(Some(401), "unauthorized", Value::Null), 77),
+            (SurgeError::api(Some(403), "forbidden", Value::Null), 77),
+            (SurgeError::Auth(message()), 77),
+            (SurgeError::InvalidProject(message()), 65),
+            (SurgeError::Ignore(message()), 65),
+            (SurgeError::Http(message()), 69),
+            (SurgeError::Network(message()), 69),
+            (SurgeError::Unknown(message()), 70),
+            (SurgeError::Io(message()), 74),
+            (
+                SurgeError::Archive {
+                    path: "index.html".to_string(),
+                    message: message(),
+                },
+                74,
+            ),
+            (SurgeError::Tls(message()), 76),
+            (SurgeError::Json(message()), 76),
+            (SurgeError::Event(message()), 76),
+            (SurgeError::Config(message()), 78),
+        ];
+        for (error, code) in cases {
+            assert_eq!(error.exit_code(), code, "{:?}", error);
+        }
+    }
+
     /// Tests deserialization of `ApiErrorResponse`:
 
     #[test]
@@ -29,21 +29,34 @@
 //! }
 //! ```
 
+mod archive;
+pub mod blocking;
 pub mod config;
 pub mod error;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod plan;
 pub mod responses;
 pub mod sdk;
 pub mod stream;
 pub mod types;
+pub mod ui;
 pub mod utils;
 
-pub use config::Config;
+pub use config::{Config, UploadMode};
 pub use error::SurgeError;
+pub use plan::{PlanWarning, PublishPlan};
 pub use responses::*;
 pub use sdk::SurgeSdk;
-pub use stream::{calculate_metadata, publish};
+pub use stream::{
+    ArchiveRoot, DuplicatePolicy, PublishOptions, SymlinkPolicy, TarFormat, UnsetEnvPolicy,
+    calculate_metadata, publish,
+};
 // pub use stream::publish_wip;
-pub use types::{Auth, Event};
+pub use types::{
+    Auth, CacheDiagnostics, DeployStatus, DeploySummary, DnsRecordInput, EVENT_TYPES, Event,
+    LiveHeaders, PollBackoff, RetryBudget, SyncState, ZoneRecordInput,
+};
 pub use utils::{generate_domain, json_to_argv};
 
 /// The default Surge.sh API endpoint
@@ -0,0 +1,67 @@
+/*
+  src/metrics.rs
+*/
+//! # Request Metrics
+//!
+//! Hooks for observing the HTTP requests the SDK sends, enabled by the `metrics` feature.
+//!
+//! Every request, including retries and publish uploads, is reported to the
+//! [`MetricsRecorder`] set with [`Config::with_metrics_recorder`](crate::Config::with_metrics_recorder)
+//! once its response headers arrive or it fails. Implement the trait to bridge the SDK to
+//! Prometheus, statsd, or any other metrics system; the default [`NoopRecorder`] discards
+//! everything.
+//!
+//! # Example
+//! ```rust
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//! use surge_sdk::{Config, SURGE_API, metrics::MetricsRecorder};
+//!
+//! #[derive(Debug)]
+//! struct LogRecorder;
+//!
+//! impl MetricsRecorder for LogRecorder {
+//!     fn record_request(&self, method: &str, path: &str, status: Option<u16>, duration: Duration) {
+//!         println!("{} {} -> {:?} in {:?}", method, path, status, duration);
+//!     }
+//! }
+//!
+//! let config = Config::new(SURGE_API, "0.1.0")
+//!     .unwrap()
+//!     .with_metrics_recorder(Arc::new(LogRecorder));
+//! ```
+
+use std::{fmt::Debug, time::Duration};
+
+/// Receives the method, path, status, and latency of each request sent by the SDK.
+///
+/// Implementations are called from the task that sent the request, so they should be cheap
+/// and must not block, e.g. by incrementing counters or observing histograms.
+pub trait MetricsRecorder: Debug + Send + Sync {
+    /// Records one HTTP request.
+    ///
+    /// # Arguments
+    /// * `method` - The HTTP method, such as `GET` or `PUT`.
+    /// * `path` - The URL path, such as `/token` or `/example.surge.sh/certs`. Paths contain
+    ///   domain names, so map them to a fixed set of labels before using them as one.
+    /// * `status` - The response status, or `None` if the request failed without a response,
+    ///   e.g. on a connection error or timeout.
+    /// * `duration` - The time from sending the request until its response headers arrived or
+    ///   it failed.
+    fn record_request(&self, method: &str, path: &str, status: Option<u16>, duration: Duration);
+}
+
+/// A recorder that discards every request; the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopRecorder;
+
+impl MetricsRecorder for NoopRecorder {
+    fn record_request(
+        &self,
+        _method: &str,
+        _path: &str,
+        _status: Option<u16>,
+        _duration: Duration,
+    ) {
+    }
+}
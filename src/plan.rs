@@ -0,0 +1,600 @@
+/*
+  src/plan.rs
+*/
+//! Pre-publish planning for the Surge SDK.
+//!
+//! A `PublishPlan` is the exact list of files a publish will upload, computed by walking the
+//! project directory with the same `.surgeignore` and junk rules used for the tarball. Building
+//! the plan before uploading lets the SDK (and callers) check the project for problems, such as
+//! file names that collide on case-insensitive filesystems, without sending anything.
+//!
+//! # Example
+//! ```rust,no_run
+//! use surge_sdk::{PublishOptions, plan::plan};
+//! use std::path::Path;
+//!
+//! let plan = plan(Path::new("./dist"), &PublishOptions::default()).unwrap();
+//! println!("{} files, {} bytes", plan.file_count(), plan.total_size);
+//! for warning in &plan.warnings {
+//!     eprintln!("warning: {}", warning);
+//! }
+//! ```
+
+use crate::{
+    error::SurgeError,
+    stream::{DuplicatePolicy, PublishOptions, SymlinkPolicy, build_custom_gitignore},
+};
+use ignore::{DirEntry, WalkBuilder, WalkState};
+use log::{debug, error, trace, warn};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt, fs,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
+
+/// Directories that are usually not meant to be published: dependencies, repository history,
+/// and build artifacts.
+///
+/// Files inside them are left out of planned directories unless
+/// [`PublishOptions::allow_heavy_dirs`] or `Config::allow_heavy_dirs` is set.
+pub const HEAVY_DIRS: &[&str] = &["node_modules", ".git", "target"];
+
+/// A file included in a publish.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedFile {
+    /// The path relative to the project directory.
+    pub path: PathBuf,
+    /// The file size in bytes.
+    pub size: u64,
+}
+
+/// A potential problem found while planning a publish.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanWarning {
+    /// Paths that differ only in letter case, e.g. `Index.html` and `index.html`.
+    ///
+    /// Only one of them survives on a case-insensitive filesystem, so which one is served
+    /// may be nondeterministic.
+    CaseCollision(Vec<PathBuf>),
+}
+
+impl fmt::Display for PlanWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanWarning::CaseCollision(paths) => {
+                let paths: Vec<_> = paths.iter().map(|p| p.display().to_string()).collect();
+                write!(f, "Paths differ only in case: {}", paths.join(", "))
+            }
+        }
+    }
+}
+
+/// The files a publish will upload, and any warnings about them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PublishPlan {
+    /// The included files, sorted by path.
+    pub files: Vec<PlannedFile>,
+    /// The total size of all included files, in bytes.
+    pub total_size: u64,
+    /// Potential problems found in the file list.
+    pub warnings: Vec<PlanWarning>,
+}
+
+impl PublishPlan {
+    /// Returns the number of included files.
+    pub fn file_count(&self) -> u64 {
+        self.files.len() as u64
+    }
+}
+
+/// Computes the publish plan for a project directory.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory.
+/// * `options` - The publish options that determine which files are included.
+///
+/// # Returns
+/// A `Result` containing the `PublishPlan`, or a `SurgeError` if the directory cannot be read.
+/// When `options.strict` is set, any warning is returned as `SurgeError::InvalidProject`
+/// instead.
+pub fn plan(project_path: &Path, options: &PublishOptions) -> Result<PublishPlan, SurgeError> {
+    debug!("Planning publish for path: {:?}", project_path);
+
+    if !project_path.is_dir() {
+        error!("Project path {:?} is not a directory", project_path);
+        return Err(SurgeError::Io(format!(
+            "Invalid project directory: {}",
+            project_path.display()
+        )));
+    }
+
+    if options.use_gitignore {
+        check_git_repository(project_path, options.strict)?;
+    }
+
+    let mut files = collect_files(project_path, options)?;
+    if !options.allow_heavy_dirs {
+        files = exclude_heavy_dirs(files);
+    }
+    plan_files(files, options)
+}
+
+/// Checks that `project_path` is inside a git repository, so its `.gitignore` rules apply.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory.
+/// * `strict` - Whether a missing repository is an error rather than a warning.
+///
+/// # Returns
+/// `Ok(())` if a `.git` directory or file is found in `project_path` or one of its parents, or
+/// if it is not and `strict` is unset; otherwise `SurgeError::Config`.
+fn check_git_repository(project_path: &Path, strict: bool) -> Result<(), SurgeError> {
+    let path = project_path
+        .canonicalize()
+        .unwrap_or_else(|_| project_path.to_path_buf());
+    if path.ancestors().any(|dir| dir.join(".git").exists()) {
+        return Ok(());
+    }
+
+    if strict {
+        return Err(SurgeError::Config(format!(
+            "use_gitignore requested but no .git found in {} or its parents",
+            project_path.display()
+        )));
+    }
+    warn!(
+        "use_gitignore requested but no .git found in {} or its parents; \
+         .gitignore rules are not applied",
+        project_path.display()
+    );
+    Ok(())
+}
+
+/// Computes the publish plan for a single file.
+///
+/// The file is planned relative to its parent directory; ignore rules do not apply to it.
+///
+/// # Arguments
+/// * `file` - Path to the file.
+/// * `options` - The publish options.
+///
+/// # Returns
+/// A `Result` containing a `PublishPlan` with the one file, or a `SurgeError` if `file` is not
+/// a readable file.
+pub(crate) fn plan_file(file: &Path, options: &PublishOptions) -> Result<PublishPlan, SurgeError> {
+    debug!("Planning single-file publish for path: {:?}", file);
+
+    let (Some(name), true) = (file.file_name(), file.is_file()) else {
+        error!("Path {:?} is not a file", file);
+        return Err(SurgeError::Io(format!("Invalid file: {}", file.display())));
+    };
+    let metadata = fs::metadata(file)
+        .map_err(|e| SurgeError::Io(format!("Failed to get metadata for {:?}: {}", file, e)))?;
+
+    plan_files(
+        vec![PlannedFile {
+            path: PathBuf::from(name),
+            size: metadata.len(),
+        }],
+        options,
+    )
+}
+
+/// Builds a `PublishPlan` from an already filtered list of files.
+///
+/// # Arguments
+/// * `files` - The files to publish, in any order.
+/// * `options` - The publish options; `strict` turns warnings into errors.
+///
+/// # Returns
+/// A `Result` containing the `PublishPlan`, or `SurgeError::InvalidProject` for warnings in
+/// strict mode.
+pub(crate) fn plan_files(
+    mut files: Vec<PlannedFile>,
+    options: &PublishOptions,
+) -> Result<PublishPlan, SurgeError> {
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    let total_size = files.iter().map(|file| file.size).sum();
+    let warnings = case_collisions(&files);
+
+    if options.strict && !warnings.is_empty() {
+        let messages: Vec<_> = warnings.iter().map(ToString::to_string).collect();
+        return Err(SurgeError::InvalidProject(messages.join("; ")));
+    }
+
+    debug!(
+        "Planned {} files, {} bytes, {} warnings",
+        files.len(),
+        total_size,
+        warnings.len()
+    );
+    Ok(PublishPlan {
+        files,
+        total_size,
+        warnings,
+    })
+}
+
+/// Removes the files inside [`HEAVY_DIRS`], logging a warning for each excluded directory with
+/// its file count and size.
+///
+/// # Arguments
+/// * `files` - The collected files.
+///
+/// # Returns
+/// The files outside the heavy directories.
+fn exclude_heavy_dirs(mut files: Vec<PlannedFile>) -> Vec<PlannedFile> {
+    let total_size: u64 = files.iter().map(|file| file.size).sum();
+    let mut excluded: BTreeMap<PathBuf, (u64, u64)> = BTreeMap::new();
+    files.retain(|file| {
+        let Some(dir) = heavy_dir(&file.path) else {
+            return true;
+        };
+        let (count, size) = excluded.entry(dir).or_default();
+        *count += 1;
+        *size += file.size;
+        false
+    });
+
+    for (dir, (count, size)) in &excluded {
+        warn!(
+            "Excluding {}: {} files, {} bytes ({:.1}% of the project); \
+             publish it with Config::with_allow_heavy_dirs(true)",
+            dir.display(),
+            count,
+            size,
+            *size as f64 * 100.0 / total_size.max(1) as f64
+        );
+    }
+    files
+}
+
+/// Returns the outermost directory of `path` named in [`HEAVY_DIRS`], if any.
+///
+/// # Arguments
+/// * `path` - A file path, relative to the project directory.
+pub(crate) fn heavy_dir(path: &Path) -> Option<PathBuf> {
+    let parent = path.parent()?;
+    let mut dir = PathBuf::new();
+    for component in parent.components() {
+        dir.push(component);
+        if HEAVY_DIRS
+            .iter()
+            .any(|heavy| component.as_os_str() == *heavy)
+        {
+            return Some(dir);
+        }
+    }
+    None
+}
+
+/// Walks the project directory and returns every file that is not ignored.
+fn collect_files(
+    project_path: &Path,
+    options: &PublishOptions,
+) -> Result<Vec<PlannedFile>, SurgeError> {
+    let mut files = Vec::new();
+    let mut seen = HashSet::new();
+    for entry in walk(project_path, options)? {
+        let path = entry.path();
+
+        if options.symlinks == SymlinkPolicy::Skip && entry.path_is_symlink() {
+            trace!("Skipping symlink: {}", path.display());
+            continue;
+        }
+
+        if !path.is_file() {
+            trace!("Ignored or not a file: {}", path.display());
+            continue;
+        }
+
+        let relative = path.strip_prefix(project_path)?.to_path_buf();
+        if !seen.insert(relative.clone()) {
+            debug!("Skipping repeated path: {}", relative.display());
+            continue;
+        }
+
+        let metadata = fs::metadata(path)
+            .map_err(|e| SurgeError::Io(format!("Failed to get metadata for {:?}: {}", path, e)))?;
+        files.push(PlannedFile {
+            path: relative,
+            size: metadata.len(),
+        });
+    }
+
+    if options.symlinks == SymlinkPolicy::Follow {
+        files = dedupe_targets(project_path, files, options.duplicates)?;
+    }
+    Ok(files)
+}
+
+/// Walks the project directory in parallel and returns every entry that is not ignored.
+///
+/// The walk uses at most [`PublishOptions::max_open_files`] threads, each of which holds one
+/// directory open at a time. Entries are returned in no particular order.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory.
+/// * `options` - The publish options, for the ignore rules, symlink policy, and open file limit.
+///
+/// # Returns
+/// A `Result` containing the entries, or the first error of the walk. Partial results are never
+/// returned, since the server rejects a publish whose counts do not match its tarball.
+pub(crate) fn walk(
+    project_path: &Path,
+    options: &PublishOptions,
+) -> Result<Vec<DirEntry>, SurgeError> {
+    let gitignore = build_custom_gitignore(project_path, options)?;
+
+    // Each walker thread holds at most one directory open at a time
+    let threads = std::thread::available_parallelism()
+        .map_or(1, NonZeroUsize::get)
+        .min(options.max_open_files);
+    let walker = WalkBuilder::new(project_path)
+        .standard_filters(false)
+        .git_ignore(options.use_gitignore)
+        .git_exclude(options.use_gitignore)
+        .parents(options.use_gitignore)
+        .follow_links(options.symlinks == SymlinkPolicy::Follow)
+        .threads(threads)
+        .build_parallel();
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    walker.run(|| {
+        let tx = tx.clone();
+        let gitignore = &gitignore;
+
+        Box::new(move |result| match result {
+            Ok(entry) => {
+                let path = entry.path();
+                let matched = gitignore.matched_path_or_any_parents(path, path.is_dir());
+                if !matched.is_ignore() {
+                    tx.send(Ok(entry)).ok();
+                }
+                WalkState::Continue
+            }
+            Err(err) => {
+                error!("Walker error: {:?}", err);
+                // Forward the error and stop: partial counts would be rejected by the server
+                tx.send(Err(err)).ok();
+                WalkState::Quit
+            }
+        })
+    });
+    drop(tx); // Close the channel so the receiver ends with the walk
+
+    rx.into_iter()
+        .map(|entry| entry.map_err(walk_error))
+        .collect()
+}
+
+/// Converts an error from the directory walk into a `SurgeError`.
+///
+/// A symlink that points back to one of its ancestor directories would make a walk that
+/// follows links descend forever. The walker detects this and reports it as a loop, which is
+/// returned as `SurgeError::InvalidProject` naming the link; other errors become
+/// `SurgeError::Ignore`.
+pub(crate) fn walk_error(err: ignore::Error) -> SurgeError {
+    match symlink_loop(&err) {
+        Some(link) => {
+            error!("Symlink loop detected at {}", link.display());
+            SurgeError::InvalidProject(format!("symlink loop detected at {}", link.display()))
+        }
+        None => SurgeError::Ignore(err.to_string()),
+    }
+}
+
+/// Returns the link that closes a symlink loop, if `err` reports one.
+fn symlink_loop(err: &ignore::Error) -> Option<&Path> {
+    match err {
+        ignore::Error::Loop { child, .. } => Some(child),
+        ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => symlink_loop(err),
+        _ => None,
+    }
+}
+
+/// Resolves files that are reachable through more than one path to a single path.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory.
+/// * `files` - The collected files.
+/// * `policy` - Whether to skip the extra paths or fail.
+///
+/// # Returns
+/// A `Result` containing the files with one path per target, or `SurgeError::InvalidProject`
+/// under `DuplicatePolicy::Error`.
+fn dedupe_targets(
+    project_path: &Path,
+    files: Vec<PlannedFile>,
+    policy: DuplicatePolicy,
+) -> Result<Vec<PlannedFile>, SurgeError> {
+    let root = fs::canonicalize(project_path)?;
+    let mut targets: BTreeMap<PathBuf, Vec<PlannedFile>> = BTreeMap::new();
+    for file in files {
+        let target = fs::canonicalize(project_path.join(&file.path))?;
+        targets.entry(target).or_default().push(file);
+    }
+
+    let mut deduped = Vec::with_capacity(targets.len());
+    for (target, mut paths) in targets {
+        if paths.len() == 1 {
+            deduped.append(&mut paths);
+            continue;
+        }
+
+        let names: Vec<_> = paths.iter().map(|p| p.path.display().to_string()).collect();
+        if policy == DuplicatePolicy::Error {
+            return Err(SurgeError::InvalidProject(format!(
+                "{} is reachable through several paths: {}",
+                target.display(),
+                names.join(", ")
+            )));
+        }
+
+        paths.sort_by(|a, b| a.path.cmp(&b.path));
+        let real_path = target.strip_prefix(&root).ok();
+        let keep = paths
+            .iter()
+            .position(|p| Some(p.path.as_path()) == real_path)
+            .unwrap_or(0);
+        warn!(
+            "{} is reachable through several paths ({}), keeping {}",
+            target.display(),
+            names.join(", "),
+            names[keep]
+        );
+        deduped.push(paths.swap_remove(keep));
+    }
+
+    Ok(deduped)
+}
+
+/// Groups paths that are equal when compared case-insensitively.
+fn case_collisions(files: &[PlannedFile]) -> Vec<PlanWarning> {
+    let mut groups: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for file in files {
+        let key = file.path.to_string_lossy().to_lowercase();
+        groups.entry(key).or_default().push(file.path.clone());
+    }
+
+    groups
+        .into_values()
+        .filter(|paths| paths.len() > 1)
+        .map(PlanWarning::CaseCollision)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_collision() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("Index.html"), "upper").unwrap();
+        fs::write(dir.path().join("index.html"), "lower").unwrap();
+        fs::create_dir(dir.path().join("css")).unwrap();
+        fs::write(dir.path().join("css").join("site.css"), "body {}").unwrap();
+
+        let plan = plan(dir.path(), &PublishOptions::default()).unwrap();
+        assert_eq!(plan.file_count(), 3);
+        assert_eq!(plan.total_size, 17);
+        assert_eq!(
+            plan.warnings,
+            vec![PlanWarning::CaseCollision(vec![
+                PathBuf::from("Index.html"),
+                PathBuf::from("index.html"),
+            ])]
+        );
+
+        let strict = PublishOptions::default().with_strict(true);
+        let result = super::plan(dir.path(), &strict);
+        assert!(matches!(result, Err(SurgeError::InvalidProject(_))));
+    }
+
+    #[test]
+    fn test_exclude_heavy_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("index.html"), "hello").unwrap();
+        fs::write(dir.path().join("target.html"), "page").unwrap();
+        let package = dir.path().join("node_modules").join("left-pad");
+        fs::create_dir_all(&package).unwrap();
+        fs::write(package.join("index.js"), "module.exports = 1").unwrap();
+        fs::create_dir_all(dir.path().join("docs").join("target")).unwrap();
+        fs::write(dir.path().join("docs").join("target").join("a.o"), "obj").unwrap();
+
+        let plan = plan(dir.path(), &PublishOptions::default()).unwrap();
+        let paths: Vec<_> = plan.files.iter().map(|file| file.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("index.html"), PathBuf::from("target.html")]
+        );
+        assert_eq!(plan.total_size, 9);
+
+        let allowed = PublishOptions::default().with_allow_heavy_dirs(true);
+        let plan = super::plan(dir.path(), &allowed).unwrap();
+        assert_eq!(plan.file_count(), 4);
+    }
+
+    #[test]
+    fn test_use_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".gitignore"), "secret.txt\n").unwrap();
+        fs::write(dir.path().join("index.html"), "hello").unwrap();
+        fs::write(dir.path().join("secret.txt"), "hunter2").unwrap();
+        let options = PublishOptions::default().with_use_gitignore(true);
+        let paths = |plan: PublishPlan| -> Vec<PathBuf> {
+            plan.files.into_iter().map(|file| file.path).collect()
+        };
+
+        // Outside a repository the rules are not applied, like git
+        let plan = plan(dir.path(), &options).unwrap();
+        assert!(paths(plan).contains(&PathBuf::from("secret.txt")));
+
+        let strict = options.clone().with_strict(true);
+        let result = super::plan(dir.path(), &strict);
+        assert!(
+            matches!(result, Err(SurgeError::Config(message)) if message.contains("no .git found"))
+        );
+
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        let plan = super::plan(dir.path(), &strict).unwrap();
+        assert_eq!(
+            paths(plan),
+            vec![PathBuf::from(".gitignore"), PathBuf::from("index.html")]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_duplicates() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("assets")).unwrap();
+        fs::write(dir.path().join("assets").join("site.css"), "body {}").unwrap();
+        // Points back into the project, so following it reaches site.css a second time
+        std::os::unix::fs::symlink(dir.path().join("assets"), dir.path().join("alias")).unwrap();
+
+        let plan = plan(dir.path(), &PublishOptions::default()).unwrap();
+        let paths: Vec<_> = plan.files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(paths, vec![PathBuf::from("assets/site.css")]);
+
+        let follow =
+            PublishOptions::default().with_symlinks(SymlinkPolicy::Follow, DuplicatePolicy::Skip);
+        let plan = super::plan(dir.path(), &follow).unwrap();
+        let paths: Vec<_> = plan.files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(paths, vec![PathBuf::from("assets/site.css")]);
+
+        let strict =
+            PublishOptions::default().with_symlinks(SymlinkPolicy::Follow, DuplicatePolicy::Error);
+        let result = super::plan(dir.path(), &strict);
+        assert!(matches!(result, Err(SurgeError::InvalidProject(_))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_loop() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("docs")).unwrap();
+        fs::write(dir.path().join("docs").join("index.html"), "hello").unwrap();
+        // Points at the project root, so following it would descend forever
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("docs").join("up")).unwrap();
+
+        let plan = plan(dir.path(), &PublishOptions::default()).unwrap();
+        assert_eq!(plan.file_count(), 1);
+
+        let follow =
+            PublishOptions::default().with_symlinks(SymlinkPolicy::Follow, DuplicatePolicy::Skip);
+        match super::plan(dir.path(), &follow) {
+            Err(SurgeError::InvalidProject(message)) => {
+                assert!(message.starts_with("symlink loop detected at "));
+                assert!(message.ends_with("up"));
+            }
+            other => panic!("expected a symlink loop error, got {:?}", other),
+        }
+        let result = crate::stream::calculate_metadata_with(dir.path(), &follow);
+        assert!(matches!(result, Err(SurgeError::InvalidProject(_))));
+    }
+}
@@ -3,7 +3,7 @@
 */
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -25,6 +25,53 @@ pub struct AccountResponse {
     pub card: Value,
 }
 
+impl AccountResponse {
+    /// Returns the account's role as a [`Role`], mapped from the raw `role` code.
+    pub fn role_kind(&self) -> Role {
+        Role::from(self.role)
+    }
+
+    /// Returns the account's Stripe subscriptions, or an empty slice without a Stripe customer.
+    pub fn subscriptions(&self) -> &[StripeSubscription] {
+        self.stripe
+            .as_ref()
+            .map_or(&[], |stripe| stripe.subscriptions.data.as_slice())
+    }
+}
+
+/// The role of an account, as reported by the numeric `role` field.
+///
+/// Codes without a known meaning are kept in `Role::Other`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    /// An administrator account (code `1`).
+    Admin,
+    /// A regular user account (code `5`).
+    User,
+    /// Any other role code.
+    Other(i64),
+}
+
+impl From<i64> for Role {
+    fn from(code: i64) -> Self {
+        match code {
+            1 => Role::Admin,
+            5 => Role::User,
+            other => Role::Other(other),
+        }
+    }
+}
+
+impl From<Role> for i64 {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::Admin => 1,
+            Role::User => 5,
+            Role::Other(code) => code,
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Stripe {
@@ -103,7 +150,7 @@ pub struct Sources {
 #[serde(rename_all = "camelCase")]
 pub struct Subscriptions {
     pub object: String,
-    pub data: Vec<Value>,
+    pub data: Vec<StripeSubscription>,
     #[serde(rename = "has_more")]
     pub has_more: bool,
     #[serde(rename = "total_count")]
@@ -111,6 +158,55 @@ pub struct Subscriptions {
     pub url: String,
 }
 
+/// A Stripe subscription, as listed in `stripe.subscriptions.data`.
+///
+/// Only the fields needed to tell a subscription's state are typed; the rest of the Stripe
+/// object is kept in `extra`. Timestamps are Unix epoch seconds.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StripeSubscription {
+    /// The subscription id (`sub_...`).
+    #[serde(default)]
+    pub id: String,
+    /// The subscription status, e.g. `active`, `trialing`, `past_due` or `canceled`.
+    #[serde(default)]
+    pub status: String,
+    /// End of the current billing period.
+    #[serde(default)]
+    pub current_period_end: Option<i64>,
+    /// When the subscription is scheduled to be canceled, if it is.
+    #[serde(default)]
+    pub cancel_at: Option<i64>,
+    /// The subscribed plan.
+    #[serde(default)]
+    pub plan: Option<StripePlan>,
+    /// Every other field of the subscription.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl StripeSubscription {
+    /// Returns the id of the subscribed plan, if any.
+    pub fn plan_id(&self) -> Option<&str> {
+        self.plan.as_ref().map(|plan| plan.id.as_str())
+    }
+
+    /// Returns `true` if the subscription is `active` or `trialing`.
+    pub fn is_active(&self) -> bool {
+        matches!(self.status.as_str(), "active" | "trialing")
+    }
+}
+
+/// The plan of a [`StripeSubscription`].
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StripePlan {
+    /// The plan id, matching the `id` of a Surge plan.
+    #[serde(default)]
+    pub id: String,
+    /// Every other field of the plan.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TaxIds {
@@ -144,3 +240,55 @@ pub struct Metadata2 {
     #[serde(rename = "type")]
     pub type_field: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_kind() {
+        let mut account = AccountResponse {
+            role: 5,
+            ..AccountResponse::default()
+        };
+        assert_eq!(account.role_kind(), Role::User);
+
+        account.role = 1;
+        assert_eq!(account.role_kind(), Role::Admin);
+
+        account.role = 42;
+        assert_eq!(account.role_kind(), Role::Other(42));
+        assert_eq!(i64::from(account.role_kind()), 42);
+    }
+
+    #[test]
+    fn test_stripe_subscriptions() {
+        let mut account = serde_json::to_value(AccountResponse {
+            stripe: Some(Stripe::default()),
+            ..AccountResponse::default()
+        })
+        .unwrap();
+        account["stripe"]["subscriptions"]["data"] = serde_json::json!([{
+            "id": "sub_123",
+            "object": "subscription",
+            "status": "active",
+            "current_period_end": 1735689600,
+            "cancel_at": null,
+            "plan": { "id": "standard", "object": "plan", "amount": 3000 }
+        }]);
+
+        let account: AccountResponse = serde_json::from_value(account).unwrap();
+        let subscriptions = account.subscriptions();
+        assert_eq!(subscriptions.len(), 1);
+
+        let subscription = &subscriptions[0];
+        assert_eq!(subscription.status, "active");
+        assert!(subscription.is_active());
+        assert_eq!(subscription.current_period_end, Some(1735689600));
+        assert_eq!(subscription.cancel_at, None);
+        assert_eq!(subscription.plan_id(), Some("standard"));
+        assert_eq!(subscription.extra["object"], "subscription");
+
+        assert!(AccountResponse::default().subscriptions().is_empty());
+    }
+}
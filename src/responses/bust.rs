@@ -0,0 +1,28 @@
+/*
+  src/responses/bust.rs
+*/
+use serde_derive::Deserialize;
+use serde_derive::Serialize;
+use serde_json::{Map, Value};
+
+/// The confirmation returned when a domain's cache is cleared.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BustResponse {
+    /// Number of cache entries purged, if reported by the server.
+    #[serde(default)]
+    pub purged: Option<u64>,
+    /// Paths purged from the cache, if listed by the server.
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Every other key of the response.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl BustResponse {
+    /// Returns the number of purged cache entries: `purged` if reported, otherwise the number
+    /// of listed `paths`.
+    pub fn purged_count(&self) -> u64 {
+        self.purged.unwrap_or(self.paths.len() as u64)
+    }
+}
@@ -2,6 +2,7 @@
   src/responses/certs.rs
 */
 use chrono::DateTime;
+use chrono::Duration;
 use chrono::Utc;
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
@@ -24,3 +25,77 @@ pub struct Cert {
     pub cert_name: String,
     pub auto_renew: bool,
 }
+
+impl Cert {
+    /// Parses `not_before`, which the API sends as an RFC 3339 or RFC 2822 timestamp.
+    ///
+    /// # Returns
+    /// The start of the validity period, or `None` if `not_before` cannot be parsed.
+    pub fn not_before_time(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.not_before)
+            .or_else(|_| DateTime::parse_from_rfc2822(&self.not_before))
+            .ok()
+            .map(|time| time.with_timezone(&Utc))
+    }
+
+    /// Returns the length of the validity period, `not_after - not_before`.
+    ///
+    /// # Returns
+    /// The validity window, or `None` if `not_before` cannot be parsed.
+    pub fn validity_window(&self) -> Option<Duration> {
+        self.not_before_time()
+            .map(|not_before| self.not_after - not_before)
+    }
+
+    /// Returns the time left until the certificate expires.
+    ///
+    /// # Arguments
+    /// * `now` - The current time, e.g. `Utc::now()`.
+    ///
+    /// # Returns
+    /// `not_after - now`; negative if the certificate has already expired.
+    pub fn time_until_expiry(&self, now: DateTime<Utc>) -> Duration {
+        self.not_after - now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn one_year_cert() -> Cert {
+        Cert {
+            not_before: "2024-01-01T00:00:00Z".to_string(),
+            not_after: "2024-12-31T00:00:00Z".parse().unwrap(),
+            ..Cert::default()
+        }
+    }
+
+    #[test]
+    fn test_validity_window() {
+        let cert = one_year_cert();
+        assert_eq!(cert.validity_window(), Some(Duration::days(365)));
+
+        let cert = Cert {
+            not_before: "Mon, 1 Jan 2024 00:00:00 +0000".to_string(),
+            ..one_year_cert()
+        };
+        assert_eq!(cert.validity_window(), Some(Duration::days(365)));
+
+        let cert = Cert {
+            not_before: "soon".to_string(),
+            ..one_year_cert()
+        };
+        assert_eq!(cert.validity_window(), None);
+    }
+
+    #[test]
+    fn test_time_until_expiry() {
+        let cert = one_year_cert();
+        let now = "2024-12-01T00:00:00Z".parse().unwrap();
+        assert_eq!(cert.time_until_expiry(now), Duration::days(30));
+
+        let now = "2025-01-02T00:00:00Z".parse().unwrap();
+        assert_eq!(cert.time_until_expiry(now), Duration::days(-2));
+    }
+}
@@ -51,6 +51,55 @@ pub struct DAnalyticsResponse {
     pub extra: HashMap<String, serde_json::Value>,
 }
 
+impl DAnalyticsResponse {
+    /// Returns the bytes served over the response's `range`, headers and bodies included.
+    ///
+    /// Read from `bandwidth.all`, see [`TimeSeries::total`]. `None` if the response has no
+    /// bandwidth data.
+    pub fn total_bandwidth_bytes(&self) -> Option<u64> {
+        self.bandwidth
+            .as_ref()
+            .map(|bandwidth| bandwidth.all.total())
+    }
+
+    /// Returns the response body bytes served over the `range`, from `bandwidth.body`.
+    pub fn body_bandwidth_bytes(&self) -> Option<u64> {
+        self.bandwidth
+            .as_ref()
+            .map(|bandwidth| bandwidth.body.total())
+    }
+
+    /// Returns the number of visits over the `range`, from `traffic.visits`.
+    pub fn total_visits(&self) -> Option<u64> {
+        self.traffic.as_ref().map(|traffic| traffic.visits.total())
+    }
+
+    /// Returns the number of unique visitors over the `range`, from `traffic.uniques`.
+    pub fn total_uniques(&self) -> Option<u64> {
+        self.traffic.as_ref().map(|traffic| traffic.uniques.total())
+    }
+
+    /// Returns the number of connections over the `range`, from `traffic.connections`.
+    pub fn total_connections(&self) -> Option<u64> {
+        self.traffic
+            .as_ref()
+            .map(|traffic| traffic.connections.total())
+    }
+
+    /// Returns the share of requests served from the CDN cache, between `0.0` and `1.0`.
+    ///
+    /// Computed as `hit / (hit + miss)` from the `cache` totals; `None` if the response has no
+    /// cache data or no requests were counted.
+    pub fn cache_hit_ratio(&self) -> Option<f64> {
+        let cache = self.cache.as_ref()?;
+        let (hit, miss) = (cache.hit.total(), cache.miss.total());
+        let requests = hit + miss;
+        (requests > 0).then(|| hit as f64 / requests as f64)
+    }
+}
+
+/// A metric over the analytics `range`: `t` is the total and `s` holds one sample per period
+/// (typically a day), oldest first.
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct TimeSeries {
     #[serde(default)]
@@ -59,6 +108,25 @@ pub struct TimeSeries {
     pub s: Vec<i64>,
 }
 
+impl TimeSeries {
+    /// Returns the sum of the samples in `s`, ignoring negative values.
+    pub fn sum(&self) -> u64 {
+        self.s.iter().map(|&sample| sample.max(0) as u64).sum()
+    }
+
+    /// Returns the total of the metric.
+    ///
+    /// This is `t` as reported by the server; if `t` is missing or zero while samples are
+    /// present, the samples are summed instead.
+    pub fn total(&self) -> u64 {
+        if self.t > 0 {
+            self.t as u64
+        } else {
+            self.sum()
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Bandwidth {
     #[serde(default)]
@@ -112,3 +180,39 @@ pub struct Traffic {
     pub uniques: TimeSeries,
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_aggregates() {
+        let analytics: DAnalyticsResponse = serde_json::from_value(json!({
+            "range": ["2024-01-01", "2024-01-02", "2024-01-03"],
+            "bandwidth": {
+                "all": { "t": 2_300_000_000u64, "s": [1_000_000_000u64, 800_000_000u64, 500_000_000u64] },
+                "body": { "s": [900, 700, 400] }
+            },
+            "traffic": {
+                "visits": { "t": 30, "s": [10, 15, 5] },
+                "uniques": { "t": 12, "s": [4, 6, 2] }
+            },
+            "cache": {
+                "hit": { "t": 75, "s": [] },
+                "miss": { "t": 25, "s": [] }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(analytics.total_bandwidth_bytes(), Some(2_300_000_000));
+        assert_eq!(analytics.body_bandwidth_bytes(), Some(2000));
+        assert_eq!(analytics.total_visits(), Some(30));
+        assert_eq!(analytics.total_uniques(), Some(12));
+        assert_eq!(analytics.total_connections(), Some(0));
+        assert_eq!(analytics.cache_hit_ratio(), Some(0.75));
+
+        let empty: DAnalyticsResponse = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(empty.total_bandwidth_bytes(), None);
+        assert_eq!(empty.cache_hit_ratio(), None);
+    }
+}
@@ -110,4 +110,3 @@ pub struct Manifest {
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
 }
-
@@ -1,6 +1,8 @@
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
-use serde_json::Value;
+use serde_json::{Map, Value};
+
+use super::BuildOutput;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -12,6 +14,13 @@ pub struct DiscardResponse {
     pub instances: Vec<Instance>,
 }
 
+impl DiscardResponse {
+    /// Returns the cache entries changed by the discard, see [`CacheChange`].
+    pub fn cache_change(&self) -> CacheChange {
+        self.uncached.cache_change()
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Uncached {
@@ -20,6 +29,45 @@ pub struct Uncached {
     pub change: Value,
 }
 
+impl Uncached {
+    /// Returns the known keys of `change`, see [`CacheChange`].
+    pub fn cache_change(&self) -> CacheChange {
+        CacheChange::from_value(&self.change)
+    }
+}
+
+/// The known keys of the `uncached.change` field of a discard response.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheChange {
+    /// Cache entries added by the change.
+    #[serde(default)]
+    pub added: Vec<String>,
+    /// Cache entries removed by the change.
+    #[serde(default)]
+    pub removed: Vec<String>,
+    /// Every other key of the `change` object.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl CacheChange {
+    /// Parses the raw `change` value of a discard response.
+    ///
+    /// `null`, non-object values, and objects whose `added`/`removed` are not lists of strings
+    /// yield an empty `CacheChange`; the raw value remains available as `Uncached::change`.
+    pub fn from_value(value: &Value) -> Self {
+        if !value.is_object() {
+            return Self::default();
+        }
+        serde_json::from_value(value.clone()).unwrap_or_default()
+    }
+
+    /// Returns `true` if no cache entries were added or removed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Revision {
@@ -31,7 +79,9 @@ pub struct Revision {
     pub output: Option<Value>,
     pub config: Config,
     pub message: Value,
-    pub build_time: Value,
+    /// How long the build took, in seconds.
+    #[serde(default, deserialize_with = "crate::responses::lenient_f64")]
+    pub build_time: Option<f64>,
     pub ip: String,
     pub private_file_list: Vec<Value>,
     pub public_file_count: i64,
@@ -44,6 +94,16 @@ pub struct Revision {
     pub preview: String,
 }
 
+impl Revision {
+    /// Returns the known keys of `output`, see [`BuildOutput`].
+    pub fn build_output(&self) -> BuildOutput {
+        self.output
+            .as_ref()
+            .map(BuildOutput::from_value)
+            .unwrap_or_default()
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
@@ -65,3 +125,61 @@ pub struct Instance {
     pub ip: String,
     pub info: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_discard_cache_change() {
+        let response: DiscardResponse = serde_json::from_value(json!({
+            "rev": "1700000000000",
+            "domain": "example.surge.sh",
+            "uncached": {
+                "revs": ["1700000000000"],
+                "domains": ["example.surge.sh"],
+                "change": {
+                    "added": [],
+                    "removed": ["example.surge.sh/1700000000000"],
+                    "at": 1700000001000u64
+                }
+            },
+            "revision": {
+                "rev": 1700000000000u64,
+                "cmd": "surge",
+                "email": "test@example.com",
+                "platform": "surge.sh",
+                "cliVersion": "0.24.6",
+                "output": null,
+                "config": { "pdf": false },
+                "message": null,
+                "buildTime": null,
+                "ip": "127.0.0.1",
+                "privateFileList": [],
+                "publicFileCount": 1,
+                "publicTotalSize": 5,
+                "privateFileCount": 0,
+                "privateTotalSize": 0,
+                "uploadStartTime": 1700000000000u64,
+                "uploadEndTime": 1700000000500u64,
+                "uploadDuration": 0.5,
+                "preview": "1700000000000-example.surge.sh"
+            },
+            "instances": []
+        }))
+        .unwrap();
+
+        let change = response.cache_change();
+        assert!(change.added.is_empty());
+        assert_eq!(change.removed, vec!["example.surge.sh/1700000000000"]);
+        assert_eq!(change.extra["at"], json!(1700000001000u64));
+        assert!(!change.is_empty());
+
+        assert!(CacheChange::from_value(&json!(null)).is_empty());
+        assert_eq!(
+            CacheChange::from_value(&json!({ "added": "everything" })),
+            CacheChange::default()
+        );
+    }
+}
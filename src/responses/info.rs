@@ -0,0 +1,29 @@
+/*
+  src/responses/info.rs
+*/
+use serde::{Deserialize, Serialize};
+
+use crate::types::{CertDetails, Instance, Url};
+
+/// Where a deployment revision is served: its URLs, certificates, and instances.
+///
+/// The same data the `info` event of a publish carries, fetched for an existing revision.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PublishResult {
+    /// The URLs the revision is served from.
+    #[serde(default)]
+    pub urls: Vec<Url>,
+    /// The certificates covering the domain.
+    #[serde(default)]
+    pub certs: Vec<CertDetails>,
+    /// The instances serving the revision.
+    #[serde(default)]
+    pub instances: Vec<Instance>,
+}
+
+impl PublishResult {
+    /// Returns the names of the URLs the revision is served from.
+    pub fn url_names(&self) -> Vec<&str> {
+        self.urls.iter().map(|url| url.name.as_str()).collect()
+    }
+}
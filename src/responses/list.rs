@@ -4,7 +4,10 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(Debug)]
+use super::{BuildOutput, DeployConfig};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "camelCase")]
 pub enum ListResult {
     Global(Vec<ListResponse>),
     Domain(ListDomainResponse),
@@ -24,8 +27,13 @@ pub struct ListResponse {
     pub output: Value,
     pub config: Value,
     pub message: Option<String>,
-    #[serde(rename = "buildTime")]
-    pub build_time: Option<String>,
+    /// How long the build took, in seconds.
+    #[serde(
+        rename = "buildTime",
+        default,
+        deserialize_with = "crate::responses::lenient_f64"
+    )]
+    pub build_time: Option<f64>,
     pub ip: String,
     #[serde(rename = "privateFileList")]
     pub private_file_list: Vec<String>,
@@ -48,6 +56,18 @@ pub struct ListResponse {
     pub time_ago_in_words: String,
 }
 
+impl ListResponse {
+    /// Returns the known keys of `output`, see [`BuildOutput`].
+    pub fn build_output(&self) -> BuildOutput {
+        BuildOutput::from_value(&self.output)
+    }
+
+    /// Returns the known keys of `config`, see [`DeployConfig`].
+    pub fn deploy_config(&self) -> DeployConfig {
+        DeployConfig::from_value(&self.config)
+    }
+}
+
 pub type ListDomainResponse = Vec<DomainList>;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -59,7 +79,9 @@ pub struct DomainList {
     pub cmd: String,
     pub public_file_count: i64,
     pub public_total_size: i64,
-    pub build_time: Value,
+    /// How long the build took, in seconds.
+    #[serde(default, deserialize_with = "crate::responses::lenient_f64")]
+    pub build_time: Option<f64>,
     pub msg: Value,
     pub current: bool,
     pub preview: String,
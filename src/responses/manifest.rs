@@ -6,7 +6,7 @@ use std::collections::HashMap;
 
 pub type ManifestResponse = HashMap<String, ManifestResponseValue>;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ManifestResponseValue {
     pub size: i64,
     #[serde(rename = "md5sum")]
@@ -14,3 +14,116 @@ pub struct ManifestResponseValue {
     #[serde(rename = "sha256sum")]
     pub sha256_sum: String,
 }
+
+/// The file-level difference between two deployment manifests.
+///
+/// Paths are compared by their `sha256sum`; each list is sorted alphabetically.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestDiff {
+    /// Paths present only in the newer manifest.
+    pub added: Vec<String>,
+    /// Paths present only in the older manifest.
+    pub removed: Vec<String>,
+    /// Paths present in both manifests whose contents differ.
+    pub changed: Vec<String>,
+}
+
+impl ManifestDiff {
+    /// Computes the difference between two manifests.
+    ///
+    /// # Arguments
+    /// * `old` - The manifest of the earlier revision.
+    /// * `new` - The manifest of the later revision.
+    ///
+    /// # Returns
+    /// A `ManifestDiff` listing added, removed, and changed paths.
+    pub fn between(old: &ManifestResponse, new: &ManifestResponse) -> Self {
+        let mut diff = Self::default();
+
+        for (path, entry) in new {
+            match old.get(path) {
+                None => diff.added.push(path.clone()),
+                Some(previous) if previous.sha256_sum != entry.sha256_sum => {
+                    diff.changed.push(path.clone())
+                }
+                Some(_) => {}
+            }
+        }
+        diff.removed = old
+            .keys()
+            .filter(|path| !new.contains_key(*path))
+            .cloned()
+            .collect();
+
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changed.sort();
+        diff
+    }
+
+    /// Returns the total number of added, removed, and changed files.
+    pub fn len(&self) -> usize {
+        self.added.len() + self.removed.len() + self.changed.len()
+    }
+
+    /// Returns `true` if the two manifests are identical.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over every entry as a `(marker, path)` pair, in added/removed/changed order.
+    ///
+    /// The marker is `'+'` for added, `'-'` for removed, and `'~'` for changed files.
+    pub fn entries(&self) -> impl Iterator<Item = (char, &str)> {
+        let added = self.added.iter().map(|path| ('+', path.as_str()));
+        let removed = self.removed.iter().map(|path| ('-', path.as_str()));
+        let changed = self.changed.iter().map(|path| ('~', path.as_str()));
+        added.chain(removed).chain(changed)
+    }
+
+    /// Returns a one-line summary such as `3 files changed (1 added, 1 removed, 1 changed)`.
+    pub fn summary(&self) -> String {
+        if self.is_empty() {
+            return "No files changed".to_string();
+        }
+        format!(
+            "{} file{} changed ({} added, {} removed, {} changed)",
+            self.len(),
+            if self.len() == 1 { "" } else { "s" },
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )
+    }
+
+    /// Renders the diff as plain text, suitable for a PR comment.
+    ///
+    /// Each file is listed on its own line prefixed with `+`, `-`, or `~`, followed by a
+    /// blank line and the [`summary`](Self::summary).
+    ///
+    /// # Example
+    /// ```
+    /// use surge_sdk::ManifestDiff;
+    ///
+    /// let diff = ManifestDiff {
+    ///     added: vec!["added.html".into()],
+    ///     removed: vec!["removed.js".into()],
+    ///     changed: vec!["changed.css".into()],
+    /// };
+    /// assert_eq!(
+    ///     diff.to_text(),
+    ///     "+ added.html\n- removed.js\n~ changed.css\n\n3 files changed (1 added, 1 removed, 1 changed)"
+    /// );
+    /// ```
+    pub fn to_text(&self) -> String {
+        let mut text: String = self
+            .entries()
+            .map(|(marker, path)| format!("{} {}\n", marker, path))
+            .collect();
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&self.summary());
+        text
+    }
+}
@@ -1,9 +1,13 @@
 /*
   src/responses/metadata.rs
 */
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value; // For the flexible "output" field
 
+use super::{BuildOutput, DeployConfig};
+use crate::utils::epoch_to_datetime;
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MetadataResponse {
@@ -14,8 +18,10 @@ pub struct MetadataResponse {
     pub cli_version: String,
     pub output: Value, // Using Value for flexible JSON object
     pub config: Config,
-    pub message: Option<String>,    // Nullable field
-    pub build_time: Option<String>, // Nullable field
+    pub message: Option<String>, // Nullable field
+    /// How long the build took, in seconds.
+    #[serde(default, deserialize_with = "crate::responses::lenient_f64")]
+    pub build_time: Option<f64>,
     pub ip: String,
     pub private_file_list: Vec<Value>,
     pub public_file_count: i32,
@@ -28,7 +34,61 @@ pub struct MetadataResponse {
     pub preview: String,
 }
 
+impl MetadataResponse {
+    /// Returns the known keys of `output`, see [`BuildOutput`].
+    pub fn build_output(&self) -> BuildOutput {
+        BuildOutput::from_value(&self.output)
+    }
+
+    /// Returns the known keys of `config`, see [`DeployConfig`].
+    pub fn deploy_config(&self) -> DeployConfig {
+        let mut config = self.config.extra.clone();
+        config.insert("pdf".to_string(), Value::Bool(self.config.pdf));
+        DeployConfig::from_value(&Value::Object(config))
+    }
+
+    /// Returns when the upload started.
+    ///
+    /// `upload_start_time` may be in epoch seconds or milliseconds; both are handled.
+    pub fn uploaded_at(&self) -> DateTime<Utc> {
+        epoch_to_datetime(self.upload_start_time)
+    }
+
+    /// Returns `true` if this deployment records `commit` as its source commit.
+    ///
+    /// The commit is compared exactly with the `sourceCommit` or `commit` keys of `output`,
+    /// falling back to the words of `message`: `"Deploy abc123"` records `abc123`, but
+    /// `"Release v1.2"` does not record `1`. Surrounding punctuation such as `(abc123)` is ignored.
+    pub fn records_commit(&self, commit: &str) -> bool {
+        if commit.is_empty() {
+            return false;
+        }
+        let in_output = ["sourceCommit", "commit"]
+            .iter()
+            .any(|key| self.output.get(key).and_then(Value::as_str) == Some(commit));
+        in_output
+            || self.message.as_deref().is_some_and(|message| {
+                message.split_whitespace().any(|word| {
+                    word.trim_matches(|c: char| c.is_ascii_punctuation() && c != '-' && c != '_')
+                        == commit
+                })
+            })
+    }
+
+    /// Returns how long the upload took, from `upload_start_time` to `upload_end_time`.
+    ///
+    /// Never negative: a reversed pair of timestamps yields `Duration::zero()`.
+    pub fn upload_elapsed(&self) -> Duration {
+        let elapsed =
+            epoch_to_datetime(self.upload_end_time) - epoch_to_datetime(self.upload_start_time);
+        elapsed.max(Duration::zero())
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
     pub pdf: bool,
+    /// Every other key of the `config` object, see [`MetadataResponse::deploy_config`].
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, Value>,
 }
@@ -8,14 +8,17 @@
 //! strongly-typed structures to deserialize HTTP responses. These types are used
 //! across the application to ensure safe and predictable handling of API data.
 mod account;
+mod bust;
 mod certs;
 mod danalytics;
 mod daudit;
 mod discard;
+mod info;
 mod list;
 mod login;
 mod manifest;
 mod metadata;
+mod output;
 mod plans;
 mod roll;
 mod settings;
@@ -27,8 +30,8 @@ mod usage;
 /// Re-exports the unified error type for response handling.
 pub use crate::error::SurgeError;
 
-/// Represents the authenticated user's account information.
-pub use account::AccountResponse;
+/// Represents the authenticated user's account information, with its Stripe subscriptions.
+pub use account::{AccountResponse, Role, StripePlan, StripeSubscription};
 
 /// Represents a response containing usage statistics
 pub use usage::UsageResponse;
@@ -39,17 +42,22 @@ pub use certs::{Cert as Certs, CertsResponse};
 /// Represents analytics data about deployments or traffic.
 pub use danalytics::DAnalyticsResponse;
 
-/// Represents settings status
-pub use settings::SettingsResponse;
+/// Represents settings status, with typed redirect, CORS, and HSTS settings
+pub use settings::{
+    CorsConfig, DeployConfig, DomainSettings, HstsConfig, RedirectRule, SettingsResponse,
+};
 
 /// Represents deployment audit logs or changes.
-pub use daudit::DAuditResponse;
+pub use daudit::{DAuditResponse, DAuditResponseValue};
 
 /// Represents the list of deployments, including associated plans.
 pub use list::{ListDomainResponse, ListResponse, ListResult};
 
-/// Represents a discard response result
-pub use discard::DiscardResponse;
+/// Represents the confirmation of a cache bust.
+pub use bust::BustResponse;
+
+/// Represents a discard response result, with the cache entries it changed
+pub use discard::{CacheChange, DiscardResponse};
 
 /// Represents the result of a login operation, typically containing tokens or session info.
 pub use login::LoginResponse;
@@ -57,11 +65,12 @@ pub use login::LoginResponse;
 /// Represents the result of rolling back a rev
 pub use roll::RollResponse;
 
-/// Represents the deployment manifest returned after a successful upload or update.
-pub use manifest::ManifestResponse;
+/// Represents the deployment manifest returned after a successful upload or update,
+/// and the file-level difference between two manifests.
+pub use manifest::{ManifestDiff, ManifestResponse, ManifestResponseValue};
 
-/// Represents all available plans a user can subscribe to.
-pub use plans::PlansResponse;
+/// Represents all available plans a user can subscribe to, and the quoted cost of changing plan.
+pub use plans::{PlanChangePreview, PlansResponse};
 
 /// Represents the finalization state of an upload process.
 pub use uploadfin::UploadFinResponse;
@@ -69,5 +78,12 @@ pub use uploadfin::UploadFinResponse;
 /// Represents a teardown response
 pub use teardown::TeardownResponse;
 
+/// Represents the URLs, certificates, and instances serving a revision.
+pub use info::PublishResult;
+
 /// Represents the result of an metadata response.
 pub use metadata::MetadataResponse;
+
+/// Represents the known keys of a deployment's free-form `output` field.
+pub use output::BuildOutput;
+pub(crate) use output::lenient_f64;
@@ -0,0 +1,141 @@
+/*
+  src/responses/output.rs
+*/
+//! The build output recorded with a deployment.
+//!
+//! The `output` field of revisions is free-form JSON. It commonly holds the build log, the build
+//! duration, and any warnings; [`BuildOutput`] captures those keys leniently and keeps the rest
+//! in [`BuildOutput::extra`].
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+/// The known keys of a deployment's `output` field.
+///
+/// Deserialization never fails on unexpected shapes: a key with an unexpected type is treated as
+/// missing, and `log`/`warnings` also accept a single string, split into lines.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuildOutput {
+    /// The build log, one entry per line.
+    #[serde(default, deserialize_with = "lenient_lines")]
+    pub log: Vec<String>,
+    /// How long the build took, in seconds.
+    #[serde(default, deserialize_with = "lenient_f64")]
+    pub duration: Option<f64>,
+    /// Warnings reported by the build.
+    #[serde(default, deserialize_with = "lenient_lines")]
+    pub warnings: Vec<String>,
+    /// Every other key of the `output` object.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl BuildOutput {
+    /// Parses the raw `output` value of a response.
+    ///
+    /// `null` and non-object values yield an empty `BuildOutput`.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json::json;
+    /// use surge_sdk::BuildOutput;
+    ///
+    /// let output = BuildOutput::from_value(&json!({ "log": "built\ndone", "duration": 1.5 }));
+    /// assert_eq!(output.log, vec!["built", "done"]);
+    /// assert_eq!(output.duration, Some(1.5));
+    /// ```
+    pub fn from_value(value: &Value) -> Self {
+        if !value.is_object() {
+            return Self::default();
+        }
+        serde_json::from_value(value.clone()).unwrap_or_default()
+    }
+}
+
+/// Accepts an array (keeping its string items) or a single string (split into lines).
+fn lenient_lines<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(text) => text.lines().map(str::to_string).collect(),
+        Value::Array(items) => items
+            .into_iter()
+            .filter_map(|item| match item {
+                Value::String(line) => Some(line),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    })
+}
+
+/// Accepts a number or a numeric string; anything else, including `null`, is `None`.
+///
+/// Also used for the `buildTime` of deployments, which the API sends as a number of seconds,
+/// a string, or `null`.
+pub(crate) fn lenient_f64<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<f64>, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_build_output_known_and_extra_keys() {
+        let output = BuildOutput::from_value(&json!({
+            "log": ["npm run build", "done"],
+            "duration": "12.5",
+            "warnings": "large bundle\nmissing favicon",
+            "sourceCommit": "abc123"
+        }));
+
+        assert_eq!(output.log, vec!["npm run build", "done"]);
+        assert_eq!(output.duration, Some(12.5));
+        assert_eq!(output.warnings, vec!["large bundle", "missing favicon"]);
+        assert_eq!(output.extra.get("sourceCommit"), Some(&json!("abc123")));
+    }
+
+    #[test]
+    fn test_lenient_build_time() {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Revision {
+            #[serde(default, deserialize_with = "lenient_f64")]
+            build_time: Option<f64>,
+        }
+
+        let metadata = |build_time: Option<Value>| {
+            let body = match build_time {
+                Some(value) => json!({ "buildTime": value }),
+                None => json!({}),
+            };
+            serde_json::from_value::<Revision>(body).map(|r| r.build_time)
+        };
+
+        assert_eq!(metadata(Some(json!(12.5))).unwrap(), Some(12.5));
+        assert_eq!(metadata(Some(json!(3))).unwrap(), Some(3.0));
+        assert_eq!(metadata(Some(json!("12.5"))).unwrap(), Some(12.5));
+        assert_eq!(metadata(Some(json!(" 7 "))).unwrap(), Some(7.0));
+        assert_eq!(metadata(Some(json!("unknown"))).unwrap(), None);
+        assert_eq!(metadata(Some(Value::Null)).unwrap(), None);
+        assert_eq!(metadata(None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_build_output_is_lenient() {
+        assert_eq!(
+            BuildOutput::from_value(&Value::Null),
+            BuildOutput::default()
+        );
+
+        let output = BuildOutput::from_value(&json!({ "log": 42, "duration": [] }));
+        assert!(output.log.is_empty());
+        assert_eq!(output.duration, None);
+    }
+}
@@ -1,6 +1,8 @@
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
-use serde_json::Value;
+use serde_json::{Map, Value};
+
+use crate::utils::epoch_to_datetime;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -109,3 +111,35 @@ pub struct Metadata2 {
     #[serde(rename = "type")]
     pub type_field: String,
 }
+
+/// The quoted cost of switching to another plan, returned by `SurgeSdk::preview_plan_change`.
+///
+/// Amounts are in the smallest unit of `currency` (e.g. cents), as Stripe reports them. Both
+/// camelCase keys and Stripe's upcoming-invoice keys (`amount_due`, `next_payment_attempt`) are
+/// accepted; every other key is kept in `extra`.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanChangePreview {
+    /// The prorated amount charged immediately when the plan is changed.
+    #[serde(default, alias = "amount_due")]
+    pub immediate_charge: Option<i64>,
+    /// When the next regular charge happens, in Unix epoch seconds.
+    #[serde(default, alias = "next_payment_attempt")]
+    pub next_billing_date: Option<i64>,
+    /// The amount charged every billing period on the new plan.
+    #[serde(default, alias = "recurring_amount")]
+    pub recurring_amount: Option<i64>,
+    /// The ISO currency code of the amounts, e.g. `usd`.
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// Every other key of the preview.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl PlanChangePreview {
+    /// Returns `next_billing_date` as a `DateTime`.
+    pub fn next_billing_datetime(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.next_billing_date.map(epoch_to_datetime)
+    }
+}
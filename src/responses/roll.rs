@@ -2,6 +2,8 @@ use serde_derive::Deserialize;
 use serde_derive::Serialize;
 use serde_json::Value;
 
+use super::BuildOutput;
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RollResponse {
@@ -21,7 +23,9 @@ pub struct Revision {
     pub output: Option<Value>,
     pub config: Config,
     pub message: Value,
-    pub build_time: Value,
+    /// How long the build took, in seconds.
+    #[serde(default, deserialize_with = "crate::responses::lenient_f64")]
+    pub build_time: Option<f64>,
     pub ip: String,
     pub private_file_list: Vec<Value>,
     pub public_file_count: i64,
@@ -34,6 +38,16 @@ pub struct Revision {
     pub preview: String,
 }
 
+impl Revision {
+    /// Returns the known keys of `output`, see [`BuildOutput`].
+    pub fn build_output(&self) -> BuildOutput {
+        self.output
+            .as_ref()
+            .map(BuildOutput::from_value)
+            .unwrap_or_default()
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
@@ -51,7 +65,9 @@ pub struct Former {
     pub output: Option<Value>,
     pub config: Config2,
     pub message: Value,
-    pub build_time: Value,
+    /// How long the build took, in seconds.
+    #[serde(default, deserialize_with = "crate::responses::lenient_f64")]
+    pub build_time: Option<f64>,
     pub ip: String,
     pub private_file_list: Vec<Value>,
     pub public_file_count: i64,
@@ -64,6 +80,16 @@ pub struct Former {
     pub preview: String,
 }
 
+impl Former {
+    /// Returns the known keys of `output`, see [`BuildOutput`].
+    pub fn build_output(&self) -> BuildOutput {
+        self.output
+            .as_ref()
+            .map(BuildOutput::from_value)
+            .unwrap_or_default()
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Config2 {
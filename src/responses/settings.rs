@@ -4,13 +4,171 @@
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
 use serde_json::Value;
+use std::collections::HashMap;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SettingsResponse {
-    pub force: Value,
-    pub redirect: Value,
-    pub cors: Value,
-    pub hsts: Value,
-    pub ttl: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub force: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect: Option<Vec<RedirectRule>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cors: Option<CorsConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hsts: Option<HstsConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u64>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>, // Settings not modeled above
+}
+
+/// Settings to apply to a domain, e.g. with `PublishOptions::with_settings`.
+///
+/// Unset fields are omitted from the request body.
+pub type DomainSettings = SettingsResponse;
+
+/// The known keys of a deployment's `config` field.
+///
+/// A deployment records the settings it was published with next to a few flags of its own,
+/// such as `pdf`. Keys not modeled here end up in `settings.extra`.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeployConfig {
+    /// Whether PDF rendering was enabled for the deployment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pdf: Option<bool>,
+    /// The domain settings recorded with the deployment.
+    #[serde(flatten)]
+    pub settings: DomainSettings,
+}
+
+impl DeployConfig {
+    /// Parses the raw `config` value of a response.
+    ///
+    /// `null`, non-object values, and objects whose known keys have unexpected shapes yield an
+    /// empty `DeployConfig`; the raw value remains available on the response.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json::json;
+    /// use surge_sdk::DeployConfig;
+    ///
+    /// let config = DeployConfig::from_value(&json!({ "pdf": false, "ttl": 60, "spa": true }));
+    /// assert_eq!(config.pdf, Some(false));
+    /// assert_eq!(config.settings.ttl, Some(60));
+    /// assert_eq!(config.settings.extra["spa"], json!(true));
+    /// ```
+    pub fn from_value(value: &Value) -> Self {
+        if !value.is_object() {
+            return Self::default();
+        }
+        serde_json::from_value(value.clone()).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedirectRule {
+    pub from: String,
+    pub to: String,
+    #[serde(default = "default_redirect_status")]
+    pub status: u16,
+}
+
+fn default_redirect_status() -> u16 {
+    301
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorsConfig {
+    #[serde(default)]
+    pub origins: Vec<String>,
+    #[serde(default)]
+    pub methods: Vec<String>,
+    #[serde(default)]
+    pub headers: Vec<String>,
+    pub max_age: Option<u64>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HstsConfig {
+    pub max_age: Option<u64>,
+    pub include_sub_domains: Option<bool>,
+    pub preload: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_settings_with_redirect_and_hsts() {
+        let settings: SettingsResponse = serde_json::from_value(json!({
+            "force": true,
+            "redirect": [{ "from": "/old", "to": "https://example.com/new", "status": 302 }],
+            "cors": null,
+            "hsts": { "maxAge": 31536000, "includeSubDomains": true, "preload": false },
+            "ttl": 3600,
+            "spa": true
+        }))
+        .unwrap();
+
+        assert_eq!(settings.force, Some(true));
+        assert_eq!(
+            settings.redirect,
+            Some(vec![RedirectRule {
+                from: "/old".to_string(),
+                to: "https://example.com/new".to_string(),
+                status: 302,
+            }])
+        );
+        assert_eq!(settings.cors, None);
+        let hsts = settings.hsts.unwrap();
+        assert_eq!(hsts.max_age, Some(31_536_000));
+        assert_eq!(hsts.include_sub_domains, Some(true));
+        assert_eq!(settings.ttl, Some(3600));
+        assert_eq!(settings.extra["spa"], json!(true));
+    }
+
+    #[test]
+    fn test_settings_all_null() {
+        let settings: SettingsResponse = serde_json::from_value(json!({
+            "force": null,
+            "redirect": null,
+            "cors": null,
+            "hsts": null,
+            "ttl": null
+        }))
+        .unwrap();
+
+        assert_eq!(settings, SettingsResponse::default());
+    }
+
+    #[test]
+    fn test_deploy_config() {
+        let config = DeployConfig::from_value(&json!({
+            "pdf": true,
+            "force": null,
+            "hsts": { "maxAge": 600 },
+            "redirect": [{ "from": "/a", "to": "/b" }],
+            "spa": true
+        }));
+
+        assert_eq!(config.pdf, Some(true));
+        assert_eq!(config.settings.force, None);
+        assert_eq!(config.settings.hsts.unwrap().max_age, Some(600));
+        assert_eq!(config.settings.redirect.unwrap()[0].status, 301);
+        assert_eq!(config.settings.extra.len(), 1);
+
+        assert_eq!(
+            DeployConfig::from_value(&json!(null)),
+            DeployConfig::default()
+        );
+        assert_eq!(
+            DeployConfig::from_value(&json!({ "ttl": "soon" })),
+            DeployConfig::default()
+        );
+    }
 }
@@ -4,6 +4,7 @@
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
 use serde_json::Value;
+use std::collections::HashMap;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -16,15 +17,27 @@ pub struct UsageResponse {
     pub encryption: Encryption,
     pub bandwidth: Bandwidth,
     pub cache: Cache,
-    pub source: Source,
-    pub device: Device,
-    pub os: Os,
-    pub browser: Browser,
-    pub success: Success,
-    pub fail: Fail,
-    pub redirect: Redirect,
-    pub load: Load,
-    pub datacenters: Datacenters,
+    /// Per-day breakdowns, keyed by the dates of `range` (`YYYY-MM-DD`). Days without data may
+    /// be missing.
+    #[serde(default)]
+    pub source: HashMap<String, Vec<Value>>,
+    #[serde(default)]
+    pub device: HashMap<String, Vec<Value>>,
+    #[serde(default)]
+    pub os: HashMap<String, Vec<Value>>,
+    #[serde(default)]
+    pub browser: HashMap<String, Vec<Value>>,
+    #[serde(default)]
+    pub success: HashMap<String, Vec<Value>>,
+    #[serde(default)]
+    pub fail: HashMap<String, Vec<Value>>,
+    #[serde(default)]
+    pub redirect: HashMap<String, Vec<Value>>,
+    #[serde(default)]
+    pub load: HashMap<String, Vec<Value>>,
+    /// Traffic per datacenter, keyed by its id (e.g. `sfo-16`).
+    #[serde(default)]
+    pub datacenters: HashMap<String, Datacenter>,
     pub normalized_at_in_words: String,
 }
 
@@ -144,249 +157,98 @@ pub struct Miss {
     pub s: Vec<i64>,
 }
 
+/// The traffic served by one datacenter over the `range`.
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct Source {
-    #[serde(rename = "2025-06-02")]
-    pub n2025_06_02: Vec<Value>,
-    #[serde(rename = "2025-06-01")]
-    pub n2025_06_01: Vec<Value>,
-    #[serde(rename = "2025-05-31")]
-    pub n2025_05_31: Vec<Value>,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Device {
-    #[serde(rename = "2025-06-02")]
-    pub n2025_06_02: Vec<Value>,
-    #[serde(rename = "2025-06-01")]
-    pub n2025_06_01: Vec<Value>,
-    #[serde(rename = "2025-05-31")]
-    pub n2025_05_31: Vec<Value>,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Os {
-    #[serde(rename = "2025-06-02")]
-    pub n2025_06_02: Vec<Value>,
-    #[serde(rename = "2025-06-01")]
-    pub n2025_06_01: Vec<Value>,
-    #[serde(rename = "2025-05-31")]
-    pub n2025_05_31: Vec<Value>,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Browser {
-    #[serde(rename = "2025-06-02")]
-    pub n2025_06_02: Vec<Value>,
-    #[serde(rename = "2025-06-01")]
-    pub n2025_06_01: Vec<Value>,
-    #[serde(rename = "2025-05-31")]
-    pub n2025_05_31: Vec<Value>,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Success {
-    #[serde(rename = "2025-06-02")]
-    pub n2025_06_02: Vec<Value>,
-    #[serde(rename = "2025-06-01")]
-    pub n2025_06_01: Vec<Value>,
-    #[serde(rename = "2025-05-31")]
-    pub n2025_05_31: Vec<Value>,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Fail {
-    #[serde(rename = "2025-06-02")]
-    pub n2025_06_02: Vec<Value>,
-    #[serde(rename = "2025-06-01")]
-    pub n2025_06_01: Vec<Value>,
-    #[serde(rename = "2025-05-31")]
-    pub n2025_05_31: Vec<Value>,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Redirect {
-    #[serde(rename = "2025-06-02")]
-    pub n2025_06_02: Vec<Value>,
-    #[serde(rename = "2025-06-01")]
-    pub n2025_06_01: Vec<Value>,
-    #[serde(rename = "2025-05-31")]
-    pub n2025_05_31: Vec<Value>,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Load {
-    #[serde(rename = "2025-06-02")]
-    pub n2025_06_02: Vec<Value>,
-    #[serde(rename = "2025-06-01")]
-    pub n2025_06_01: Vec<Value>,
-    #[serde(rename = "2025-05-31")]
-    pub n2025_05_31: Vec<Value>,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Datacenters {
-    #[serde(rename = "syd-01")]
-    pub syd_01: Syd01,
-    #[serde(rename = "jfk-01")]
-    pub jfk_01: Jfk01,
-    #[serde(rename = "sfo-16")]
-    pub sfo_16: Sfo16,
-    #[serde(rename = "sjc-00")]
-    pub sjc_00: Sjc00,
-    #[serde(rename = "blr-01")]
-    pub blr_01: Blr01,
-    #[serde(rename = "lhr-01")]
-    pub lhr_01: Lhr01,
-    #[serde(rename = "sfo-12")]
-    pub sfo_12: Sfo12,
-    #[serde(rename = "sfo-15")]
-    pub sfo_15: Sfo15,
-    #[serde(rename = "yyz-06")]
-    pub yyz_06: Yyz06,
-    #[serde(rename = "nrt-01")]
-    pub nrt_01: Nrt01,
-    #[serde(rename = "fra-04")]
-    pub fra_04: Fra04,
-    #[serde(rename = "ams-02")]
-    pub ams_02: Ams02,
-    #[serde(rename = "yyz-02")]
-    pub yyz_02: Yyz02,
-    #[serde(rename = "sfo-14")]
-    pub sfo_14: Sfo14,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Syd01 {
-    pub t: i64,
-    pub s: Vec<i64>,
-    pub city: String,
-    pub country: String,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Jfk01 {
-    pub t: i64,
-    pub s: Vec<i64>,
-    pub city: String,
-    pub country: String,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Sfo16 {
-    pub t: i64,
-    pub s: Vec<i64>,
-    pub city: String,
-    pub country: String,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Sjc00 {
-    pub t: i64,
-    pub s: Vec<i64>,
-    pub city: String,
-    pub country: String,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Blr01 {
-    pub t: i64,
-    pub s: Vec<i64>,
-    pub city: String,
-    pub country: String,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Lhr01 {
-    pub t: i64,
-    pub s: Vec<i64>,
-    pub city: String,
-    pub country: String,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Sfo12 {
+pub struct Datacenter {
+    #[serde(default)]
     pub t: i64,
+    #[serde(default)]
     pub s: Vec<i64>,
+    #[serde(default)]
     pub city: String,
+    #[serde(default)]
     pub country: String,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Sfo15 {
-    pub t: i64,
-    pub s: Vec<i64>,
-    pub city: String,
-    pub country: String,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Yyz06 {
-    pub t: i64,
-    pub s: Vec<i64>,
-    pub city: String,
-    pub country: String,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Nrt01 {
-    pub t: i64,
-    pub s: Vec<i64>,
-    pub city: String,
-    pub country: String,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Fra04 {
-    pub t: i64,
-    pub s: Vec<i64>,
-    pub city: String,
-    pub country: String,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Ams02 {
-    pub t: i64,
-    pub s: Vec<i64>,
-    pub city: String,
-    pub country: String,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Yyz02 {
-    pub t: i64,
-    pub s: Vec<i64>,
-    pub city: String,
-    pub country: String,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Sfo14 {
-    pub t: i64,
-    pub s: Vec<i64>,
-    pub city: String,
-    pub country: String,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::responses::DAnalyticsResponse;
+    use serde_json::json;
+
+    /// Builds a usage response covering `days`, with data for every day.
+    fn usage_json(days: &[&str]) -> Value {
+        let series = json!({ "t": days.len(), "s": vec![1; days.len()] });
+        let per_day: serde_json::Map<String, Value> = days
+            .iter()
+            .map(|day| (day.to_string(), json!([{ "name": "direct", "count": 1 }])))
+            .collect();
+        json!({
+            "normalizedAt": "2025-06-07T00:00:00Z",
+            "version": "1",
+            "domain": "example.surge.sh",
+            "range": days,
+            "traffic": { "connections": series, "visits": series, "uniques": series },
+            "encryption": { "cE": series, "cU": series, "cRe": series, "cRu": series },
+            "bandwidth": { "all": series, "body": series, "headers": series },
+            "cache": { "hit": series, "miss": series },
+            "source": per_day,
+            "device": per_day,
+            "os": per_day,
+            "browser": per_day,
+            "success": per_day,
+            "fail": per_day,
+            "redirect": per_day,
+            "load": per_day,
+            "datacenters": {
+                "fra-04": { "t": 2, "s": [1, 1], "city": "Frankfurt", "country": "DE" }
+            },
+            "normalizedAtInWords": "just now"
+        })
+    }
+
+    #[test]
+    fn test_usage_seven_days() {
+        let days = [
+            "2025-06-01",
+            "2025-06-02",
+            "2025-06-03",
+            "2025-06-04",
+            "2025-06-05",
+            "2025-06-06",
+            "2025-06-07",
+        ];
+        let body = usage_json(&days);
+
+        let usage: UsageResponse = serde_json::from_value(body.clone()).unwrap();
+        assert_eq!(usage.range.len(), 7);
+        assert_eq!(usage.source.len(), 7);
+        assert_eq!(usage.load["2025-06-04"].len(), 1);
+        assert_eq!(usage.traffic.visits.s, vec![1; 7]);
+        assert_eq!(usage.datacenters["fra-04"].city, "Frankfurt");
+
+        let analytics: DAnalyticsResponse = serde_json::from_value(body).unwrap();
+        assert_eq!(analytics.source.len(), 7);
+        assert_eq!(analytics.total_visits(), Some(7));
+    }
+
+    #[test]
+    fn test_usage_single_day_with_missing_days() {
+        let mut body = usage_json(&["2025-06-01"]);
+        // A day in the range without data, and no datacenters at all
+        body["range"] = json!(["2025-05-31", "2025-06-01"]);
+        body.as_object_mut().unwrap().remove("datacenters");
+
+        let usage: UsageResponse = serde_json::from_value(body.clone()).unwrap();
+        assert_eq!(usage.range.len(), 2);
+        assert_eq!(usage.browser.len(), 1);
+        assert!(usage.browser.contains_key("2025-06-01"));
+        assert!(!usage.browser.contains_key("2025-05-31"));
+        assert!(usage.datacenters.is_empty());
+
+        let analytics: DAnalyticsResponse = serde_json::from_value(body).unwrap();
+        assert_eq!(analytics.browser.len(), 1);
+        assert_eq!(analytics.total_visits(), Some(1));
+    }
 }
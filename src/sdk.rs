@@ -35,28 +35,43 @@
 //! # Ok(())
 //! # }
 //! ```
-use futures_util::Stream;
-use log::debug;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures_util::{Stream, StreamExt};
+use log::{debug, warn};
 use rustls::{ClientConfig, RootCertStore};
+use serde::Deserialize;
 use serde_json::Value;
-use std::{fs, path::Path, time::Duration};
+use std::{
+    borrow::Cow, collections::HashMap, fs, io::Read, path::Path, sync::Once, time::Duration,
+};
+use url::Url;
 
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, Response};
+use tokio::{sync::mpsc, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
 
 use crate::{
-    CertsResponse, DAnalyticsResponse, DAuditResponse, DiscardResponse, ListDomainResponse,
-    ListResponse, ListResult, ManifestResponse, MetadataResponse, PlansResponse, RollResponse,
-    TeardownResponse,
+    BustResponse, Certs, CertsResponse, DAnalyticsResponse, DAuditResponse, DAuditResponseValue,
+    DiscardResponse, DomainSettings, ListDomainResponse, ListResponse, ListResult, ManifestDiff,
+    ManifestResponse, MetadataResponse, PlanChangePreview, PlansResponse, PublishResult,
+    RedirectRule, RollResponse, SettingsResponse, TeardownResponse,
     config::Config,
     error::{ApiErrorResponse, SurgeError},
     responses::{AccountResponse, LoginResponse},
-    types::{Auth, Event},
+    stream::{PublishOptions, ndjson_event_stream_with},
+    types::{
+        Auth, CacheDiagnostics, DeployStatus, DeploySummary, DnsRecordInput, Event, LiveHeaders,
+        PollBackoff, RetryBudget, SyncState, ZoneRecordInput,
+    },
+    utils::{epoch_to_datetime, normalize_domain, poll_with_backoff},
 };
 
 /// SDK for interacting with the Surge API.
 ///
 /// Encapsulates an HTTP client and configuration for managing domains, publishing projects,
 /// and handling account operations.
+#[derive(Clone)]
 pub struct SurgeSdk {
     /// Configuration settings for the SDK, including the API endpoint and timeout settings.
     pub config: Config,
@@ -71,7 +86,8 @@ impl SurgeSdk {
     /// * `config` - Configuration settings for the SDK.
     ///
     /// # Returns
-    /// A `Result` containing the `SurgeSdk` or a `SurgeError` if the HTTP client cannot be built.
+    /// A `Result` containing the `SurgeSdk`, or a `SurgeError` if the configuration is invalid
+    /// (see [`Config::validate`]) or the HTTP client cannot be built.
     ///
     /// # Example
     /// ```
@@ -80,10 +96,15 @@ impl SurgeSdk {
     /// let sdk = SurgeSdk::new(config).unwrap();
     /// ```
     pub fn new(config: Config) -> Result<Self, SurgeError> {
+        config.validate()?;
+        if config.insecure {
+            warn!("TLS certificate validation is disabled (insecure mode)");
+        } else if config.accept_invalid_hostnames {
+            warn!("TLS hostname verification is disabled");
+        }
+
         let client = if cfg!(feature = "rustls") {
-            rustls::crypto::ring::default_provider()
-                .install_default()
-                .map_err(|e| SurgeError::Http(format!("Failed to set crypto provider: {:?}", e)))?;
+            install_crypto_provider();
 
             let mut root_store = RootCertStore::empty();
             root_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
@@ -95,6 +116,7 @@ impl SurgeSdk {
             Client::builder()
                 .timeout(Duration::from_secs(config.timeout_secs))
                 .danger_accept_invalid_certs(config.insecure)
+                .danger_accept_invalid_hostnames(config.accept_invalid_hostnames)
                 .use_preconfigured_tls(tls_confg)
                 .build()
                 .map_err(|e| SurgeError::Http(e.to_string()))?
@@ -102,6 +124,7 @@ impl SurgeSdk {
             Client::builder()
                 .timeout(Duration::from_secs(config.timeout_secs))
                 .danger_accept_invalid_certs(config.insecure)
+                .danger_accept_invalid_hostnames(config.accept_invalid_hostnames)
                 .build()
                 .map_err(|e| SurgeError::Http(e.to_string()))?
         };
@@ -109,6 +132,126 @@ impl SurgeSdk {
         Ok(Self { config, client })
     }
 
+    /// Returns a `ClientBuilder` with the timeout and TLS validation settings of `config`, for
+    /// one-off clients that need settings the shared client cannot take per request.
+    pub(crate) fn client_builder(&self) -> reqwest::ClientBuilder {
+        Client::builder()
+            .timeout(Duration::from_secs(self.config.timeout_secs))
+            .danger_accept_invalid_certs(self.config.insecure)
+            .danger_accept_invalid_hostnames(self.config.accept_invalid_hostnames)
+    }
+
+    /// Sends a request, reporting it to `config.metrics_recorder` with the `metrics` feature.
+    ///
+    /// # Arguments
+    /// * `req` - The request to send.
+    ///
+    /// # Returns
+    /// The `reqwest::Response`, or the `reqwest::Error` if the request could not be sent.
+    pub(crate) async fn send_request(&self, req: RequestBuilder) -> reqwest::Result<Response> {
+        #[cfg(feature = "metrics")]
+        {
+            let (client, request) = req.build_split();
+            let request = request?;
+            let method = request.method().to_string();
+            let path = request.url().path().to_string();
+            let start = std::time::Instant::now();
+            let result = client.execute(request).await;
+            self.config.metrics_recorder.record_request(
+                &method,
+                &path,
+                result.as_ref().ok().map(|res| res.status().as_u16()),
+                start.elapsed(),
+            );
+            result
+        }
+        #[cfg(not(feature = "metrics"))]
+        req.send().await
+    }
+
+    /// Sends a request, retrying transient failures up to `config.max_retries` times.
+    ///
+    /// Retries on `429`, `502`, `503`, and `504` responses and on connection or timeout errors,
+    /// sleeping for the longer of the exponential backoff and any `Retry-After` header. A
+    /// `Retry-After` beyond `RETRY_AFTER_MAX` is not waited for: that response is returned as
+    /// is. Requests whose body cannot be cloned are sent once.
+    ///
+    /// # Arguments
+    /// * `req` - The request to send.
+    ///
+    /// # Returns
+    /// A `Result` containing the last `reqwest::Response` or a `SurgeError`.
+    async fn send_with_retry(&self, req: RequestBuilder) -> Result<Response, SurgeError> {
+        self.send_with_budget(req, &RetryBudget::unlimited()).await
+    }
+
+    /// Sends a request like `send_with_retry`, also spending `budget` on every retry.
+    ///
+    /// # Arguments
+    /// * `req` - The request to send.
+    /// * `budget` - The retries left for the whole operation; no retry is made once exhausted.
+    ///
+    /// # Returns
+    /// A `Result` containing the last `reqwest::Response` or a `SurgeError`.
+    async fn send_with_budget(
+        &self,
+        req: RequestBuilder,
+        budget: &RetryBudget,
+    ) -> Result<Response, SurgeError> {
+        let mut attempt = 0;
+        loop {
+            let Some(attempt_req) = req.try_clone() else {
+                return Ok(self.send_request(req).await?);
+            };
+            let backoff = retry_backoff(attempt);
+            let delay = match self.send_request(attempt_req).await {
+                Ok(res)
+                    if is_retryable(res.status())
+                        && retry_after(res.headers()).is_some_and(|ra| ra > RETRY_AFTER_MAX) =>
+                {
+                    warn!(
+                        "Request failed with status {}, not retrying: Retry-After exceeds {:?}",
+                        res.status(),
+                        RETRY_AFTER_MAX
+                    );
+                    return Ok(res);
+                }
+                Ok(res)
+                    if attempt < self.config.max_retries
+                        && is_retryable(res.status())
+                        && budget.try_spend() =>
+                {
+                    let delay = retry_after(res.headers()).map_or(backoff, |ra| ra.max(backoff));
+                    warn!(
+                        "Request failed with status {}, retrying in {:?} ({}/{})",
+                        res.status(),
+                        delay,
+                        attempt + 1,
+                        self.config.max_retries
+                    );
+                    delay
+                }
+                Err(e)
+                    if attempt < self.config.max_retries
+                        && (e.is_connect() || e.is_timeout())
+                        && budget.try_spend() =>
+                {
+                    warn!(
+                        "Request failed: {}, retrying in {:?} ({}/{})",
+                        e,
+                        backoff,
+                        attempt + 1,
+                        self.config.max_retries
+                    );
+                    backoff
+                }
+                result => return Ok(result?),
+            };
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
     /// Fetches account information.
     ///
     /// # Arguments
@@ -120,13 +263,16 @@ impl SurgeSdk {
         let url = self.config.endpoint.join("account")?;
         let req = self.apply_auth(self.client.get(url), auth);
         debug!("Request sended to account: {:#?}", req);
-        let res = req.send().await?.json().await?;
+        let res = self.send_with_retry(req).await?.json().await?;
         debug!("Response received: {:#?}", res);
         Ok(res)
     }
 
     /// Lists domains, optionally filtered by a specific domain.
     ///
+    /// When the caller knows statically which listing it wants, `list_global` and
+    /// `list_domain` return the concrete type directly.
+    ///
     /// # Arguments
     /// * `domain` - Optional domain to filter the list.
     /// * `auth` - Authentication credentials.
@@ -134,28 +280,123 @@ impl SurgeSdk {
     /// # Returns
     /// A `Result` containing a `ListResponse` or a `SurgeError`.
     pub async fn list(&self, domain: Option<&str>, auth: &Auth) -> Result<ListResult, SurgeError> {
-        let path = match domain {
-            Some(d) => format!("{}/list", d),
-            None => "list".to_string(),
-        };
-        let url = self.config.endpoint.join(&path)?;
+        match domain {
+            Some(domain) => Ok(ListResult::Domain(self.list_domain(domain, auth).await?)),
+            None => Ok(ListResult::Global(self.list_global(auth).await?)),
+        }
+    }
+
+    /// Lists every deployment on the account.
+    ///
+    /// # Arguments
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing the `ListResponse` of each deployment, or a `SurgeError`.
+    pub async fn list_global(&self, auth: &Auth) -> Result<Vec<ListResponse>, SurgeError> {
+        self.fetch_list("list", auth).await
+    }
+
+    /// Lists the revisions of a domain.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing the domain's `ListDomainResponse`, or a `SurgeError`.
+    pub async fn list_domain(
+        &self,
+        domain: &str,
+        auth: &Auth,
+    ) -> Result<ListDomainResponse, SurgeError> {
+        self.fetch_list(&format!("{}/list", normalize_domain(domain)), auth)
+            .await
+    }
+
+    /// Fetches and parses a listing endpoint.
+    async fn fetch_list<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        auth: &Auth,
+    ) -> Result<T, SurgeError> {
+        let url = self.config.endpoint.join(path)?;
         let req = self.apply_auth(self.client.get(url), auth);
         debug!("Request sent to list: {:#?}", req);
 
-        let res = req.send().await?;
-        let body_text = res.text().await?;
+        let res = self.send_with_retry(req).await?;
+        let status = res.status();
+        let body_text = response_text(res).await?;
         debug!("response raw: {:?}", body_text);
+        if !status.is_success() {
+            return Err(api_error(status, &body_text));
+        }
 
-        match domain {
-            Some(_) => {
-                let domain_response: ListDomainResponse = serde_json::from_str(&body_text)?;
-                Ok(ListResult::Domain(domain_response))
-            }
-            None => {
-                let global_response: Vec<ListResponse> = serde_json::from_str(&body_text)?;
-                Ok(ListResult::Global(global_response))
-            }
+        Ok(serde_json::from_str(&body_text)?)
+    }
+
+    /// Lists every deployment on the account, decoding them one at a time.
+    ///
+    /// Unlike `list(None, auth)`, which buffers the whole response, the JSON array is parsed
+    /// incrementally as the body arrives, so memory use is bounded by the largest single entry
+    /// rather than the size of the account.
+    ///
+    /// # Arguments
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing a stream of `ListResponse`s, or a `SurgeError` if the request fails.
+    /// A malformed or truncated body ends the stream with `SurgeError::Json`.
+    pub async fn list_stream(
+        &self,
+        auth: &Auth,
+    ) -> Result<impl Stream<Item = Result<ListResponse, SurgeError>> + use<>, SurgeError> {
+        let url = self.config.endpoint.join("list")?;
+        let req = self.apply_auth(self.client.get(url), auth);
+        debug!("Request sent to list_stream: {:#?}", req);
+
+        let res = self.send_with_retry(req).await?;
+        let status = res.status();
+        if !status.is_success() {
+            let body_text = response_text(res).await?;
+            return Err(api_error(status, &body_text));
+        }
+
+        let bytes = res
+            .bytes_stream()
+            .map(|chunk| chunk.map_err(SurgeError::from));
+        Ok(json_array_items(Box::pin(bytes)))
+    }
+
+    /// Lists the names of every domain on the account.
+    ///
+    /// The server has no name-only listing, so this fetches the same list as `list(None, auth)`
+    /// but only decodes the `domain` field of each entry, skipping the rest of the payload.
+    ///
+    /// # Arguments
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing the domain names or a `SurgeError`.
+    pub async fn domain_names(&self, auth: &Auth) -> Result<Vec<String>, SurgeError> {
+        #[derive(Deserialize)]
+        struct DomainName {
+            domain: String,
+        }
+
+        let url = self.config.endpoint.join("list")?;
+        let req = self.apply_auth(self.client.get(url), auth);
+        debug!("Request sent to list: {:#?}", req);
+
+        let res = self.send_with_retry(req).await?;
+        let status = res.status();
+        let body_text = response_text(res).await?;
+        if !status.is_success() {
+            return Err(api_error(status, &body_text));
         }
+
+        let names: Vec<DomainName> = serde_json::from_str(&body_text)?;
+        Ok(names.into_iter().map(|name| name.domain).collect())
     }
 
     /// Deletes the account.
@@ -169,7 +410,7 @@ impl SurgeSdk {
         let url = self.config.endpoint.join("account")?;
         let req = self.apply_auth(self.client.delete(url), auth);
         debug!("Request sent to nuke: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_request(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         Ok(())
@@ -188,10 +429,10 @@ impl SurgeSdk {
         domain: &str,
         auth: &Auth,
     ) -> Result<TeardownResponse, SurgeError> {
-        let url = self.config.endpoint.join(domain)?;
+        let url = self.config.endpoint.join(&normalize_domain(domain))?;
         let req = self.apply_auth(self.client.delete(url), auth);
         debug!("Request sent to teardown: {:#?}", &req);
-        let response = req.send().await?;
+        let response = self.send_request(req).await?;
         let body_text = response.text().await?;
         debug!("response raw: {:?}", body_text);
 
@@ -210,28 +451,71 @@ impl SurgeSdk {
         let url = self.config.endpoint.join("token")?;
         let req = self.apply_auth(self.client.post(url), auth);
         debug!("Request sent to login: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_request(req).await?;
         let status = res.status();
-        let body_text = res.text().await?;
+        let body_text = response_text(res).await?;
         debug!("response raw: {:?}", body_text);
 
         if status.is_success() {
             let login_response: LoginResponse = serde_json::from_str(&body_text)?;
             Ok(login_response)
         } else {
-            // Try to deserialize the error response
-            match serde_json::from_str::<ApiErrorResponse>(&body_text) {
-                Ok(api_error) => Err(SurgeError::Api {
-                    status: api_error.status,
-                    message: api_error.errors.join("; "),
-                    details: api_error.details,
-                }),
-                Err(_) => Err(SurgeError::Http(format!(
-                    "HTTP error: status {}, body: {}",
-                    status, body_text
-                ))),
-            }
+            Err(api_error(status, &body_text))
+        }
+    }
+
+    /// Fetches when the token used by `auth` expires.
+    ///
+    /// The expiry is read from the account endpoint, which reports it as `tokenExpiresAt` (or
+    /// `token_expires_at`) when tokens have a TTL.
+    ///
+    /// # Arguments
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing the expiry time, `None` if the server does not report one, or a
+    /// `SurgeError`.
+    pub async fn token_expiry(&self, auth: &Auth) -> Result<Option<DateTime<Utc>>, SurgeError> {
+        let url = self.config.endpoint.join("account")?;
+        let req = self.apply_auth(self.client.get(url), auth);
+        debug!("Request sent to token_expiry: {:#?}", req);
+        let res = self.send_with_retry(req).await?;
+        let status = res.status();
+        let body_text = response_text(res).await?;
+        debug!("response raw: {:?}", body_text);
+
+        if !status.is_success() {
+            return Err(api_error(status, &body_text));
         }
+        let body: Value = serde_json::from_str(&body_text)?;
+        Ok(token_expiry_from(&body))
+    }
+
+    /// Checks whether the token used by `auth` expires within `within`.
+    ///
+    /// Lets long-running services rotate their token before it expires instead of failing
+    /// mid-operation. A token without a reported expiry never expires soon.
+    ///
+    /// # Arguments
+    /// * `within` - How far ahead to look.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing `true` if the token has expired or expires within `within`, or a
+    /// `SurgeError`.
+    pub async fn token_expires_soon(
+        &self,
+        within: Duration,
+        auth: &Auth,
+    ) -> Result<bool, SurgeError> {
+        let Some(expiry) = self.token_expiry(auth).await? else {
+            return Ok(false);
+        };
+        let within = chrono::Duration::from_std(within).unwrap_or(chrono::Duration::MAX);
+        let deadline = Utc::now()
+            .checked_add_signed(within)
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+        Ok(expiry <= deadline)
     }
 
     /// Publishes a project directory to a domain.
@@ -258,6 +542,283 @@ impl SurgeSdk {
         crate::stream::publish(self, project_path, domain, auth, headers, argv).await
     }
 
+    /// Publishes a project directory to a domain using explicit `PublishOptions`.
+    ///
+    /// Delegates to `stream::publish_with`.
+    ///
+    /// # Arguments
+    /// * `project_path` - Path to the project directory.
+    /// * `domain` - Target domain for publishing.
+    /// * `auth` - Authentication credentials.
+    /// * `options` - Options controlling packaging and request headers.
+    ///
+    /// # Returns
+    /// A `Result` containing a stream of `Event`s or a `SurgeError`.
+    pub async fn publish_with(
+        &self,
+        project_path: &Path,
+        domain: &str,
+        auth: &Auth,
+        options: &PublishOptions,
+    ) -> Result<impl Stream<Item = Result<Event, SurgeError>>, SurgeError> {
+        crate::stream::publish_with(self, project_path, domain, auth, options).await
+    }
+
+    /// Publishes a project directory in a background task, forwarding its events to a channel.
+    ///
+    /// For event loops, GUI frameworks, and actor systems that prefer owning a task handle over
+    /// polling a stream. The publish runs like [`publish_with`](Self::publish_with) on a task
+    /// spawned on the current Tokio runtime, and every event, including a failing one, is sent
+    /// to `tx`. Must be called from within a Tokio runtime.
+    ///
+    /// Cancelling `cancel` drops the publish request and its event stream, which aborts the
+    /// upload if it is still in progress, and the task returns `SurgeError::Event`. A deploy
+    /// the server has already received in full may still go live. If the receiver of `tx` is
+    /// dropped, events are no longer forwarded but the publish runs to completion; cancel it to
+    /// stop it.
+    ///
+    /// # Arguments
+    /// * `project_path` - Path to the project directory.
+    /// * `domain` - Target domain for publishing.
+    /// * `auth` - Authentication credentials.
+    /// * `options` - Options controlling packaging and request headers.
+    /// * `tx` - The channel the events are sent to.
+    /// * `cancel` - A token that aborts the publish when cancelled.
+    ///
+    /// # Returns
+    /// The `JoinHandle` of the task, resolving to the `DeploySummary` of the publish, the first
+    /// `SurgeError` it failed with, or `SurgeError::Event` if it was cancelled.
+    pub fn publish_to_channel(
+        &self,
+        project_path: &Path,
+        domain: &str,
+        auth: &Auth,
+        options: &PublishOptions,
+        tx: mpsc::Sender<Result<Event, SurgeError>>,
+        cancel: CancellationToken,
+    ) -> JoinHandle<Result<DeploySummary, SurgeError>> {
+        let sdk = self.clone();
+        let project_path = project_path.to_path_buf();
+        let domain = domain.to_string();
+        let auth = auth.clone();
+        let options = options.clone();
+        let cancelled =
+            |domain: &str| SurgeError::Event(format!("publish to {} was cancelled", domain));
+
+        tokio::spawn(async move {
+            let events = tokio::select! {
+                biased;
+                _ = cancel.cancelled() => return Err(cancelled(&domain)),
+                events = sdk.publish_with(&project_path, &domain, &auth, &options) => events?,
+            };
+            let mut events = std::pin::pin!(events);
+            let mut summary = DeploySummary::new(&domain);
+            let mut forwarding = true;
+            loop {
+                let event = tokio::select! {
+                    biased;
+                    _ = cancel.cancelled() => return Err(cancelled(&domain)),
+                    event = events.next() => event,
+                };
+                let Some(event) = event else {
+                    break;
+                };
+                let failure = match &event {
+                    Ok(event) => {
+                        summary.record(event);
+                        None
+                    }
+                    Err(e) => Some(e.clone()),
+                };
+                if forwarding {
+                    let sent = tokio::select! {
+                        biased;
+                        _ = cancel.cancelled() => return Err(cancelled(&domain)),
+                        sent = tx.send(event) => sent,
+                    };
+                    if sent.is_err() {
+                        debug!(
+                            "Event channel closed; publishing {} without forwarding",
+                            domain
+                        );
+                        forwarding = false;
+                    }
+                }
+                if let Some(e) = failure {
+                    return Err(e);
+                }
+            }
+            debug!("Deploy summary: {:?}", summary);
+            Ok(summary)
+        })
+    }
+
+    /// Returns `options` with the client-wide publish settings of the `Config` applied.
+    ///
+    /// `Config::allow_heavy_dirs` enables `PublishOptions::allow_heavy_dirs`.
+    pub(crate) fn publish_options<'a>(
+        &self,
+        options: &'a PublishOptions,
+    ) -> Cow<'a, PublishOptions> {
+        if self.config.allow_heavy_dirs && !options.allow_heavy_dirs {
+            Cow::Owned(options.clone().with_allow_heavy_dirs(true))
+        } else {
+            Cow::Borrowed(options)
+        }
+    }
+
+    /// Returns the URL that a publish to `domain` is sent to.
+    ///
+    /// Delegates to `stream::publish_url`.
+    ///
+    /// # Arguments
+    /// * `domain` - Target domain for publishing.
+    ///
+    /// # Returns
+    /// A `Result` containing the target `Url` or a `SurgeError`.
+    pub fn publish_url(&self, domain: &str) -> Result<Url, SurgeError> {
+        crate::stream::publish_url(self, domain)
+    }
+
+    /// Publishes the contents of a `.zip`, `.tar`, or `.tar.gz` archive to a domain.
+    ///
+    /// Delegates to `stream::publish_archive`. The archive is read in memory, so it does not need
+    /// to be extracted first; archives that unpack to more than
+    /// [`MAX_ARCHIVE_UNPACKED_BYTES`](crate::stream::MAX_ARCHIVE_UNPACKED_BYTES) are rejected.
+    ///
+    /// # Arguments
+    /// * `archive` - Path to the archive file.
+    /// * `domain` - Target domain for publishing.
+    /// * `auth` - Authentication credentials.
+    /// * `options` - Options controlling packaging and request headers.
+    ///
+    /// # Returns
+    /// A `Result` containing a stream of `Event`s or a `SurgeError`.
+    pub async fn publish_archive(
+        &self,
+        archive: &Path,
+        domain: &str,
+        auth: &Auth,
+        options: &PublishOptions,
+    ) -> Result<impl Stream<Item = Result<Event, SurgeError>>, SurgeError> {
+        crate::stream::publish_archive(self, archive, domain, auth, options).await
+    }
+
+    /// Returns the URLs a deployment to `domain` is expected to be served at.
+    ///
+    /// Delegates to `utils::deploy_urls`; useful for a confirmation prompt before publishing.
+    ///
+    /// # Arguments
+    /// * `domain` - Target domain for publishing.
+    ///
+    /// # Returns
+    /// The `https://` URLs, with the given domain first.
+    pub fn deploy_urls(&self, domain: &str) -> Vec<String> {
+        crate::utils::deploy_urls(domain)
+    }
+
+    /// Publishes a single file, such as a standalone HTML page, to a domain.
+    ///
+    /// Delegates to `stream::publish_file`. The file is served at `/<file name>`.
+    ///
+    /// # Arguments
+    /// * `file` - Path to the file.
+    /// * `domain` - Target domain for publishing.
+    /// * `auth` - Authentication credentials.
+    /// * `options` - Options controlling packaging and request headers.
+    ///
+    /// # Returns
+    /// A `Result` containing a stream of `Event`s or a `SurgeError`.
+    pub async fn publish_file(
+        &self,
+        file: &Path,
+        domain: &str,
+        auth: &Auth,
+        options: &PublishOptions,
+    ) -> Result<impl Stream<Item = Result<Event, SurgeError>>, SurgeError> {
+        crate::stream::publish_file(self, file, domain, auth, options).await
+    }
+
+    /// Publishes a redirect-only deployment that sends visitors of `domain` to `target`.
+    ///
+    /// No project directory is needed: a minimal site is generated whose `index.html` and
+    /// `200.html` redirect with a meta refresh. The domain's `redirect` setting, which lets the
+    /// server answer with a proper `status` redirect, is applied before the upload through
+    /// [`PublishOptions::with_settings`], so the new revision never goes live without it.
+    ///
+    /// # Arguments
+    /// * `domain` - The domain to redirect from.
+    /// * `target` - The URL or domain to redirect to. `https://` is assumed if no scheme is given.
+    /// * `status` - The redirect status code; must be a 3xx code.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing the `DeploySummary` or a `SurgeError`. Returns
+    /// `SurgeError::Config` if `status` is not a 3xx code, and the `SurgeError::Api` of the
+    /// settings request, without uploading anything, if the server rejects the redirect.
+    pub async fn publish_redirect(
+        &self,
+        domain: &str,
+        target: &str,
+        status: u16,
+        auth: &Auth,
+    ) -> Result<DeploySummary, SurgeError> {
+        if !(300..=399).contains(&status) {
+            return Err(SurgeError::Config(format!(
+                "Invalid redirect status {}: expected a 3xx code",
+                status
+            )));
+        }
+        let target = if target.contains("://") {
+            target.to_string()
+        } else {
+            format!("https://{}", target)
+        };
+        let target_url = url::Url::parse(&target)?;
+
+        let dir = tempfile::tempdir()?;
+        let page = redirect_page(target_url.as_str());
+        fs::write(dir.path().join("index.html"), &page)?;
+        fs::write(dir.path().join("200.html"), &page)?;
+
+        let settings = DomainSettings {
+            redirect: Some(vec![RedirectRule {
+                from: "/".to_string(),
+                to: target_url.to_string(),
+                status,
+            }]),
+            ..Default::default()
+        };
+        let options = PublishOptions::default().with_settings(settings);
+        let events = crate::stream::publish_with(self, dir.path(), domain, auth, &options).await?;
+        crate::stream::summarize(domain, events).await
+    }
+
+    /// Publishes a project directory as a resumable, multi-part upload.
+    ///
+    /// Delegates to `stream::publish_multipart`. The Surge API has no multi-part protocol yet,
+    /// so this currently performs a regular single-request upload, exactly like `publish`.
+    ///
+    /// # Arguments
+    /// * `project_path` - Path to the project directory.
+    /// * `domain` - Target domain for publishing.
+    /// * `auth` - Authentication credentials.
+    /// * `headers` - Optional custom HTTP headers.
+    /// * `argv` - Optional command-line arguments.
+    ///
+    /// # Returns
+    /// A `Result` containing a stream of `Event`s or a `SurgeError`.
+    pub async fn publish_multipart(
+        &self,
+        project_path: &Path,
+        domain: &str,
+        auth: &Auth,
+        headers: Option<Vec<(String, String)>>,
+        argv: Option<&[String]>,
+    ) -> Result<impl Stream<Item = Result<Event, SurgeError>>, SurgeError> {
+        crate::stream::publish_multipart(self, project_path, domain, auth, headers, argv).await
+    }
+
     /// Publishes a work-in-progress version of a project to a preview domain.
     ///
     /// Delegates to `stream::publish_wip` for tarball creation and streaming.
@@ -291,10 +852,13 @@ impl SurgeSdk {
     /// # Returns
     /// A `Result` indicating success or a `SurgeError`.
     pub async fn rollback(&self, domain: &str, auth: &Auth) -> Result<RollResponse, SurgeError> {
-        let url = self.config.endpoint.join(&format!("{}/rollback", domain))?;
+        let url = self
+            .config
+            .endpoint
+            .join(&format!("{}/rollback", normalize_domain(domain)))?;
         let req = self.apply_auth(self.client.post(url), auth);
         debug!("Request sent to rollback: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_request(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         let rollback_response: RollResponse = serde_json::from_str(&body_text)?;
@@ -310,10 +874,13 @@ impl SurgeSdk {
     /// # Returns
     /// A `Result` indicating success or a `SurgeError`.
     pub async fn rollfore(&self, domain: &str, auth: &Auth) -> Result<RollResponse, SurgeError> {
-        let url = self.config.endpoint.join(&format!("{}/rollfore", domain))?;
+        let url = self
+            .config
+            .endpoint
+            .join(&format!("{}/rollfore", normalize_domain(domain)))?;
         let req = self.apply_auth(self.client.post(url), auth);
         debug!("Request sent to rollfore: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_request(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         let rollfore_response: RollResponse = serde_json::from_str(&body_text)?;
@@ -336,13 +903,13 @@ impl SurgeSdk {
         auth: &Auth,
     ) -> Result<(), SurgeError> {
         let path = match revision {
-            Some(rev) => format!("{}/rev/{}", domain, rev),
-            None => format!("{}/rev", domain),
+            Some(rev) => format!("{}/rev/{}", normalize_domain(domain), rev),
+            None => format!("{}/rev", normalize_domain(domain)),
         };
         let url = self.config.endpoint.join(&path)?;
         let req = self.apply_auth(self.client.put(url), auth);
         debug!("Request sent to cutover: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_request(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         Ok(())
@@ -365,7 +932,7 @@ impl SurgeSdk {
         let url = self.config.endpoint.join(&format!("{}/rev", revision))?;
         let req = self.apply_auth(self.client.delete(url), auth);
         debug!("Request sent to discard: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_request(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
 
@@ -382,20 +949,247 @@ impl SurgeSdk {
     /// # Returns
     /// A `Result` containing a `DCertsResponse` or a `SurgeError`.
     pub async fn certs(&self, domain: &str, auth: &Auth) -> Result<CertsResponse, SurgeError> {
-        let url = self.config.endpoint.join(&format!("{}/certs", domain))?;
+        self.certs_with_budget(domain, auth, &RetryBudget::unlimited())
+            .await
+    }
+
+    /// Fetches SSL certificates for a domain, spending `budget` on retries.
+    async fn certs_with_budget(
+        &self,
+        domain: &str,
+        auth: &Auth,
+        budget: &RetryBudget,
+    ) -> Result<CertsResponse, SurgeError> {
+        let url = self
+            .config
+            .endpoint
+            .join(&format!("{}/certs", normalize_domain(domain)))?;
         let req = self.apply_auth(self.client.get(url), auth);
         debug!("Request sent to certs: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_with_budget(req, budget).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         let certs_response: CertsResponse = serde_json::from_str(&body_text)?;
         Ok(certs_response)
     }
 
-    /// Fetches metadata for a domain or specific revision.
+    /// Fetches the certificates of every domain on the account.
+    ///
+    /// Domains are listed first, then their certificates are fetched concurrently, with at most
+    /// eight `certs` requests in flight at once. The first request to fail cancels the others;
+    /// use `all_certs_collect` to keep going past failures.
     ///
     /// # Arguments
-    /// * `domain` - The target domain.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing `(domain, certs)` pairs in listing order, or the first `SurgeError`.
+    pub async fn all_certs(&self, auth: &Auth) -> Result<Vec<(String, Vec<Certs>)>, SurgeError> {
+        let domains = self.domain_names(auth).await?;
+        debug!("Fetching certs for {} domains", domains.len());
+
+        try_fetch_many(domains, |domain| async move {
+            Ok(self.certs(&domain, auth).await?.certs)
+        })
+        .await
+    }
+
+    /// Fetches the certificates of every domain on the account, like `all_certs`, without
+    /// stopping at failed requests.
+    ///
+    /// # Arguments
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing each domain with the result of its `certs` request, in listing
+    /// order, or the `SurgeError` of listing the domains.
+    pub async fn all_certs_collect(
+        &self,
+        auth: &Auth,
+    ) -> Result<Vec<(String, Result<Vec<Certs>, SurgeError>)>, SurgeError> {
+        let domains = self.domain_names(auth).await?;
+        debug!("Fetching certs for {} domains", domains.len());
+
+        Ok(fetch_many(domains, |domain| async move {
+            Ok(self.certs(&domain, auth).await?.certs)
+        })
+        .await)
+    }
+
+    /// Polls a domain's certificates until a valid one is issued.
+    ///
+    /// Certificate provisioning after `ssl` or `encrypt` is asynchronous, so `certs` is polled,
+    /// with delays growing per `backoff`, until it returns a certificate whose `not_after` lies
+    /// in the future.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
+    /// * `auth` - Authentication credentials.
+    /// * `timeout` - How long to wait for the certificate.
+    /// * `backoff` - The delays between polls, e.g. `PollBackoff::default()`.
+    ///
+    /// # Returns
+    /// A `Result` containing the issued `Cert`, or `SurgeError::Network` if none appears before
+    /// the timeout elapses.
+    pub async fn wait_for_cert(
+        &self,
+        domain: &str,
+        auth: &Auth,
+        timeout: Duration,
+        backoff: PollBackoff,
+    ) -> Result<Certs, SurgeError> {
+        self.wait_for_cert_with_budget(domain, auth, timeout, backoff, &RetryBudget::unlimited())
+            .await
+    }
+
+    /// Polls a domain's certificates like `wait_for_cert`, bounding the total attempts.
+    ///
+    /// Every poll after the first, and every retry of a failed `certs` request, spends one retry
+    /// from `budget`; see [`RetryBudget`] for how this interacts with request timeouts.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
+    /// * `auth` - Authentication credentials.
+    /// * `timeout` - How long to wait for the certificate.
+    /// * `backoff` - The delays between polls, e.g. `PollBackoff::default()`.
+    /// * `budget` - The retries allowed for the whole wait, possibly shared with other calls.
+    ///
+    /// # Returns
+    /// A `Result` containing the issued `Cert`, or `SurgeError::Network` if none appears before
+    /// the timeout elapses or the budget is exhausted.
+    pub async fn wait_for_cert_with_budget(
+        &self,
+        domain: &str,
+        auth: &Auth,
+        timeout: Duration,
+        backoff: PollBackoff,
+        budget: &RetryBudget,
+    ) -> Result<Certs, SurgeError> {
+        let polled = poll_with_backoff(timeout, backoff, budget, || async {
+            let certs = self.certs_with_budget(domain, auth, budget).await?;
+            let cert = certs
+                .certs
+                .into_iter()
+                .find(|cert| cert.not_after > Utc::now());
+            Ok(cert.ok_or(()))
+        })
+        .await?;
+
+        match polled {
+            Ok(cert) => {
+                debug!("Certificate issued for {}: {}", domain, cert.cert_name);
+                Ok(cert)
+            }
+            Err(()) if budget.is_exhausted() => Err(SurgeError::Network(format!(
+                "retry budget exhausted waiting for a certificate for {}",
+                domain
+            ))),
+            Err(()) => Err(SurgeError::Network(format!(
+                "timed out waiting for a certificate for {}",
+                domain
+            ))),
+        }
+    }
+
+    /// Polls a domain's DNS records until an expected record appears.
+    ///
+    /// Records added with `dns_add` or `dns_add_record` take time to propagate, so `dns` is
+    /// polled, with delays growing per `backoff`, until it lists a record with the same type,
+    /// name, and value as `expected`. Types are compared case-insensitively, and a name also
+    /// matches its fully qualified form (`www` and `www.example.com`). The TTL is not compared.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
+    /// * `expected` - The record to wait for.
+    /// * `timeout` - How long to wait for the record.
+    /// * `backoff` - The delays between polls, e.g. `PollBackoff::default()`.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// `Ok(())` once the record is listed, or `SurgeError::Network` with the last observed
+    /// records if it does not appear before the timeout elapses.
+    pub async fn wait_for_dns(
+        &self,
+        domain: &str,
+        expected: &DnsRecordInput,
+        timeout: Duration,
+        backoff: PollBackoff,
+        auth: &Auth,
+    ) -> Result<(), SurgeError> {
+        self.wait_for_dns_with_budget(
+            domain,
+            expected,
+            timeout,
+            backoff,
+            &RetryBudget::unlimited(),
+            auth,
+        )
+        .await
+    }
+
+    /// Polls a domain's DNS records like `wait_for_dns`, bounding the total attempts.
+    ///
+    /// Every poll after the first, and every retry of a failed `dns` request, spends one retry
+    /// from `budget`; see [`RetryBudget`] for how this interacts with request timeouts.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
+    /// * `expected` - The record to wait for.
+    /// * `timeout` - How long to wait for the record.
+    /// * `backoff` - The delays between polls, e.g. `PollBackoff::default()`.
+    /// * `budget` - The retries allowed for the whole wait, possibly shared with other calls.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// `Ok(())` once the record is listed, or `SurgeError::Network` with the last observed
+    /// records if it does not appear before the timeout elapses or the budget is exhausted.
+    pub async fn wait_for_dns_with_budget(
+        &self,
+        domain: &str,
+        expected: &DnsRecordInput,
+        timeout: Duration,
+        backoff: PollBackoff,
+        budget: &RetryBudget,
+        auth: &Auth,
+    ) -> Result<(), SurgeError> {
+        let polled = poll_with_backoff(timeout, backoff, budget, || async {
+            let records = self.dns_with_budget(domain, auth, budget).await?;
+            if has_dns_record(&records, domain, expected) {
+                Ok(Ok(()))
+            } else {
+                Ok(Err(records))
+            }
+        })
+        .await?;
+
+        match polled {
+            Ok(()) => {
+                debug!(
+                    "DNS record {} {} is live for {}",
+                    expected.record_type, expected.name, domain
+                );
+                Ok(())
+            }
+            Err(records) => Err(SurgeError::Network(format!(
+                "{} waiting for the {} record {} -> {} on {}; last seen: {}",
+                if budget.is_exhausted() {
+                    "retry budget exhausted"
+                } else {
+                    "timed out"
+                },
+                expected.record_type,
+                expected.name,
+                expected.value,
+                domain,
+                records
+            ))),
+        }
+    }
+
+    /// Fetches metadata for a domain or specific revision.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
     /// * `revision` - Optional revision to fetch metadata for.
     /// * `auth` - Authentication credentials.
     ///
@@ -408,19 +1202,104 @@ impl SurgeSdk {
         auth: &Auth,
     ) -> Result<MetadataResponse, SurgeError> {
         let path = match revision {
-            Some(rev) => format!("{}/{}/metadata.json", domain, rev),
-            None => format!("{}/metadata.json", domain),
+            Some(rev) => format!("{}/{}/metadata.json", normalize_domain(domain), rev),
+            None => format!("{}/metadata.json", normalize_domain(domain)),
         };
         let url = self.config.endpoint.join(&path)?;
         let req = self.apply_auth(self.client.get(url), auth);
         debug!("Request sent to metadata: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_with_retry(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         let metadata_response: MetadataResponse = serde_json::from_str(&body_text)?;
         Ok(metadata_response)
     }
 
+    /// Fetches the URLs, certificates, and instances serving a domain or specific revision.
+    ///
+    /// Unlike [`metadata`](Self::metadata), which describes the uploaded files, this returns
+    /// where the revision is served, e.g. the live URLs after a rollback.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
+    /// * `revision` - Optional revision to fetch the info for; the current one by default.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing a `PublishResult` or a `SurgeError`.
+    pub async fn deployment_info(
+        &self,
+        domain: &str,
+        revision: Option<&str>,
+        auth: &Auth,
+    ) -> Result<PublishResult, SurgeError> {
+        let path = match revision {
+            Some(rev) => format!("{}/{}/info.json", normalize_domain(domain), rev),
+            None => format!("{}/info.json", normalize_domain(domain)),
+        };
+        let url = self.config.endpoint.join(&path)?;
+        let req = self.apply_auth(self.client.get(url), auth);
+        debug!("Request sent to deployment_info: {:#?}", req);
+        let res = self.send_with_retry(req).await?;
+        let status = res.status();
+        let body_text = response_text(res).await?;
+        debug!("response raw: {:?}", body_text);
+
+        if !status.is_success() {
+            return Err(api_error(status, &body_text));
+        }
+        Ok(serde_json::from_str(&body_text)?)
+    }
+
+    /// Fetches the current metadata of several domains concurrently.
+    ///
+    /// At most `MAX_CONCURRENT_REQUESTS` requests are in flight at once. The first request to
+    /// fail ends the call: every request runs under a shared `CancellationToken`, which is
+    /// cancelled so the requests still in flight are abandoned, and no new ones are started. Use
+    /// `metadata_many_collect` to fetch every domain regardless of failures.
+    ///
+    /// # Arguments
+    /// * `domains` - The domains to fetch metadata for.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing each domain with its metadata, in the order of `domains`, or the
+    /// first `SurgeError`.
+    pub async fn metadata_many<D: AsRef<str>>(
+        &self,
+        domains: &[D],
+        auth: &Auth,
+    ) -> Result<Vec<(String, MetadataResponse)>, SurgeError> {
+        debug!("Fetching metadata for {} domains", domains.len());
+        let domains = domains.iter().map(|domain| domain.as_ref().to_string());
+        try_fetch_many(domains, |domain| async move {
+            self.metadata(&domain, None, auth).await
+        })
+        .await
+    }
+
+    /// Fetches the current metadata of several domains concurrently, like `metadata_many`,
+    /// returning failures alongside the successes instead of stopping at the first one.
+    ///
+    /// # Arguments
+    /// * `domains` - The domains to fetch metadata for.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// Each domain with its result, in the order of `domains`.
+    pub async fn metadata_many_collect<D: AsRef<str>>(
+        &self,
+        domains: &[D],
+        auth: &Auth,
+    ) -> Vec<(String, Result<MetadataResponse, SurgeError>)> {
+        debug!("Fetching metadata for {} domains", domains.len());
+        let domains = domains.iter().map(|domain| domain.as_ref().to_string());
+        fetch_many(domains, |domain| async move {
+            self.metadata(&domain, None, auth).await
+        })
+        .await
+    }
+
     /// Fetches the manifest for a domain or specific revision.
     ///
     /// # Arguments
@@ -436,18 +1315,42 @@ impl SurgeSdk {
         revision: Option<&str>,
         auth: &Auth,
     ) -> Result<ManifestResponse, SurgeError> {
+        let (manifest_response, _) = self
+            .manifest_with_diagnostics(domain, revision, auth)
+            .await?;
+        Ok(manifest_response)
+    }
+
+    /// Fetches the manifest for a domain or specific revision, along with cache diagnostics.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
+    /// * `revision` - Optional revision to fetch the manifest for.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing the `ManifestResponse` and the `CacheDiagnostics` parsed from the
+    /// response headers, or a `SurgeError`.
+    pub async fn manifest_with_diagnostics(
+        &self,
+        domain: &str,
+        revision: Option<&str>,
+        auth: &Auth,
+    ) -> Result<(ManifestResponse, CacheDiagnostics), SurgeError> {
         let path = match revision {
-            Some(rev) => format!("{}/{}/manifest.json", domain, rev),
-            None => format!("{}/manifest.json", domain),
+            Some(rev) => format!("{}/{}/manifest.json", normalize_domain(domain), rev),
+            None => format!("{}/manifest.json", normalize_domain(domain)),
         };
         let url = self.config.endpoint.join(&path)?;
         let req = self.apply_auth(self.client.get(url), auth);
         debug!("Request sent to manifest: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_with_retry(req).await?;
+        let diagnostics = CacheDiagnostics::from_headers(res.headers());
+        debug!("Cache diagnostics: {:?}", diagnostics);
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         let manifest_response: ManifestResponse = serde_json::from_str(&body_text)?;
-        Ok(manifest_response)
+        Ok((manifest_response, diagnostics))
     }
 
     /// Fetches the file manifest for a domain (alias for `manifest` with no revision).
@@ -462,6 +1365,278 @@ impl SurgeSdk {
         self.manifest(domain, None, auth).await
     }
 
+    /// Downloads a file of the live deployment of a domain as a stream of bytes.
+    ///
+    /// The file is fetched from `<endpoint>/<domain>/files/<path>`. Each segment of `path` is
+    /// percent-encoded.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
+    /// * `path` - The file path within the deployment, e.g. `css/site.css`.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing a stream of the file's bytes, or a `SurgeError` if `path` is
+    /// invalid or the server does not return the file. A non-2xx response is always a
+    /// `SurgeError::Api` carrying the HTTP status, e.g. `404` for a missing file.
+    pub async fn download_file(
+        &self,
+        domain: &str,
+        path: &str,
+        auth: &Auth,
+    ) -> Result<impl Stream<Item = Result<Bytes, SurgeError>> + use<>, SurgeError> {
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        if segments.is_empty() || segments.iter().any(|s| *s == "." || *s == "..") {
+            return Err(SurgeError::Config(format!("Invalid file path: {}", path)));
+        }
+
+        let mut url = self
+            .config
+            .endpoint
+            .join(&format!("{}/files/", normalize_domain(domain)))?;
+        url.path_segments_mut()
+            .map_err(|_| SurgeError::Config("Endpoint cannot be a base URL".to_string()))?
+            .pop_if_empty()
+            .extend(segments);
+
+        let req = self.apply_auth(self.client.get(url), auth);
+        debug!("Request sent to download_file: {:#?}", req);
+        let res = self.send_with_retry(req).await?;
+        let status = res.status();
+        if !status.is_success() {
+            let body_text = response_text(res).await?;
+            return Err(match api_error(status, &body_text) {
+                SurgeError::Http(_) => SurgeError::api(
+                    Some(status.as_u16()),
+                    format!(
+                        "Failed to download {} from {}: status {}",
+                        path, domain, status
+                    ),
+                    Value::String(body_text),
+                ),
+                error => error,
+            });
+        }
+
+        Ok(res
+            .bytes_stream()
+            .map(|chunk| chunk.map_err(SurgeError::from)))
+    }
+
+    /// Checks whether a domain has a deployment, without downloading anything.
+    ///
+    /// Sends an authenticated `HEAD` request for the domain's metadata. The live site itself is
+    /// not probed, since surge.sh answers unknown domains with a placeholder page and an
+    /// unauthenticated probe cannot tell a missing project from a missing `index.html`.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// `Ok(true)` for a 2xx response, `Ok(false)` for a 404, or `SurgeError::Api` carrying the
+    /// status for any other response.
+    pub async fn exists(&self, domain: &str, auth: &Auth) -> Result<bool, SurgeError> {
+        let url = self
+            .config
+            .endpoint
+            .join(&format!("{}/metadata.json", normalize_domain(domain)))?;
+        let req = self.apply_auth(self.client.head(url), auth);
+        debug!("Request sent to exists: {:#?}", req);
+        let res = self.send_with_retry(req).await?;
+
+        let status = res.status();
+        debug!("exists {}: {}", domain, status);
+        match status {
+            status if status.is_success() => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            status => Err(SurgeError::Api {
+                status: Some(status.as_u16()),
+                message: format!("Unexpected status checking {}: {}", domain, status),
+                details: Value::Null,
+            }),
+        }
+    }
+
+    /// Fetches the status and headers served by the live deployment of a domain.
+    ///
+    /// Sends an unauthenticated `HEAD` request to `https://<domain><path>`, so CI can check that
+    /// settings such as HSTS or `Cache-Control` took effect. A `domain` that already includes a
+    /// scheme (e.g. `http://localhost:8080`) is used as given.
+    ///
+    /// # Arguments
+    /// * `domain` - The live domain.
+    /// * `path` - The path to request, e.g. `/index.html`.
+    /// * `follow_redirects` - Whether to follow redirects (up to 10) to the final response.
+    ///
+    /// # Returns
+    /// A `Result` containing the `LiveHeaders` of the final response, or a `SurgeError` if the
+    /// request fails. Non-success statuses are reported in `LiveHeaders::status`, not as errors.
+    pub async fn inspect_live_headers(
+        &self,
+        domain: &str,
+        path: &str,
+        follow_redirects: bool,
+    ) -> Result<LiveHeaders, SurgeError> {
+        let base = if domain.contains("://") {
+            Url::parse(domain)?
+        } else {
+            Url::parse(&format!("https://{}", domain))?
+        };
+        let url = base.join(path)?;
+
+        let client = if follow_redirects {
+            self.client.clone()
+        } else {
+            self.client_builder()
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .map_err(|e| SurgeError::Http(e.to_string()))?
+        };
+        let req = client.head(url);
+        debug!("Request sent to inspect_live_headers: {:#?}", req);
+        let res = self.send_with_retry(req).await?;
+
+        let mut headers: HashMap<String, String> = HashMap::new();
+        for (name, value) in res.headers() {
+            let value = String::from_utf8_lossy(value.as_bytes());
+            headers
+                .entry(name.as_str().to_string())
+                .and_modify(|joined| {
+                    joined.push_str(", ");
+                    joined.push_str(&value);
+                })
+                .or_insert_with(|| value.into_owned());
+        }
+
+        Ok(LiveHeaders {
+            url: res.url().clone(),
+            status: res.status().as_u16(),
+            headers,
+        })
+    }
+
+    /// Fetches the file manifest for a domain, along with cache diagnostics.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing the `ManifestResponse` and its `CacheDiagnostics`, or a `SurgeError`.
+    pub async fn files_with_diagnostics(
+        &self,
+        domain: &str,
+        auth: &Auth,
+    ) -> Result<(ManifestResponse, CacheDiagnostics), SurgeError> {
+        self.manifest_with_diagnostics(domain, None, auth).await
+    }
+
+    /// Compares a local project against the live deployment of a domain.
+    ///
+    /// The equivalent of `git status` for deployments: the project's manifest is computed
+    /// locally with `stream::compute_manifest` and compared with the live `manifest`. When they
+    /// differ, the project is [`SyncState::Ahead`] if a differing local file was modified after
+    /// the live deployment was uploaded, and [`SyncState::Behind`] otherwise.
+    ///
+    /// # Arguments
+    /// * `project_path` - Path to the project directory.
+    /// * `domain` - The target domain.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing the `DeployStatus` or a `SurgeError`.
+    pub async fn status(
+        &self,
+        project_path: &Path,
+        domain: &str,
+        auth: &Auth,
+    ) -> Result<DeployStatus, SurgeError> {
+        self.status_with(project_path, domain, auth, &PublishOptions::default())
+            .await
+    }
+
+    /// Compares a local project, as it would be published with `options`, against the live
+    /// deployment of a domain. See [`status`](Self::status).
+    ///
+    /// # Arguments
+    /// * `project_path` - Path to the project directory.
+    /// * `domain` - The target domain.
+    /// * `auth` - Authentication credentials.
+    /// * `options` - The publish options that determine which files are included.
+    ///
+    /// # Returns
+    /// A `Result` containing the `DeployStatus` or a `SurgeError`.
+    pub async fn status_with(
+        &self,
+        project_path: &Path,
+        domain: &str,
+        auth: &Auth,
+        options: &PublishOptions,
+    ) -> Result<DeployStatus, SurgeError> {
+        let local = crate::stream::compute_manifest(project_path, &self.publish_options(options))?;
+        let (live, metadata) = futures_util::try_join!(
+            self.manifest(domain, None, auth),
+            self.metadata(domain, None, auth)
+        )?;
+        let diff = ManifestDiff::between(&live, &local);
+
+        let state = if diff.is_empty() {
+            SyncState::InSync
+        } else {
+            let newest_change = diff
+                .added
+                .iter()
+                .chain(&diff.changed)
+                .filter_map(|path| {
+                    fs::metadata(project_path.join(path.trim_start_matches('/')))
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                })
+                .max()
+                .map(DateTime::<Utc>::from);
+            if newest_change.is_some_and(|modified| modified > metadata.uploaded_at()) {
+                SyncState::Ahead
+            } else {
+                SyncState::Behind
+            }
+        };
+        debug!(
+            "Status of {} against {}: {:?}",
+            project_path.display(),
+            domain,
+            state
+        );
+
+        Ok(DeployStatus {
+            domain: domain.to_string(),
+            state,
+            diff,
+        })
+    }
+
+    /// Compares the manifests of two revisions of a domain.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
+    /// * `from_revision` - The earlier revision.
+    /// * `to_revision` - The later revision, or `None` for the current deployment.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing the `ManifestDiff` between the two revisions or a `SurgeError`.
+    pub async fn diff_revisions(
+        &self,
+        domain: &str,
+        from_revision: &str,
+        to_revision: Option<&str>,
+        auth: &Auth,
+    ) -> Result<ManifestDiff, SurgeError> {
+        let old = self.manifest(domain, Some(from_revision), auth).await?;
+        let new = self.manifest(domain, to_revision, auth).await?;
+        Ok(ManifestDiff::between(&old, &new))
+    }
+
     /// Updates configuration settings for a domain.
     ///
     /// # Arguments
@@ -477,15 +1652,51 @@ impl SurgeSdk {
         settings: Value,
         auth: &Auth,
     ) -> Result<(), SurgeError> {
-        let url = self.config.endpoint.join(&format!("{}/settings", domain))?;
+        let url = self
+            .config
+            .endpoint
+            .join(&format!("{}/settings", normalize_domain(domain)))?;
         let req = self.apply_auth(self.client.put(url), auth).json(&settings);
         debug!("Request sent to config: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_request(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         Ok(())
     }
 
+    /// Applies typed settings to a domain.
+    ///
+    /// Unlike `config`, a non-success response is returned as an error.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
+    /// * `settings` - The settings to apply.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` indicating success or a `SurgeError`.
+    pub async fn apply_settings(
+        &self,
+        domain: &str,
+        settings: &DomainSettings,
+        auth: &Auth,
+    ) -> Result<(), SurgeError> {
+        let url = self
+            .config
+            .endpoint
+            .join(&format!("{}/settings", normalize_domain(domain)))?;
+        let req = self.apply_auth(self.client.put(url), auth).json(settings);
+        debug!("Request sent to settings: {:#?}", req);
+        let res = self.send_request(req).await?;
+        let status = res.status();
+        let body_text = response_text(res).await?;
+        debug!("response raw: {:?}", body_text);
+        if !status.is_success() {
+            return Err(api_error(status, &body_text));
+        }
+        Ok(())
+    }
+
     /// Fetches DNS records for a domain.
     ///
     /// # Arguments
@@ -495,10 +1706,24 @@ impl SurgeSdk {
     /// # Returns
     /// A `Result` containing a `serde_json::Value` or a `SurgeError`.
     pub async fn dns(&self, domain: &str, auth: &Auth) -> Result<Value, SurgeError> {
-        let url = self.config.endpoint.join(&format!("{}/dns", domain))?;
+        self.dns_with_budget(domain, auth, &RetryBudget::unlimited())
+            .await
+    }
+
+    /// Fetches DNS records for a domain, spending `budget` on retries.
+    async fn dns_with_budget(
+        &self,
+        domain: &str,
+        auth: &Auth,
+        budget: &RetryBudget,
+    ) -> Result<Value, SurgeError> {
+        let url = self
+            .config
+            .endpoint
+            .join(&format!("{}/dns", normalize_domain(domain)))?;
         let req = self.apply_auth(self.client.get(url), auth);
         debug!("Request sent to dns: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_with_budget(req, budget).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         let dns_response: Value = serde_json::from_str(&body_text)?;
@@ -520,15 +1745,37 @@ impl SurgeSdk {
         record: Value,
         auth: &Auth,
     ) -> Result<(), SurgeError> {
-        let url = self.config.endpoint.join(&format!("{}/dns", domain))?;
+        let url = self
+            .config
+            .endpoint
+            .join(&format!("{}/dns", normalize_domain(domain)))?;
         let req = self.apply_auth(self.client.post(url), auth).json(&record);
         debug!("Request sent to dns_add: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_request(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         Ok(())
     }
 
+    /// Adds a typed DNS record for a domain.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
+    /// * `record` - The validated DNS record.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` indicating success or a `SurgeError`.
+    pub async fn dns_add_record(
+        &self,
+        domain: &str,
+        record: &DnsRecordInput,
+        auth: &Auth,
+    ) -> Result<(), SurgeError> {
+        self.dns_add(domain, serde_json::to_value(record)?, auth)
+            .await
+    }
+
     /// Removes a DNS record for a domain.
     ///
     /// # Arguments
@@ -542,10 +1789,10 @@ impl SurgeSdk {
         let url = self
             .config
             .endpoint
-            .join(&format!("{}/dns/{}", domain, id))?;
+            .join(&format!("{}/dns/{}", normalize_domain(domain), id))?;
         let req = self.apply_auth(self.client.delete(url), auth);
         debug!("Request sent to dns_remove: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_request(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         Ok(())
@@ -560,10 +1807,13 @@ impl SurgeSdk {
     /// # Returns
     /// A `Result` containing a `serde_json::Value` or a `SurgeError`.
     pub async fn zone(&self, domain: &str, auth: &Auth) -> Result<Value, SurgeError> {
-        let url = self.config.endpoint.join(&format!("{}/zone", domain))?;
+        let url = self
+            .config
+            .endpoint
+            .join(&format!("{}/zone", normalize_domain(domain)))?;
         let req = self.apply_auth(self.client.get(url), auth);
         debug!("Request sent to zone: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_with_retry(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         let zone_response: Value = serde_json::from_str(&body_text)?;
@@ -585,15 +1835,37 @@ impl SurgeSdk {
         record: Value,
         auth: &Auth,
     ) -> Result<(), SurgeError> {
-        let url = self.config.endpoint.join(&format!("{}/zone", domain))?;
+        let url = self
+            .config
+            .endpoint
+            .join(&format!("{}/zone", normalize_domain(domain)))?;
         let req = self.apply_auth(self.client.post(url), auth).json(&record);
         debug!("Request sent to zone_add: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_request(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         Ok(())
     }
 
+    /// Adds a typed zone record for a domain.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
+    /// * `record` - The validated zone record.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` indicating success or a `SurgeError`.
+    pub async fn zone_add_record(
+        &self,
+        domain: &str,
+        record: &ZoneRecordInput,
+        auth: &Auth,
+    ) -> Result<(), SurgeError> {
+        self.zone_add(domain, serde_json::to_value(record)?, auth)
+            .await
+    }
+
     /// Removes a zone record for a domain.
     ///
     /// # Arguments
@@ -604,13 +1876,13 @@ impl SurgeSdk {
     /// # Returns
     /// A `Result` indicating success or a `SurgeError`.
     pub async fn zone_remove(&self, domain: &str, id: &str, auth: &Auth) -> Result<(), SurgeError> {
-        let url = self
-            .config
-            .endpoint
-            .join(&format!("{}/zone/{}", domain, id))?;
+        let url =
+            self.config
+                .endpoint
+                .join(&format!("{}/zone/{}", normalize_domain(domain), id))?;
         let req = self.apply_auth(self.client.delete(url), auth);
         debug!("Request sent to zone_remove: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_request(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         Ok(())
@@ -623,15 +1895,27 @@ impl SurgeSdk {
     /// * `auth` - Authentication credentials.
     ///
     /// # Returns
-    /// A `Result` indicating success or a `SurgeError`.
-    pub async fn bust(&self, domain: &str, auth: &Auth) -> Result<(), SurgeError> {
-        let url = self.config.endpoint.join(&format!("{}/cache", domain))?;
+    /// A `Result` containing the server's `BustResponse` confirmation, or a `SurgeError` if the
+    /// server rejects the request. An empty response body yields a default `BustResponse`.
+    pub async fn bust(&self, domain: &str, auth: &Auth) -> Result<BustResponse, SurgeError> {
+        let url = self
+            .config
+            .endpoint
+            .join(&format!("{}/cache", normalize_domain(domain)))?;
         let req = self.apply_auth(self.client.delete(url), auth);
         debug!("Request sent to bust: {:#?}", req);
-        let res = req.send().await?;
-        let body_text = res.text().await?;
+        let res = self.send_request(req).await?;
+        let status = res.status();
+        let body_text = response_text(res).await?;
         debug!("response raw: {:?}", body_text);
-        Ok(())
+
+        if !status.is_success() {
+            return Err(api_error(status, &body_text));
+        }
+        if body_text.trim().is_empty() {
+            return Ok(BustResponse::default());
+        }
+        Ok(serde_json::from_str(&body_text)?)
     }
 
     /// Fetches account statistics.
@@ -645,7 +1929,7 @@ impl SurgeSdk {
         let url = self.config.endpoint.join("stats")?;
         let req = self.apply_auth(self.client.get(url), auth);
         debug!("Request sent to stats: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_with_retry(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         let stats_response: Value = serde_json::from_str(&body_text)?;
@@ -668,16 +1952,68 @@ impl SurgeSdk {
         let url = self
             .config
             .endpoint
-            .join(&format!("{}/analytics", domain))?;
+            .join(&format!("{}/analytics", normalize_domain(domain)))?;
         let req = self.apply_auth(self.client.get(url), auth);
         debug!("Request sent to analytics: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_with_retry(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         let analytics_response: DAnalyticsResponse = serde_json::from_str(&body_text)?;
         Ok(analytics_response)
     }
 
+    /// Fetches analytics data scoped to a single revision of a domain.
+    ///
+    /// Requests `{domain}/{revision}/analytics`, mirroring the revision-scoped `metadata` and
+    /// `manifest` paths. Revision-scoped analytics are not documented by Surge, so the server may
+    /// not support them; a rejected request is returned as `SurgeError::Api` carrying the status
+    /// and raw body rather than falling back to domain-wide data. Use [`analytics`](Self::analytics)
+    /// for the whole domain.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
+    /// * `revision` - The revision to scope the analytics to.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing a `DAnalyticsResponse` or a `SurgeError`.
+    pub async fn analytics_for_revision(
+        &self,
+        domain: &str,
+        revision: &str,
+        auth: &Auth,
+    ) -> Result<DAnalyticsResponse, SurgeError> {
+        if revision.is_empty() {
+            return Err(SurgeError::Config("Revision cannot be empty".to_string()));
+        }
+        let url = self.config.endpoint.join(&format!(
+            "{}/{}/analytics",
+            normalize_domain(domain),
+            revision
+        ))?;
+        let req = self.apply_auth(self.client.get(url), auth);
+        debug!("Request sent to analytics_for_revision: {:#?}", req);
+        let res = self.send_with_retry(req).await?;
+        let status = res.status();
+        let body_text = response_text(res).await?;
+        debug!("response raw: {:?}", body_text);
+        if !status.is_success() {
+            return Err(match api_error(status, &body_text) {
+                SurgeError::Http(_) => SurgeError::api(
+                    Some(status.as_u16()),
+                    format!(
+                        "Revision-scoped analytics rejected for {} at revision {}: status {}",
+                        domain, revision, status
+                    ),
+                    Value::String(body_text),
+                ),
+                error => error,
+            });
+        }
+        let analytics_response: DAnalyticsResponse = serde_json::from_str(&body_text)?;
+        Ok(analytics_response)
+    }
+
     /// Fetches usage data for a domain.
     ///
     /// # Arguments
@@ -687,10 +2023,13 @@ impl SurgeSdk {
     /// # Returns
     /// A `Result` containing a `DAnalyticsResponse` or a `SurgeError`.
     pub async fn usage(&self, domain: &str, auth: &Auth) -> Result<DAnalyticsResponse, SurgeError> {
-        let url = self.config.endpoint.join(&format!("{}/usage", domain))?;
+        let url = self
+            .config
+            .endpoint
+            .join(&format!("{}/usage", normalize_domain(domain)))?;
         let req = self.apply_auth(self.client.get(url), auth);
         debug!("Request sent to usage: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_with_retry(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         let usage_response = serde_json::from_str(&body_text)?;
@@ -706,16 +2045,59 @@ impl SurgeSdk {
     /// # Returns
     /// A `Result` containing a `DAuditResponse` or a `SurgeError`.
     pub async fn audit(&self, domain: &str, auth: &Auth) -> Result<DAuditResponse, SurgeError> {
-        let url = self.config.endpoint.join(&format!("{}/audit", domain))?;
+        let url = self
+            .config
+            .endpoint
+            .join(&format!("{}/audit", normalize_domain(domain)))?;
         let req = self.apply_auth(self.client.get(url), auth);
         debug!("Request sent to audit: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_with_retry(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         let audit_response = serde_json::from_str(&body_text)?;
         Ok(audit_response)
     }
 
+    /// Fetches the audit log entries of a domain recorded at or after `since`.
+    ///
+    /// The server has no date filter, so the full log is fetched and filtered by each entry's
+    /// key, which is its timestamp (epoch seconds or milliseconds, or RFC 3339). Entries whose key
+    /// is not a timestamp are skipped.
+    ///
+    /// # Arguments
+    /// * `domain` - The target domain.
+    /// * `since` - The earliest timestamp to include.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing the matching `(key, entry)` pairs, oldest first, or a `SurgeError`.
+    pub async fn audit_since(
+        &self,
+        domain: &str,
+        since: DateTime<Utc>,
+        auth: &Auth,
+    ) -> Result<Vec<(String, DAuditResponseValue)>, SurgeError> {
+        let audit = self.audit(domain, auth).await?;
+
+        let mut entries: Vec<_> = audit
+            .into_iter()
+            .filter_map(|(key, entry)| match audit_timestamp(&key) {
+                Some(timestamp) => Some((timestamp, key, entry)),
+                None => {
+                    debug!("Skipping audit entry without a timestamp key: {}", key);
+                    None
+                }
+            })
+            .filter(|(timestamp, _, _)| *timestamp >= since)
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+
+        Ok(entries
+            .into_iter()
+            .map(|(_, key, entry)| (key, entry))
+            .collect())
+    }
+
     /// Invites collaborators to a domain.
     ///
     /// # Arguments
@@ -734,12 +2116,12 @@ impl SurgeSdk {
         let url = self
             .config
             .endpoint
-            .join(&format!("{}/collaborators", domain))?;
+            .join(&format!("{}/collaborators", normalize_domain(domain)))?;
         let req = self.apply_auth(self.client.post(url), auth).json(&emails);
         debug!("Request sent to invite: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_request(req).await?;
         let status = res.status();
-        let body_text = res.text().await?;
+        let body_text = response_text(res).await?;
         debug!("response raw: {:?}", body_text);
         if status.is_success() {
             Ok(true)
@@ -766,12 +2148,12 @@ impl SurgeSdk {
         let url = self
             .config
             .endpoint
-            .join(&format!("{}/collaborators", domain))?;
+            .join(&format!("{}/collaborators", normalize_domain(domain)))?;
         let req = self.apply_auth(self.client.delete(url), auth).json(&emails);
         debug!("Request sent to revoke: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_request(req).await?;
         let status = res.status();
-        let body_text = res.text().await?;
+        let body_text = response_text(res).await?;
         debug!("response raw: {:?}", body_text);
         if status.is_success() {
             Ok(true)
@@ -792,12 +2174,52 @@ impl SurgeSdk {
         let url = self.config.endpoint.join("plan")?;
         let req = self.apply_auth(self.client.put(url), auth).json(&plan);
         debug!("Request sent to plan: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_request(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         Ok(())
     }
 
+    /// Fetches the quoted cost of switching the account to another plan, without changing it.
+    ///
+    /// Lets a UI show the prorated charge before calling `plan`. Not every server offers
+    /// previews: a `404`, `405`, or `501` response is reported as `SurgeError::Api` saying so.
+    ///
+    /// # Arguments
+    /// * `plan_id` - The id of the plan to switch to, as listed by `plans`.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing the `PlanChangePreview`, or a `SurgeError` if the server rejects
+    /// the request or does not support previews.
+    pub async fn preview_plan_change(
+        &self,
+        plan_id: &str,
+        auth: &Auth,
+    ) -> Result<PlanChangePreview, SurgeError> {
+        let url = self.config.endpoint.join("plan/preview")?;
+        let req = self
+            .apply_auth(self.client.post(url), auth)
+            .json(&serde_json::json!({ "plan": plan_id }));
+        debug!("Request sent to preview_plan_change: {:#?}", req);
+        let res = self.send_request(req).await?;
+        let status = res.status();
+        let body_text = response_text(res).await?;
+        debug!("response raw: {:?}", body_text);
+
+        if matches!(status.as_u16(), 404 | 405 | 501) {
+            return Err(SurgeError::api(
+                Some(status.as_u16()),
+                "plan change previews are not supported by this server",
+                Value::String(body_text),
+            ));
+        }
+        if !status.is_success() {
+            return Err(api_error(status, &body_text));
+        }
+        Ok(serde_json::from_str(&body_text)?)
+    }
+
     /// Updates the payment card for the account.
     ///
     /// # Arguments
@@ -810,7 +2232,7 @@ impl SurgeSdk {
         let url = self.config.endpoint.join("card")?;
         let req = self.apply_auth(self.client.put(url), auth).json(&card);
         debug!("Request sent to card: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_request(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         Ok(())
@@ -830,13 +2252,13 @@ impl SurgeSdk {
         auth: &Auth,
     ) -> Result<PlansResponse, SurgeError> {
         let path = match domain {
-            Some(d) => format!("{}/plans", d),
+            Some(d) => format!("{}/plans", normalize_domain(d)),
             None => "plans".to_string(),
         };
         let url = self.config.endpoint.join(&path)?;
         let req = self.apply_auth(self.client.get(url), auth);
         debug!("Request sent to plans: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_with_retry(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         let plans_response: PlansResponse = serde_json::from_str(&body_text)?;
@@ -854,15 +2276,232 @@ impl SurgeSdk {
     /// A `Result` indicating success or a `SurgeError`.
     pub async fn ssl(&self, domain: &str, pem_path: &Path, auth: &Auth) -> Result<(), SurgeError> {
         let pem_data = fs::read(pem_path).map_err(|e| SurgeError::Io(e.to_string()))?;
-        let url = self.config.endpoint.join(&format!("{}/certs", domain))?;
+        let url = self
+            .config
+            .endpoint
+            .join(&format!("{}/certs", normalize_domain(domain)))?;
         let req = self.apply_auth(self.client.post(url), auth).body(pem_data);
         debug!("Request sent to ssl: {:#?}", req);
-        let res = req.send().await?;
+        let res = self.send_request(req).await?;
         let body_text = res.text().await?;
         debug!("response raw: {:?}", body_text);
         Ok(())
     }
 
+    /// Requests an SSL certificate for a domain, streaming the provisioning events.
+    ///
+    /// The response is decoded with the same NDJSON machinery as a publish; certificate
+    /// updates arrive as `Event::Cert`. Use [`encrypt_and_wait`](Self::encrypt_and_wait) to
+    /// wait until the certificate is issued instead.
+    ///
+    /// # Arguments
+    /// * `domain` - The domain to provision a certificate for.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing a stream of `Event`s, or a `SurgeError` if the request fails or
+    /// the server rejects it.
+    pub async fn encrypt(
+        &self,
+        domain: &str,
+        auth: &Auth,
+    ) -> Result<impl Stream<Item = Result<Event, SurgeError>> + use<>, SurgeError> {
+        let url = self
+            .config
+            .endpoint
+            .join(&format!("{}/encrypt", normalize_domain(domain)))?;
+        let req = self
+            .apply_auth(self.client.put(url), auth)
+            .header("Accept", "application/ndjson");
+        debug!("Request sent to encrypt: {:#?}", req);
+        let res = self.send_request(req).await?;
+        let status = res.status();
+        if !status.is_success() {
+            let body_text = response_text(res).await?;
+            return Err(api_error(status, &body_text));
+        }
+        Ok(ndjson_event_stream_with(
+            res,
+            self.config.lossy_event_decoding,
+        ))
+    }
+
+    /// Requests an SSL certificate for a domain and waits until it is issued.
+    ///
+    /// Drains the event stream of [`encrypt`](Self::encrypt), then polls the domain's
+    /// certificates like [`wait_for_cert`](Self::wait_for_cert).
+    ///
+    /// # Arguments
+    /// * `domain` - The domain to provision a certificate for.
+    /// * `auth` - Authentication credentials.
+    /// * `timeout` - How long to wait for the certificate once provisioning was requested.
+    /// * `backoff` - The delays between polls, e.g. `PollBackoff::default()`.
+    ///
+    /// # Returns
+    /// A `Result` containing the issued `Cert`, the first `SurgeError` yielded by the event
+    /// stream, or `SurgeError::Network` if no certificate appears before the timeout elapses.
+    pub async fn encrypt_and_wait(
+        &self,
+        domain: &str,
+        auth: &Auth,
+        timeout: Duration,
+        backoff: PollBackoff,
+    ) -> Result<Certs, SurgeError> {
+        let mut events = std::pin::pin!(self.encrypt(domain, auth).await?);
+        while let Some(event) = events.next().await {
+            debug!("encrypt event: {}", event?);
+        }
+        self.wait_for_cert(domain, auth, timeout, backoff).await
+    }
+
+    /// Lists the custom domain aliases of a deployment.
+    ///
+    /// Aliases are added and removed with [`add_alias`](Self::add_alias) and
+    /// [`remove_alias`](Self::remove_alias). The response may be a list of names, a list of
+    /// objects with a `domain` field, or either wrapped in an `aliases` key.
+    ///
+    /// # Arguments
+    /// * `domain` - The deployment whose aliases are listed (e.g. `my-site.surge.sh`).
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing the alias domain names or a `SurgeError`.
+    pub async fn aliases(&self, domain: &str, auth: &Auth) -> Result<Vec<String>, SurgeError> {
+        let url = self
+            .config
+            .endpoint
+            .join(&format!("{}/aliases", normalize_domain(domain)))?;
+        let req = self.apply_auth(self.client.get(url), auth);
+        debug!("Request sent to aliases: {:#?}", req);
+        let res = self.send_with_retry(req).await?;
+        let status = res.status();
+        let body_text = response_text(res).await?;
+        debug!("response raw: {:?}", body_text);
+
+        if !status.is_success() {
+            return Err(api_error(status, &body_text));
+        }
+        let body: Value = serde_json::from_str(&body_text)?;
+        alias_names(&body)
+    }
+
+    /// Adds a custom domain alias to a deployment.
+    ///
+    /// A thin wrapper around [`add_domain_alias`](Self::add_domain_alias), kept next to
+    /// [`aliases`](Self::aliases) and [`remove_alias`](Self::remove_alias).
+    ///
+    /// # Arguments
+    /// * `domain` - The deployment the alias should point at (e.g. `my-site.surge.sh`).
+    /// * `alias` - The custom domain to add (e.g. `example.com`).
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing the updated `SettingsResponse` for the deployment or a `SurgeError`.
+    pub async fn add_alias(
+        &self,
+        domain: &str,
+        alias: &str,
+        auth: &Auth,
+    ) -> Result<SettingsResponse, SurgeError> {
+        self.add_domain_alias(domain, alias, auth).await
+    }
+
+    /// Removes a custom domain alias from a deployment.
+    ///
+    /// A thin wrapper around [`remove_domain_alias`](Self::remove_domain_alias), kept next to
+    /// [`aliases`](Self::aliases) and [`add_alias`](Self::add_alias).
+    ///
+    /// # Arguments
+    /// * `domain` - The deployment the alias currently points at.
+    /// * `alias` - The custom domain to remove.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing the updated `SettingsResponse` for the deployment or a `SurgeError`.
+    pub async fn remove_alias(
+        &self,
+        domain: &str,
+        alias: &str,
+        auth: &Auth,
+    ) -> Result<SettingsResponse, SurgeError> {
+        self.remove_domain_alias(domain, alias, auth).await
+    }
+
+    /// Associates a custom domain alias with an existing deployment.
+    ///
+    /// Sends `PUT {endpoint}/{base_domain}/aliases/{alias}`, under the deployment's own routes
+    /// like `{base_domain}/settings` and `{base_domain}/certs`; the alias list itself is read
+    /// from `GET {endpoint}/{base_domain}/aliases` by [`aliases`](Self::aliases).
+    ///
+    /// # Arguments
+    /// * `base_domain` - The deployment the alias should point at (e.g. `my-site.surge.sh`).
+    /// * `alias` - The custom domain to associate (e.g. `example.com`).
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing the updated `SettingsResponse` for the deployment or a `SurgeError`.
+    pub async fn add_domain_alias(
+        &self,
+        base_domain: &str,
+        alias: &str,
+        auth: &Auth,
+    ) -> Result<SettingsResponse, SurgeError> {
+        let url = self.config.endpoint.join(&format!(
+            "{}/aliases/{}",
+            normalize_domain(base_domain),
+            normalize_domain(alias)
+        ))?;
+        let req = self.apply_auth(self.client.put(url), auth);
+        debug!("Request sent to add_domain_alias: {:#?}", req);
+        let res = self.send_request(req).await?;
+        let status = res.status();
+        let body_text = response_text(res).await?;
+        debug!("response raw: {:?}", body_text);
+
+        if !status.is_success() {
+            return Err(api_error(status, &body_text));
+        }
+        let settings_response: SettingsResponse = serde_json::from_str(&body_text)?;
+        Ok(settings_response)
+    }
+
+    /// Removes a custom domain alias from a deployment.
+    ///
+    /// Sends `DELETE {endpoint}/{base_domain}/aliases/{alias}`, the counterpart of
+    /// [`add_domain_alias`](Self::add_domain_alias).
+    ///
+    /// # Arguments
+    /// * `base_domain` - The deployment the alias currently points at.
+    /// * `alias` - The custom domain to disassociate.
+    /// * `auth` - Authentication credentials.
+    ///
+    /// # Returns
+    /// A `Result` containing the updated `SettingsResponse` for the deployment or a `SurgeError`.
+    pub async fn remove_domain_alias(
+        &self,
+        base_domain: &str,
+        alias: &str,
+        auth: &Auth,
+    ) -> Result<SettingsResponse, SurgeError> {
+        let url = self.config.endpoint.join(&format!(
+            "{}/aliases/{}",
+            normalize_domain(base_domain),
+            normalize_domain(alias)
+        ))?;
+        let req = self.apply_auth(self.client.delete(url), auth);
+        debug!("Request sent to remove_domain_alias: {:#?}", req);
+        let res = self.send_request(req).await?;
+        let status = res.status();
+        let body_text = response_text(res).await?;
+        debug!("response raw: {:?}", body_text);
+
+        if !status.is_success() {
+            return Err(api_error(status, &body_text));
+        }
+        let settings_response: SettingsResponse = serde_json::from_str(&body_text)?;
+        Ok(settings_response)
+    }
+
     /// Applies authentication to an HTTP request.
     ///
     /// # Arguments
@@ -878,3 +2517,440 @@ impl SurgeSdk {
         }
     }
 }
+
+/// Converts a non-success response body into a `SurgeError`.
+///
+/// Tries to deserialize the body as an `ApiErrorResponse`, falling back to a plain
+/// `SurgeError::Http` carrying the status and raw body.
+fn api_error(status: reqwest::StatusCode, body_text: &str) -> SurgeError {
+    match serde_json::from_str::<ApiErrorResponse>(body_text) {
+        Ok(api_error) => SurgeError::Api {
+            status: api_error.status,
+            message: api_error.errors.join("; "),
+            details: api_error.details,
+        },
+        Err(_) => SurgeError::Http(format!(
+            "HTTP error: status {}, body: {}",
+            status, body_text
+        )),
+    }
+}
+
+/// Reads a response body as text, decompressing it first if it is sent with
+/// `Content-Encoding: gzip`.
+///
+/// reqwest is built without its `gzip` feature, so such bodies are not decoded transparently;
+/// without this, a gzip-encoded error body would reach `api_error` as unreadable bytes. A body
+/// that is labelled gzip but fails to decompress is returned as-is (lossily decoded).
+///
+/// # Arguments
+/// * `res` - The response to read.
+///
+/// # Returns
+/// A `Result` containing the body text or a `SurgeError` if the body cannot be read.
+pub(crate) async fn response_text(res: Response) -> Result<String, SurgeError> {
+    let gzip = res
+        .headers()
+        .get(reqwest::header::CONTENT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.trim().eq_ignore_ascii_case("gzip"));
+    let body = res.bytes().await?;
+    if gzip {
+        let mut text = String::new();
+        match flate2::read::GzDecoder::new(&body[..]).read_to_string(&mut text) {
+            Ok(_) => return Ok(text),
+            Err(e) => warn!("Failed to decompress gzip-encoded response body: {}", e),
+        }
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+/// Extracts the alias names from the body of an aliases listing.
+fn alias_names(body: &Value) -> Result<Vec<String>, SurgeError> {
+    let entries = match body.get("aliases").unwrap_or(body) {
+        Value::Array(entries) => entries,
+        Value::Null => return Ok(Vec::new()),
+        other => {
+            return Err(SurgeError::Json(format!(
+                "expected a list of aliases, got {}",
+                other
+            )));
+        }
+    };
+    entries
+        .iter()
+        .map(|entry| match entry {
+            Value::String(name) => Ok(name.clone()),
+            _ => entry
+                .get("domain")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| SurgeError::Json(format!("unexpected alias entry: {}", entry))),
+        })
+        .collect()
+}
+
+/// Reads the token expiry from an account response, as an RFC 3339 string or an epoch in
+/// seconds or milliseconds.
+fn token_expiry_from(account: &Value) -> Option<DateTime<Utc>> {
+    let expiry = ["tokenExpiresAt", "token_expires_at"]
+        .iter()
+        .find_map(|key| account.get(key))?;
+    match expiry {
+        Value::Number(epoch) => epoch.as_i64().map(epoch_to_datetime),
+        Value::String(text) => audit_timestamp(text),
+        _ => None,
+    }
+}
+
+/// Parses the timestamp key of an audit log entry.
+fn audit_timestamp(key: &str) -> Option<DateTime<Utc>> {
+    match key.parse::<i64>() {
+        Ok(epoch) => Some(epoch_to_datetime(epoch)),
+        Err(_) => DateTime::parse_from_rfc3339(key)
+            .ok()
+            .map(|datetime| datetime.with_timezone(&Utc)),
+    }
+}
+
+/// Renders a minimal HTML page that redirects to `target`.
+fn redirect_page(target: &str) -> String {
+    let target = target.replace('&', "&amp;").replace('"', "&quot;");
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta http-equiv=\"refresh\" content=\"0; url={0}\">\n\
+         <link rel=\"canonical\" href=\"{0}\">\n<title>Redirecting</title>\n</head>\n\
+         <body><a href=\"{0}\">{0}</a></body>\n</html>\n",
+        target
+    )
+}
+
+/// Guards the process-wide installation of the rustls crypto provider.
+static CRYPTO_PROVIDER: Once = Once::new();
+
+/// Installs `ring` as the process-wide rustls crypto provider, exactly once.
+///
+/// Safe to call from several threads at once. A provider installed beforehand, e.g. by the
+/// application, is kept.
+fn install_crypto_provider() {
+    CRYPTO_PROVIDER.call_once(|| {
+        if rustls::crypto::ring::default_provider()
+            .install_default()
+            .is_err()
+        {
+            debug!("A rustls crypto provider is already installed, keeping it");
+        }
+    });
+}
+
+/// What [`json_array_items`] expects next in a JSON array, after any whitespace.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ArrayPosition {
+    /// The opening `[`.
+    Open,
+    /// The first element or the closing `]`.
+    First,
+    /// An element, after a `,`.
+    Element,
+    /// A `,` or the closing `]`, after an element.
+    Separator,
+}
+
+/// How far the element being decoded by [`json_array_items`] has been scanned.
+///
+/// Kept across chunks, so the bytes of an element split over many reads are only scanned once.
+#[derive(Default)]
+struct ElementScan {
+    /// Bytes of the element scanned so far.
+    len: usize,
+    /// Open `{` and `[` of the element.
+    depth: usize,
+    /// Whether the scan is inside a string.
+    in_string: bool,
+    /// Whether the previous byte was a `\` inside a string.
+    escaped: bool,
+}
+
+impl ElementScan {
+    /// Scans `element`, which starts at the first byte of a JSON value, from where the previous
+    /// call stopped.
+    ///
+    /// # Returns
+    /// The length of the value once its end has been seen, or `None` if more bytes are needed.
+    fn advance(&mut self, element: &[u8]) -> Option<usize> {
+        let scalar = !matches!(element.first(), Some(b'{' | b'[' | b'"'));
+        while let Some(&byte) = element.get(self.len) {
+            if self.in_string {
+                match byte {
+                    _ if self.escaped => self.escaped = false,
+                    b'\\' => self.escaped = true,
+                    b'"' => {
+                        self.in_string = false;
+                        if self.depth == 0 {
+                            return Some(self.len + 1);
+                        }
+                    }
+                    _ => {}
+                }
+            } else if scalar {
+                if matches!(byte, b',' | b']' | b' ' | b'\n' | b'\r' | b'\t') {
+                    return Some(self.len);
+                }
+            } else {
+                match byte {
+                    b'"' => self.in_string = true,
+                    b'{' | b'[' => self.depth += 1,
+                    b'}' | b']' => {
+                        self.depth = self.depth.saturating_sub(1);
+                        if self.depth == 0 {
+                            return Some(self.len + 1);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            self.len += 1;
+        }
+        None
+    }
+}
+
+/// The progress of [`json_array_items`] through a JSON array.
+struct JsonArrayState<S> {
+    /// The response body.
+    bytes: S,
+    /// Bytes received; those before `start` have been decoded.
+    buffer: Vec<u8>,
+    /// The offset in `buffer` of the first byte not yet decoded.
+    start: usize,
+    /// What is expected next.
+    position: ArrayPosition,
+    /// The scan of the element starting at `start`.
+    scan: ElementScan,
+}
+
+/// Decodes the elements of a JSON array from a byte stream as they arrive.
+///
+/// Each element is scanned for its end as its bytes arrive and decoded once complete, so the
+/// work stays linear in the size of the body however it is split into chunks.
+///
+/// # Arguments
+/// * `bytes` - The byte stream holding a single JSON array.
+///
+/// # Returns
+/// A stream yielding each element, or `SurgeError::Json` if the body is not a well-formed array,
+/// including elements that are not separated by exactly one `,`.
+fn json_array_items<T, S>(bytes: S) -> impl Stream<Item = Result<T, SurgeError>>
+where
+    T: serde::de::DeserializeOwned,
+    S: Stream<Item = Result<Bytes, SurgeError>> + Unpin,
+{
+    let state = JsonArrayState {
+        bytes,
+        buffer: Vec::new(),
+        start: 0,
+        position: ArrayPosition::Open,
+        scan: ElementScan::default(),
+    };
+    futures_util::stream::try_unfold(state, |mut state| async move {
+        loop {
+            // Skip whitespace, the opening bracket, and separators up to the next element
+            while let Some(&byte) = state.buffer.get(state.start) {
+                match (byte, state.position) {
+                    (b' ' | b'\n' | b'\r' | b'\t', _) => {}
+                    (b'[', ArrayPosition::Open) => state.position = ArrayPosition::First,
+                    (_, ArrayPosition::Open) => {
+                        return Err(SurgeError::Json("expected a JSON array".to_string()));
+                    }
+                    (b']', ArrayPosition::First | ArrayPosition::Separator) => return Ok(None),
+                    (b',', ArrayPosition::Separator) => state.position = ArrayPosition::Element,
+                    (_, ArrayPosition::Separator) => {
+                        return Err(SurgeError::Json(
+                            "expected `,` or `]` after a JSON array element".to_string(),
+                        ));
+                    }
+                    (b',', ArrayPosition::First) | (b',' | b']', ArrayPosition::Element) => {
+                        return Err(SurgeError::Json(
+                            "expected a JSON array element".to_string(),
+                        ));
+                    }
+                    _ => break,
+                }
+                state.start += 1;
+            }
+
+            if state.start < state.buffer.len() {
+                if let Some(len) = state.scan.advance(&state.buffer[state.start..]) {
+                    let end = state.start + len;
+                    let item = serde_json::from_slice(&state.buffer[state.start..end])?;
+                    state.start = end;
+                    state.position = ArrayPosition::Separator;
+                    state.scan = ElementScan::default();
+                    return Ok(Some((item, state)));
+                }
+            }
+
+            match state.bytes.next().await {
+                Some(chunk) => {
+                    // Drop decoded bytes once they make up most of the buffer, so compaction
+                    // stays linear in the size of the body
+                    if state.start > state.buffer.len() / 2 {
+                        state.buffer.drain(..state.start);
+                        state.start = 0;
+                    }
+                    state.buffer.extend_from_slice(&chunk?);
+                }
+                None => {
+                    return Err(SurgeError::Json("unexpected end of JSON array".to_string()));
+                }
+            }
+        }
+    })
+}
+
+/// The maximum number of requests issued concurrently by bulk helpers such as `all_certs` and
+/// `metadata_many`.
+const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// Runs `fetch` for every domain, with at most `MAX_CONCURRENT_REQUESTS` requests in flight,
+/// stopping at the first failure.
+///
+/// Every request runs under one `CancellationToken`. The first failure cancels it, so the
+/// requests still in flight stop without completing, and its error is returned.
+///
+/// # Arguments
+/// * `domains` - The domains to fetch.
+/// * `fetch` - Issues the request for one domain.
+///
+/// # Returns
+/// A `Result` containing each domain with its value, in the order of `domains`, or the first
+/// `SurgeError`.
+async fn try_fetch_many<T, F, Fut>(
+    domains: impl IntoIterator<Item = String>,
+    fetch: F,
+) -> Result<Vec<(String, T)>, SurgeError>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<T, SurgeError>>,
+{
+    let cancel = CancellationToken::new();
+    let mut requests = futures_util::stream::iter(domains.into_iter().enumerate())
+        .map(|(index, domain)| {
+            let request = fetch(domain.clone());
+            let cancel = &cancel;
+            async move { (index, domain, cancel.run_until_cancelled(request).await) }
+        })
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS);
+
+    let mut results = Vec::new();
+    while let Some((index, domain, result)) = requests.next().await {
+        match result {
+            Some(Ok(value)) => results.push((index, domain, value)),
+            Some(Err(e)) => {
+                debug!("Request for {} failed, cancelling the rest: {}", domain, e);
+                cancel.cancel();
+                return Err(e);
+            }
+            // Only requests abandoned after a failure end without a result
+            None => {}
+        }
+    }
+    results.sort_by_key(|(index, _, _)| *index);
+    Ok(results
+        .into_iter()
+        .map(|(_, domain, value)| (domain, value))
+        .collect())
+}
+
+/// Runs `fetch` for every domain, with at most `MAX_CONCURRENT_REQUESTS` requests in flight,
+/// and returns every result.
+///
+/// # Arguments
+/// * `domains` - The domains to fetch.
+/// * `fetch` - Issues the request for one domain.
+///
+/// # Returns
+/// Each domain with its result, in the order of `domains`.
+async fn fetch_many<T, F, Fut>(
+    domains: impl IntoIterator<Item = String>,
+    fetch: F,
+) -> Vec<(String, Result<T, SurgeError>)>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<T, SurgeError>>,
+{
+    futures_util::stream::iter(domains)
+        .map(|domain| {
+            let request = fetch(domain.clone());
+            async move { (domain, request.await) }
+        })
+        .buffered(MAX_CONCURRENT_REQUESTS)
+        .collect()
+        .await
+}
+
+/// Returns `true` if a `dns` response lists a record matching `expected`.
+///
+/// The response shape is not fixed, so every object nested anywhere in it is considered a
+/// candidate record.
+fn has_dns_record(records: &Value, domain: &str, expected: &DnsRecordInput) -> bool {
+    match records {
+        Value::Array(items) => items
+            .iter()
+            .any(|item| has_dns_record(item, domain, expected)),
+        Value::Object(fields) => {
+            let field = |key: &str| fields.get(key).and_then(Value::as_str);
+            let name_matches = field("name").is_some_and(|name| {
+                let name = name.trim_end_matches('.');
+                name == expected.name || name == format!("{}.{}", expected.name, domain)
+            });
+            let is_match = field("type")
+                .is_some_and(|kind| kind.eq_ignore_ascii_case(&expected.record_type))
+                && name_matches
+                && field("value") == Some(expected.value.as_str());
+            is_match
+                || fields
+                    .values()
+                    .any(|value| has_dns_record(value, domain, expected))
+        }
+        _ => false,
+    }
+}
+
+/// The delay before the first retry; doubled on every further attempt.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// The longest backoff between two retries.
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// The longest `Retry-After` a request waits for; a response asking for more is returned as is.
+const RETRY_AFTER_MAX: Duration = Duration::from_secs(120);
+
+/// Returns the exponential backoff for a zero-based retry `attempt`.
+fn retry_backoff(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(RETRY_MAX_DELAY)
+}
+
+/// Returns `true` for statuses that indicate a transient failure worth retrying.
+fn is_retryable(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 502 | 503 | 504)
+}
+
+/// Parses a `Retry-After` header given either as delay-seconds or as an HTTP date.
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(std::time::SystemTime::now())
+            .unwrap_or_default(),
+    )
+}
@@ -16,8 +16,20 @@
 //!   and total size in bytes.
 //! - `publish` and `publish_wip`: Functions for publishing projects and work-in-progress previews,
 //!   respectively, with support for custom headers and command-line arguments.
+//! - `PublishOptions` and `publish_with`: Fine-grained control over a publish, such as junk-file
+//!   exclusion.
+//! - `publish_archive`: Publishes the contents of a `.zip`, `.tar`, or `.tar.gz` archive directly.
+//! - `publish_file`: Publishes a single file, such as a standalone HTML page.
+//! - `publish_url`: The exact URL a publish is sent to, for logging and auditing.
+//! - `publish_raw` and `ndjson_event_stream`: Low-level access to the un-parsed publish response.
+//! - `summarize`: Drains a publish event stream into a `DeploySummary`.
+//! - `progress_only`: Reduces a publish event stream to `(current, total)` tuples for progress bars.
 //! - `calculate_metadata`: A utility function to compute file count and size for a project directory.
+//! - `list_tar_entries`: Lists the entries of the tarball a publish would upload, without uploading.
 //! - `build_custom_gitignore`: A helper function to create a gitignore matcher for `.surgeignore` rules.
+//! - `check_ignore`: Reports whether paths would be ignored, like `git check-ignore`.
+//! - `fingerprint_assets`: Proposes content-hashed file names for cache busting.
+//! - `compute_manifest`: Computes the manifest a project would have once published.
 //!
 //! The module integrates with the `SurgeSdk` client for authentication and HTTP requests, and it uses
 //! the `ndjson_stream` crate to parse streaming API responses. Errors are handled using the `SurgeError`
@@ -43,33 +55,46 @@
 //! ```
 
 use crate::{
+    archive,
+    config::UploadMode,
     error::SurgeError,
-    sdk::SurgeSdk,
-    types::{Auth, Event, RawEvent},
+    plan::{PublishPlan, heavy_dir, plan, plan_file, walk},
+    responses::{DomainSettings, ManifestResponse, ManifestResponseValue},
+    sdk::{SurgeSdk, response_text},
+    types::{Auth, CacheDiagnostics, DeploySummary, Event, RawEvent},
+    utils::normalize_domain,
 };
 use bytes::Bytes;
 use flate2::{Compression, write::GzEncoder};
 use futures_util::{Stream, StreamExt};
-use ignore::{WalkBuilder, gitignore::GitignoreBuilder};
-use log::{debug, error, info, trace};
+use ignore::gitignore::GitignoreBuilder;
+use log::{debug, error, info, trace, warn};
 use ndjson_stream::{
     config::{EmptyLineHandling, NdjsonConfig},
     fallible::FallibleNdjsonError,
 };
-use reqwest::Body;
+use reqwest::{
+    Body,
+    multipart::{Form, Part},
+};
 use serde_json::{Value, json};
+#[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
 use std::pin::Pin;
 use std::{
+    collections::HashMap,
     fs::{self, File},
+    io::{Read, Write},
     path::{Path, PathBuf},
-    time::UNIX_EPOCH,
+    sync::{Arc, Mutex},
+    time::{Duration, UNIX_EPOCH},
 };
 use tar::{Builder, Header};
 use thiserror::Error;
 use tokio::io::{AsyncWriteExt, DuplexStream};
 use tokio::task::JoinHandle;
 use tokio_util::io::ReaderStream;
+use url::Url;
 
 /// Errors that can occur during tarbar creation or directory traversal.
 #[derive(Debug, Error)]
@@ -89,6 +114,772 @@ struct TarGzStream {
     done: bool,                         // Flag to indicate stream completition
 }
 
+/// Junk files excluded from every publish unless [`PublishOptions::exclude_junk`] is disabled.
+///
+/// Patterns use `.gitignore` syntax and match at any depth:
+/// - `.DS_Store`, `._*`: macOS Finder metadata and AppleDouble resource forks
+/// - `Thumbs.db`, `ehthumbs.db`, `desktop.ini`: Windows Explorer metadata
+/// - `*.swp`, `*.swo`, `*~`: Vim swap files and editor backups
+pub const JUNK_PATTERNS: &[&str] = &[
+    ".DS_Store",
+    "._*",
+    "Thumbs.db",
+    "ehthumbs.db",
+    "desktop.ini",
+    "*.swp",
+    "*.swo",
+    "*~",
+];
+
+/// The default for [`PublishOptions::max_open_files`].
+///
+/// Well below the common default soft `ulimit -n` of 256 (macOS) and 1024 (Linux).
+pub const DEFAULT_MAX_OPEN_FILES: usize = 64;
+
+/// How far the local clock may drift from the server's `Date` before a publish reports clock
+/// skew, in seconds.
+///
+/// The `timestamp` header sent with a publish is taken from the local clock.
+pub const MAX_CLOCK_SKEW_SECS: i64 = 300;
+
+/// The largest serialized `argv` header a publish sends, in bytes.
+///
+/// Servers commonly cap a request's headers at 8 KiB in total; a larger `argv` is rejected
+/// before anything is uploaded instead of failing with an opaque `431` or `400`.
+pub const MAX_ARGV_HEADER_BYTES: usize = 4096;
+
+/// The most bytes the files of an archive may unpack to before [`publish_archive`] rejects it.
+///
+/// Archives are unpacked in memory, so this bounds the memory a large or malicious archive, such
+/// as a zip bomb, can take.
+pub const MAX_ARCHIVE_UNPACKED_BYTES: u64 = 1 << 30;
+
+/// How long a deploy webhook may take before it is abandoned.
+///
+/// See [`PublishOptions::with_webhook`].
+pub const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Options controlling how a project is packaged and published.
+///
+/// Built with `PublishOptions::default()` and the `with_*` methods.
+#[derive(Debug, Clone)]
+pub struct PublishOptions {
+    /// Custom HTTP headers added to the publish request.
+    pub headers: Option<Vec<(String, String)>>,
+    /// Command-line arguments forwarded in the `argv` header.
+    pub argv: Option<Vec<String>>,
+    /// Whether to skip the files matched by [`JUNK_PATTERNS`] (default is `true`).
+    ///
+    /// Rules in `.surgeignore` are applied afterwards, so a negated pattern such as
+    /// `!.DS_Store` re-includes a junk file.
+    pub exclude_junk: bool,
+    /// Whether `${VAR}` references in `.surgeignore` are expanded from the environment
+    /// (default is `false`).
+    pub expand_env_in_ignore: bool,
+    /// How unset variables are handled when `expand_env_in_ignore` is enabled.
+    pub unset_env: UnsetEnvPolicy,
+    /// Ignore rules supplied by the caller, in `.gitignore` syntax.
+    ///
+    /// They are added after the `.surgeignore` rules, so they take precedence.
+    pub ignore_rules: Vec<String>,
+    /// Whether the project's `.surgeignore` is disregarded in favour of `ignore_rules`
+    /// (default is `false`).
+    pub replace_surgeignore: bool,
+    /// Whether `.gitignore` files and `.git/info/exclude` also exclude files (default is
+    /// `false`).
+    ///
+    /// See [`PublishOptions::with_use_gitignore`].
+    pub use_gitignore: bool,
+    /// If set, the publish is aborted when the live revision is newer than this one.
+    pub baseline_rev: Option<u64>,
+    /// The source commit the deploy was built from, sent in the `x-source-commit` header.
+    pub source_commit: Option<String>,
+    /// Whether to skip the upload when the live deployment records the same `source_commit`
+    /// (default is `false`).
+    pub skip_same_commit: bool,
+    /// The platform the deploy is recorded under, instead of the server default (`surge.sh`).
+    pub platform: Option<String>,
+    /// A message recorded with the deploy, such as `deploy v1.2.3`.
+    pub message: Option<String>,
+    /// Whether the project is published as a work-in-progress preview (default is `false`).
+    ///
+    /// See [`PublishOptions::with_wip`].
+    pub wip: bool,
+    /// The prefix of the preview domain of a WIP publish, instead of the current time in
+    /// milliseconds.
+    ///
+    /// See [`PublishOptions::with_wip_prefix`].
+    pub wip_prefix: Option<String>,
+    /// Whether warnings found while planning the publish, such as case-insensitive filename
+    /// collisions, abort it (default is `false`, which only logs them).
+    pub strict: bool,
+    /// The maximum number of directories held open at once while scanning the project (default
+    /// is [`DEFAULT_MAX_OPEN_FILES`]).
+    ///
+    /// This caps the threads of the parallel directory walk, each of which holds one directory
+    /// open; the tarball, manifest, and fingerprints read one file at a time. Keep it comfortably
+    /// below the process's `ulimit -n`, which also has to cover sockets and any descriptors held
+    /// by the rest of the application.
+    pub max_open_files: usize,
+    /// Settings applied to the domain as part of the publish.
+    ///
+    /// See [`PublishOptions::with_settings`] for how they are ordered with the upload.
+    pub settings: Option<DomainSettings>,
+    /// How symbolic links in the project directory are handled.
+    pub symlinks: SymlinkPolicy,
+    /// What happens when [`SymlinkPolicy::Follow`] reaches a file through more than one path.
+    pub duplicates: DuplicatePolicy,
+    /// The directory the files are placed under inside the uploaded tarball.
+    pub archive_root: ArchiveRoot,
+    /// The header format of the tarball's entries.
+    pub tar_format: TarFormat,
+    /// Whether an upload rejected as stale is retried once with a fresh tarball (default is
+    /// `false`).
+    ///
+    /// See [`PublishOptions::with_retry_stale_upload`].
+    pub retry_stale_upload: bool,
+    /// A fixed value for the `timestamp` header, instead of the current time.
+    pub override_timestamp: Option<String>,
+    /// A fixed value for the `version` header, instead of `Config::version`.
+    pub override_version: Option<String>,
+    /// The deadline for the publish request, replacing `Config::timeout_secs` for it.
+    ///
+    /// See [`PublishOptions::with_read_timeout`].
+    pub read_timeout: Option<Duration>,
+    /// The time allowed to establish the publish connection.
+    ///
+    /// See [`PublishOptions::with_connect_timeout`].
+    pub connect_timeout: Option<Duration>,
+    /// A URL the deploy summary is posted to once the publish succeeds.
+    ///
+    /// See [`PublishOptions::with_webhook`].
+    pub webhook: Option<Url>,
+    /// Extensions of files stored in the gzip stream without compression, such as `png`.
+    ///
+    /// See [`PublishOptions::with_skip_compression_extensions`].
+    pub skip_compression_extensions: Vec<String>,
+    /// Whether files inside [`HEAVY_DIRS`](crate::plan::HEAVY_DIRS), such as `node_modules`, are
+    /// included (default is `false`).
+    ///
+    /// See [`PublishOptions::with_allow_heavy_dirs`].
+    pub allow_heavy_dirs: bool,
+}
+
+/// How `.surgeignore` expansion treats a `${VAR}` reference to an unset variable.
+///
+/// In both cases the reference is left in the pattern literally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnsetEnvPolicy {
+    /// Keep the reference silently.
+    Literal,
+    /// Keep the reference and log a warning.
+    #[default]
+    Warn,
+}
+
+/// How symbolic links in the project directory are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymlinkPolicy {
+    /// Archive symlinked files with their target's contents, but do not descend into
+    /// symlinked directories.
+    #[default]
+    Files,
+    /// Also descend into symlinked directories. A file reachable through more than one path
+    /// is handled according to [`DuplicatePolicy`].
+    Follow,
+    /// Skip every symlink.
+    Skip,
+}
+
+/// What happens when [`SymlinkPolicy::Follow`] reaches the same file through more than one path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicatePolicy {
+    /// Keep one path and skip the others with a warning. The file's real path within the
+    /// project is preferred; otherwise the first path in sorted order is kept.
+    #[default]
+    Skip,
+    /// Fail the publish with `SurgeError::InvalidProject`.
+    Error,
+}
+
+/// The directory the files of a publish are placed under inside the uploaded tarball.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ArchiveRoot {
+    /// The name of the project directory or archive, e.g. `dist/` when publishing `./dist`.
+    #[default]
+    ProjectName,
+    /// No directory: files are placed at the root of the tarball.
+    None,
+    /// A fixed directory name.
+    Named(String),
+}
+
+impl ArchiveRoot {
+    /// Returns the directory to place entries under, given the name derived from the source.
+    pub(crate) fn resolve(&self, derived: &str) -> PathBuf {
+        match self {
+            ArchiveRoot::ProjectName => PathBuf::from(derived),
+            ArchiveRoot::None => PathBuf::new(),
+            ArchiveRoot::Named(name) => PathBuf::from(name),
+        }
+    }
+}
+
+/// The header format of the entries in an uploaded tarball.
+///
+/// A USTAR header holds paths of up to 100 bytes, or 255 when they can be split at a `/` into
+/// a prefix of at most 155 bytes and a name of at most 100. Longer paths need an extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TarFormat {
+    /// USTAR headers, with a PAX extended header for paths that do not fit.
+    #[default]
+    Auto,
+    /// USTAR headers only; a path that does not fit fails the publish with
+    /// `SurgeError::Archive`.
+    Ustar,
+    /// GNU headers, with the GNU long-name extension for long paths.
+    Gnu,
+    /// USTAR headers, with the path of every entry also recorded in a PAX extended header.
+    Pax,
+}
+
+impl Default for PublishOptions {
+    fn default() -> Self {
+        Self {
+            headers: None,
+            argv: None,
+            exclude_junk: true,
+            expand_env_in_ignore: false,
+            unset_env: UnsetEnvPolicy::default(),
+            ignore_rules: Vec::new(),
+            replace_surgeignore: false,
+            use_gitignore: false,
+            baseline_rev: None,
+            source_commit: None,
+            skip_same_commit: false,
+            platform: None,
+            message: None,
+            wip: false,
+            wip_prefix: None,
+            strict: false,
+            max_open_files: DEFAULT_MAX_OPEN_FILES,
+            settings: None,
+            symlinks: SymlinkPolicy::default(),
+            duplicates: DuplicatePolicy::default(),
+            archive_root: ArchiveRoot::default(),
+            tar_format: TarFormat::default(),
+            retry_stale_upload: false,
+            override_timestamp: None,
+            override_version: None,
+            read_timeout: None,
+            connect_timeout: None,
+            webhook: None,
+            skip_compression_extensions: Vec::new(),
+            allow_heavy_dirs: false,
+        }
+    }
+}
+
+impl PublishOptions {
+    /// Sets custom HTTP headers for the publish request.
+    ///
+    /// Accepts any iterator of name/value pairs, such as a `Vec` of tuples or a `HashMap`.
+    /// Names and values are validated when publishing, which fails with `SurgeError::Config` if
+    /// one is not a valid HTTP header.
+    ///
+    /// # Arguments
+    /// * `headers` - The headers to add.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    /// use surge_sdk::PublishOptions;
+    ///
+    /// let options =
+    ///     PublishOptions::default().with_headers(HashMap::from([("x-build-id", "42")]));
+    /// assert_eq!(
+    ///     options.headers,
+    ///     Some(vec![("x-build-id".to_string(), "42".to_string())])
+    /// );
+    /// ```
+    pub fn with_headers<K, V>(mut self, headers: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.headers = Some(
+            headers
+                .into_iter()
+                .map(|(name, value)| (name.into(), value.into()))
+                .collect(),
+        );
+        self
+    }
+
+    /// Sets the command-line arguments forwarded in the `argv` header.
+    ///
+    /// # Arguments
+    /// * `argv` - The arguments to forward.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_argv(mut self, argv: Vec<String>) -> Self {
+        self.argv = Some(argv);
+        self
+    }
+
+    /// Sets whether the built-in [`JUNK_PATTERNS`] are excluded.
+    ///
+    /// # Arguments
+    /// * `val` - Whether to exclude junk files.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    ///
+    /// # Example
+    /// ```
+    /// use surge_sdk::stream::PublishOptions;
+    ///
+    /// let options = PublishOptions::default().with_exclude_junk(false);
+    /// assert!(!options.exclude_junk);
+    /// ```
+    pub fn with_exclude_junk(mut self, val: bool) -> Self {
+        self.exclude_junk = val;
+        self
+    }
+
+    /// Sets whether `${VAR}` references in `.surgeignore` are expanded from the environment.
+    ///
+    /// Useful in CI, e.g. `${BUILD_DIR}/cache`. Only the braced `${VAR}` form is recognized.
+    ///
+    /// # Arguments
+    /// * `val` - Whether to expand environment variables.
+    /// * `unset_env` - How references to unset variables are handled.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_expand_env_in_ignore(mut self, val: bool, unset_env: UnsetEnvPolicy) -> Self {
+        self.expand_env_in_ignore = val;
+        self.unset_env = unset_env;
+        self
+    }
+
+    /// Sets ignore rules computed by the caller instead of read from a file.
+    ///
+    /// The rules use `.gitignore` syntax and apply to metadata calculation, planning, and the
+    /// tarball alike. Junk patterns still apply unless `exclude_junk` is disabled.
+    ///
+    /// # Arguments
+    /// * `rules` - The patterns, one per entry, e.g. `*.log`.
+    /// * `replace_surgeignore` - If `true`, the project's `.surgeignore` is not read; otherwise
+    ///   the rules are appended to it.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_ignore_rules<I, S>(mut self, rules: I, replace_surgeignore: bool) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.ignore_rules = rules.into_iter().map(Into::into).collect();
+        self.replace_surgeignore = replace_surgeignore;
+        self
+    }
+
+    /// Aborts the publish if the domain's live revision is newer than `rev`.
+    ///
+    /// The current metadata is fetched before anything is uploaded, which guards against
+    /// clobbering a more recent deploy by someone else. If the metadata cannot be fetched (for
+    /// example, because the domain has never been published), the publish proceeds.
+    ///
+    /// # Arguments
+    /// * `rev` - The newest revision the caller expects to be live.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn fail_if_newer_than(mut self, rev: u64) -> Self {
+        self.baseline_rev = Some(rev);
+        self
+    }
+
+    /// Stamps the deploy with the commit it was built from.
+    ///
+    /// The commit is sent in the `x-source-commit` header.
+    ///
+    /// # Arguments
+    /// * `commit` - The source commit, e.g. a git SHA.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_source_commit(mut self, commit: impl Into<String>) -> Self {
+        self.source_commit = Some(commit.into());
+        self
+    }
+
+    /// Sets whether to skip deploys of a commit that is already live.
+    ///
+    /// Requires `source_commit`. Before uploading, the live metadata is checked with
+    /// [`MetadataResponse::records_commit`](crate::MetadataResponse::records_commit); on a match
+    /// nothing is uploaded and the publish yields an empty event stream. Has no effect on
+    /// [`publish_raw`].
+    ///
+    /// # Arguments
+    /// * `val` - Whether to skip no-op deploys.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_skip_same_commit(mut self, val: bool) -> Self {
+        self.skip_same_commit = val;
+        self
+    }
+
+    /// Sets the platform the deploy is recorded under.
+    ///
+    /// Sent in the `platform` header and the `argv` payload, so white-labeled services on a
+    /// Surge-compatible backend can tag deploys with their own name. The `platform` header is
+    /// sent even when `Config::minimal_publish_headers` is enabled.
+    ///
+    /// # Arguments
+    /// * `platform` - The platform identifier, e.g. `example.com`.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_platform(mut self, platform: impl Into<String>) -> Self {
+        self.platform = Some(platform.into());
+        self
+    }
+
+    /// Sets a message recorded with the deploy.
+    ///
+    /// Sent in the `message` header and the `argv` payload; the server records it in the
+    /// revision's metadata, where it is returned as `MetadataResponse::message`. Like the
+    /// `platform` header, it is sent even when `Config::minimal_publish_headers` is enabled.
+    ///
+    /// # Arguments
+    /// * `message` - The deploy message, e.g. `deploy v1.2.3`.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Sets whether the project is published as a work-in-progress preview.
+    ///
+    /// A WIP publish goes to a timestamped preview of the domain (`<millis>-<domain>`, see
+    /// [`PublishOptions::with_wip_prefix`]) and is flagged as staged in the `stage` and `argv`
+    /// headers, like [`publish_wip`]. This lets `publish_with` and the other option-based
+    /// publishers produce previews.
+    ///
+    /// # Arguments
+    /// * `val` - Whether to publish a preview.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_wip(mut self, val: bool) -> Self {
+        self.wip = val;
+        self
+    }
+
+    /// Publishes a work-in-progress preview at `<prefix>-<domain>`, e.g. `pr-123-my-site.surge.sh`.
+    ///
+    /// Replaces the default millisecond timestamp prefix, and enables [`with_wip`]. The
+    /// prefix must be hostname-safe, as it becomes part of the domain's first label: ASCII
+    /// letters, digits and hyphens, not starting or ending with a hyphen, and short enough
+    /// that the label stays within 63 characters. Otherwise the publish fails with
+    /// `SurgeError::Config`.
+    ///
+    /// [`with_wip`]: PublishOptions::with_wip
+    ///
+    /// # Arguments
+    /// * `prefix` - The preview prefix, e.g. `pr-123`.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_wip_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.wip_prefix = Some(prefix.into());
+        self.wip = true;
+        self
+    }
+
+    /// Sets whether planning warnings abort the publish.
+    ///
+    /// # Arguments
+    /// * `val` - Whether to fail on warnings instead of logging them.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_strict(mut self, val: bool) -> Self {
+        self.strict = val;
+        self
+    }
+
+    /// Sets whether files inside [`HEAVY_DIRS`](crate::plan::HEAVY_DIRS) are included.
+    ///
+    /// By default, `node_modules`, `.git`, and `target` directories are left out of the plan,
+    /// and so of the tarball, its listing, the local manifest, and the metadata, with a warning
+    /// logging each directory's size. `Config::with_allow_heavy_dirs` enables this for every
+    /// publish of a client.
+    ///
+    /// # Arguments
+    /// * `val` - Whether to include heavy directories.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_allow_heavy_dirs(mut self, val: bool) -> Self {
+        self.allow_heavy_dirs = val;
+        self
+    }
+
+    /// Sets whether the project's git ignore rules also exclude files.
+    ///
+    /// When enabled, `.gitignore` files in the project and its parent directories, and the
+    /// repository's `.git/info/exclude`, are applied on top of the junk and `.surgeignore`
+    /// rules. Like git, they only take effect inside a repository: if neither the project nor
+    /// any parent directory contains `.git`, planning logs a warning and publishes without
+    /// them, or fails with `SurgeError::Config` when [`with_strict`](Self::with_strict) is set.
+    ///
+    /// # Arguments
+    /// * `val` - Whether to apply the git ignore rules.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_use_gitignore(mut self, val: bool) -> Self {
+        self.use_gitignore = val;
+        self
+    }
+
+    /// Sets the maximum number of directories held open at once by the project walk.
+    ///
+    /// # Arguments
+    /// * `max` - The limit; values below `1` are treated as `1`.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_max_open_files(mut self, max: usize) -> Self {
+        self.max_open_files = max.max(1);
+        self
+    }
+
+    /// Sets how symbolic links in the project directory are handled.
+    ///
+    /// # Arguments
+    /// * `symlinks` - The symlink policy.
+    /// * `duplicates` - How files reached through more than one path are handled when
+    ///   following symlinks.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_symlinks(mut self, symlinks: SymlinkPolicy, duplicates: DuplicatePolicy) -> Self {
+        self.symlinks = symlinks;
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// Sets domain settings (redirects, CORS, HSTS, ...) to apply as part of the publish.
+    ///
+    /// Ordering guarantees:
+    /// - The settings request is sent after the project files have been checked, and after any
+    ///   [`fail_if_newer_than`](Self::fail_if_newer_than) or same-commit check.
+    /// - The upload only starts once the settings request has succeeded, so the new revision
+    ///   never goes live without them. If it fails, the publish fails and nothing is uploaded.
+    /// - Settings are domain-wide, so the previously live revision is served with the new
+    ///   settings from the moment they are applied until the upload completes.
+    ///
+    /// # Arguments
+    /// * `settings` - The settings to apply.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_settings(mut self, settings: DomainSettings) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    /// Sets the directory the files are placed under inside the uploaded tarball.
+    ///
+    /// By default it is the project directory's name.
+    ///
+    /// # Arguments
+    /// * `root` - `Some(name)` to use a fixed directory name, or `None` to place files at the
+    ///   root of the tarball.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    ///
+    /// # Example
+    /// ```
+    /// use surge_sdk::{ArchiveRoot, PublishOptions};
+    ///
+    /// let options = PublishOptions::default().with_archive_root(None);
+    /// assert_eq!(options.archive_root, ArchiveRoot::None);
+    /// ```
+    pub fn with_archive_root(mut self, root: Option<String>) -> Self {
+        self.archive_root = match root {
+            Some(name) => ArchiveRoot::Named(name),
+            None => ArchiveRoot::None,
+        };
+        self
+    }
+
+    /// Sets the header format of the tarball's entries.
+    ///
+    /// The default, [`TarFormat::Auto`], only adds an extension for paths too long for a USTAR
+    /// header, such as files in deeply nested directories.
+    ///
+    /// # Arguments
+    /// * `format` - The tar header format.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_tar_format(mut self, format: TarFormat) -> Self {
+        self.tar_format = format;
+        self
+    }
+
+    /// Sets whether an upload rejected as stale is retried once.
+    ///
+    /// The server rejects an upload whose `file-count` or `project-size` header does not match
+    /// the tarball, which happens when a build process changes files while they are being
+    /// archived. With this enabled, such a rejection (a `400`, `409`, or `422` whose body reports
+    /// a `file-count`/`project-size` mismatch) makes the SDK plan the project again, rebuild the
+    /// tarball, and retry a single time. Settings and the baseline check are repeated as well.
+    ///
+    /// # Arguments
+    /// * `val` - Whether to retry a stale upload.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_retry_stale_upload(mut self, val: bool) -> Self {
+        self.retry_stale_upload = val;
+        self
+    }
+
+    /// Sets a fixed value for the `timestamp` header.
+    ///
+    /// Useful for reproducible tests that assert the exact outgoing headers.
+    ///
+    /// # Arguments
+    /// * `timestamp` - The header value, e.g. `2025-01-01T00:00:00+00:00`.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_override_timestamp(mut self, timestamp: impl Into<String>) -> Self {
+        self.override_timestamp = Some(timestamp.into());
+        self
+    }
+
+    /// Sets a fixed value for the `version` header, instead of `Config::version`.
+    ///
+    /// # Arguments
+    /// * `version` - The header value.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_override_version(mut self, version: impl Into<String>) -> Self {
+        self.override_version = Some(version.into());
+        self
+    }
+
+    /// Sets the deadline for the publish request.
+    ///
+    /// Applied with `RequestBuilder::timeout`, so it covers the whole request: connecting,
+    /// streaming the tarball, and reading the event stream until the server closes it. It
+    /// replaces `Config::timeout_secs` for the publish only; other requests, such as the
+    /// metadata check of `fail_if_newer_than` or the settings of `with_settings`, keep the
+    /// global timeout. Long uploads usually want a generous value here.
+    ///
+    /// # Arguments
+    /// * `timeout` - The deadline for the publish request.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the time allowed to establish the publish connection.
+    ///
+    /// reqwest only takes a connect timeout per client, so the publish is sent on a one-off
+    /// client built from the `Config` settings plus this timeout. It still counts towards the
+    /// overall deadline, `read_timeout` or else `Config::timeout_secs`.
+    ///
+    /// # Arguments
+    /// * `timeout` - The connect timeout for the publish request.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets a webhook that is notified after a successful deploy, e.g. to post to Slack or
+    /// Discord through a relay.
+    ///
+    /// Once the event stream ends without an error, the [`DeploySummary`] it describes is
+    /// posted to `url` as its JSON envelope (see `DeploySummary::to_json_envelope`). The call
+    /// is fire-and-forget: it runs in a background task with a [`WEBHOOK_TIMEOUT`], and a
+    /// failure is logged without affecting the deploy. Nothing is sent if the event stream is
+    /// dropped before it ends.
+    ///
+    /// # Arguments
+    /// * `url` - The URL to post the summary to.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_webhook(mut self, url: Url) -> Self {
+        self.webhook = Some(url);
+        self
+    }
+
+    /// Sets the extensions of files stored in the gzip stream without compression.
+    ///
+    /// Images, video, fonts, and archives are already compressed, so deflating them again
+    /// costs CPU time for little or no size reduction. Files with these extensions are written
+    /// at `Compression::none()` while the rest of the project is compressed as usual, which
+    /// speeds up packaging media-heavy projects, at the cost of a slightly larger upload.
+    ///
+    /// The tarball is then made of several concatenated gzip members, one per run of files
+    /// with the same compression, which gzip readers decode as a single stream. Has no effect
+    /// when `Config::compression_enabled` is `false`.
+    ///
+    /// # Arguments
+    /// * `extensions` - File extensions, with or without the leading `.`, matched
+    ///   case-insensitively, e.g. `["png", "jpg", "woff2", "mp4"]`.
+    ///
+    /// # Returns
+    /// The modified `PublishOptions` instance for method chaining.
+    pub fn with_skip_compression_extensions<I, S>(mut self, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.skip_compression_extensions = extensions
+            .into_iter()
+            .map(|extension| {
+                let extension: String = extension.into();
+                extension.trim_start_matches('.').to_ascii_lowercase()
+            })
+            .collect();
+        self
+    }
+
+    /// Builds options from the loose `headers`/`argv` arguments of `publish` and `publish_wip`.
+    fn from_args(headers: Option<Vec<(String, String)>>, argv: Option<&[String]>) -> Self {
+        Self {
+            headers,
+            argv: argv.map(<[String]>::to_vec),
+            ..Self::default()
+        }
+    }
+}
+
 /// Metadata about a project directory, including file count and total size.
 #[derive(Debug, Clone)]
 pub struct StreamMetadata {
@@ -98,6 +889,72 @@ pub struct StreamMetadata {
     pub project_size: u64,
 }
 
+/// An entry of the tarball a publish uploads, as listed by [`list_tar_entries`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TarEntry {
+    /// The path inside the tarball, with `/` as the separator.
+    pub path: String,
+    /// The file size in bytes.
+    pub size: u64,
+    /// The unix mode the file is archived with.
+    pub mode: u32,
+}
+
+/// Lists the entries of the tarball `publish_with` would upload for a project, without
+/// reading file contents or uploading anything.
+///
+/// Runs the same walk, archive root, and header logic as the upload, so the listing matches
+/// the tarball entry for entry.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory.
+/// * `options` - The publish options that determine which files are included and where.
+/// * `preserve_permissions` - The `Config::preserve_permissions` of the client that would
+///   upload; file modes are `0o644` unless set.
+///
+/// # Returns
+/// A `Result` containing the entries in archive order, or a `SurgeError` if the project cannot
+/// be planned or a file's metadata cannot be read.
+///
+/// # Example
+/// ```rust,no_run
+/// use surge_sdk::stream::{PublishOptions, list_tar_entries};
+///
+/// # fn example() -> Result<(), surge_sdk::SurgeError> {
+/// let entries = list_tar_entries("./dist".as_ref(), &PublishOptions::default(), false)?;
+/// for entry in entries {
+///     println!("{:o} {:>8} {}", entry.mode, entry.size, entry.path);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn list_tar_entries(
+    project_path: &Path,
+    options: &PublishOptions,
+    preserve_permissions: bool,
+) -> Result<Vec<TarEntry>, SurgeError> {
+    let plan = plan(project_path, options)?;
+    let root = options.archive_root.resolve(project_dir_name(project_path));
+    plan.files
+        .iter()
+        .map(|planned| {
+            let (size, mode, _) =
+                archived_header(&project_path.join(&planned.path), preserve_permissions).map_err(
+                    |e| SurgeError::Archive {
+                        path: planned.path.display().to_string(),
+                        message: e.to_string(),
+                    },
+                )?;
+            let path = tar_path_bytes(&root.join(&planned.path));
+            Ok(TarEntry {
+                path: String::from_utf8_lossy(&path).into_owned(),
+                size,
+                mode,
+            })
+        })
+        .collect()
+}
+
 /// Calculates metadata (file count and total size) for a project directory.
 ///
 /// # Arguments
@@ -107,9 +964,26 @@ pub struct StreamMetadata {
 /// A `Result` containing `StreamMetadata` or a `SurgeError` if the path is invalid or an error occurs.
 ///
 /// # Notes
-/// - Respects `.surgeignore` rules for excluding files.
+/// - Respects `.surgeignore` rules and the default [`JUNK_PATTERNS`] for excluding files.
 /// - Uses parallel directory traversal for efficiency.
+/// - Fails with `SurgeError::Ignore` if any directory cannot be read, rather than returning
+///   partial counts.
 pub fn calculate_metadata(project_path: &Path) -> Result<StreamMetadata, SurgeError> {
+    calculate_metadata_with(project_path, &PublishOptions::default())
+}
+
+/// Calculates metadata for a project directory as it would be published with `options`.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory.
+/// * `options` - The publish options that determine which files are included.
+///
+/// # Returns
+/// A `Result` containing `StreamMetadata` or a `SurgeError` if the path is invalid or an error occurs.
+pub fn calculate_metadata_with(
+    project_path: &Path,
+    options: &PublishOptions,
+) -> Result<StreamMetadata, SurgeError> {
     debug!("Calculating metadata for path: {:?}", project_path);
 
     if !project_path.is_dir() {
@@ -120,46 +994,29 @@ pub fn calculate_metadata(project_path: &Path) -> Result<StreamMetadata, SurgeEr
         )));
     }
 
-    let gitignore = build_custom_gitignore(project_path)?;
-
-    let walker = WalkBuilder::new(project_path)
-        .standard_filters(false)
-        .build_parallel();
-
-    let (tx, rx) = std::sync::mpsc::channel();
-    let worker_tx = tx.clone();
-
-    walker.run(move || {
-        // Use the cloned sender in the worker threads
-        let tx = worker_tx.clone();
-        let gitignore = gitignore.clone();
-
-        Box::new(move |result| {
-            match result {
-                Ok(entry) => {
-                    let path = entry.path();
-                    let matched = gitignore.matched_path_or_any_parents(path, path.is_dir());
-                    if !matched.is_ignore() {
-                        tx.send(entry).ok();
-                    }
-                }
-                Err(err) => {
-                    error!("Walker error: {:?}", err);
-                    // Cannot send errors directly due to channel limitations
-                }
-            }
-            ignore::WalkState::Continue
-        })
-    });
-
-    drop(tx); // Important for proper channel closure
+    if options.symlinks != SymlinkPolicy::Files {
+        // Skipping and de-duplicating symlinks is handled by the planner
+        let plan = plan(project_path, options)?;
+        return Ok(StreamMetadata {
+            file_count: plan.file_count(),
+            project_size: plan.total_size,
+        });
+    }
 
     let mut file_count = 0;
     let mut project_size = 0;
 
-    for entry in rx {
+    for entry in walk(project_path, options)? {
         let path = entry.path();
         trace!("Processing file for metadata: {:?}", path);
+        let in_heavy_dir = !options.allow_heavy_dirs
+            && path
+                .strip_prefix(project_path)
+                .is_ok_and(|relative| heavy_dir(relative).is_some());
+        if in_heavy_dir {
+            trace!("Skipping file in a heavy directory: {:?}", path);
+            continue;
+        }
         if path.is_file() {
             let metadata = fs::metadata(path).map_err(|e| {
                 SurgeError::Io(format!("Failed to get metadata for {:?}: {}", path, e))
@@ -181,135 +1038,397 @@ pub fn calculate_metadata(project_path: &Path) -> Result<StreamMetadata, SurgeEr
     })
 }
 
-/// Creates a new `TarGzStream` for a project directory.
-///
-/// # Arguments
-/// * `project_path` - Path to the project directory.
-/// * `chunk_size` - Size of the duplex stream buffer.
-///
-/// # Returns
-/// A `Result` containing the `TarGzStream` or a `SurgeError` if the path is invalid or an error occurs.
 impl TarGzStream {
-    fn new(project_path: &Path, chunk_size: usize) -> Result<Self, SurgeError> {
-        debug!("Creating new TarGzStream for path: {:?}", project_path);
-
-        // Validate that the path is a directory
-        if !project_path.is_dir() {
-            error!("Project path {:?}: is not a directory", project_path);
-            return Err(SurgeError::Io(format!(
-                "Invalid project directory: {}",
-                project_path.display()
-            )));
-        }
-
-        // Extract directory name for tarball paths
-        let dir_name = project_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("project")
-            .to_string();
-
+    /// Creates a `TarGzStream` that archives the files of a `PublishPlan`.
+    ///
+    /// # Arguments
+    /// * `project_path` - Path to the project directory.
+    /// * `root` - The directory entries are placed under; empty for the tarball root.
+    /// * `plan` - The files to archive, as returned by [`plan`].
+    /// * `chunk_size` - Size of the duplex stream buffer.
+    /// * `preserve_permissions` - Whether to keep each file's unix mode instead of `0o644`.
+    /// * `compress` - Whether to gzip the tarball; `false` streams a raw tar.
+    /// * `options` - The publish options, for the header format and uncompressed extensions.
+    ///
+    /// # Returns
+    /// The `TarGzStream`; errors reading the files are yielded by the stream.
+    fn from_plan(
+        project_path: &Path,
+        root: PathBuf,
+        plan: PublishPlan,
+        chunk_size: usize,
+        preserve_permissions: bool,
+        compress: bool,
+        options: &PublishOptions,
+    ) -> Self {
         let project_path = project_path.to_path_buf();
-        let ignore_matcher = build_custom_gitignore(&project_path)?;
+        let options = options.clone();
 
         // Create a duplex stream for async I/O
         let (reader, writer) = tokio::io::duplex(chunk_size);
 
         // Spawn an async task to build the tarball
         let task = tokio::spawn(async move {
-            // Temporary buffer for tarball data
-            let buffer = Vec::new();
-            let mut encoder = GzEncoder::new(buffer, Compression::new(6));
-
-            // Build tar in a block to drop it before encoder.finish()
-            {
-                let mut tar = Builder::new(&mut encoder);
-
-                let walker = WalkBuilder::new(&project_path)
-                    .standard_filters(false)
-                    .build();
-
-                for entry in walker {
-                    let entry = entry.map_err(|e| SurgeError::Ignore(e.to_string()))?;
-                    let path = entry.path();
-
-                    // Skip ignored files or non-files
-                    let is_ignored = ignore_matcher
-                        .matched_path_or_any_parents(path, path.is_dir())
-                        .is_ignore();
-
-                    if is_ignored || !path.is_file() {
-                        trace!("Ignored or not a file: {}", path.display());
-                        continue;
-                    }
-
-                    // Process each file
-                    if path.is_file() {
-                        trace!("Processing file: {}", path.display());
-
-                        // Compute relative path for tar
-                        let rel_path = path
-                            .strip_prefix(project_path.parent().unwrap_or(Path::new("")))
-                            .map_err(|e| SurgeError::InvalidProject(e.to_string()))?;
-                        // Get file_name and handle None case
-                        let file_name = rel_path.file_name().ok_or_else(|| {
-                            SurgeError::InvalidProject(format!(
-                                "No file name for path: {}",
-                                path.display()
-                            ))
-                        })?;
-
-                        let tar_path = PathBuf::from(&dir_name).join(file_name);
-                        let metadata = fs::metadata(path)?;
-                        debug!(
-                            "Adding file to tar: {} (size: {}, mode: {:o})",
-                            tar_path.display(),
-                            metadata.len(),
-                            metadata.permissions().mode()
-                        );
-
-                        // Set up tar header
-                        let mut header = Header::new_ustar();
-                        header.set_size(metadata.len());
-                        header.set_mode(0o644); // Standard file permissions
-                        header.set_mtime(
-                            metadata
-                                .modified()
-                                .map(|t| t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs())
-                                .unwrap_or(0),
-                        );
-                        header.set_cksum();
-
-                        // Add file to tar
-                        let mut file =
-                            File::open(path).map_err(|e| SurgeError::Io(e.to_string()))?;
-                        tar.append_data(&mut header, &tar_path, &mut file)
-                            .map_err(|e| SurgeError::Io(e.to_string()))?;
-                    }
-                }
-
-                tar.finish()?;
-            } // Drop tar to release encoder borrow
-
-            // Finalize gzip compression
-            let data = encoder.finish()?;
-
-            // Write tarball to the duplex stream
             let mut writer = writer;
-            writer.write_all(&data).await?;
-            writer.shutdown().await?;
-            Ok(())
+            let archive = build_archive(
+                &project_path,
+                &root,
+                &plan,
+                preserve_permissions,
+                compress,
+                &options,
+            );
+            match archive {
+                Ok(data) => {
+                    // Write tarball to the duplex stream; this fails rather than stalls once
+                    // the reader is dropped
+                    writer
+                        .write_all(&data)
+                        .await
+                        .map_err(|e| SurgeError::Io(format!("tarball write failed: {}", e)))?;
+                    writer
+                        .shutdown()
+                        .await
+                        .map_err(|e| SurgeError::Io(format!("tarball write failed: {}", e)))?;
+                    Ok(())
+                }
+                Err(e) => {
+                    // Close the writer so the reader sees EOF and picks up the error promptly
+                    writer.shutdown().await.ok();
+                    Err(e)
+                }
+            }
         });
 
-        Ok(Self {
+        Self {
             reader: ReaderStream::new(reader),
             task: Some(task), // Wrap task in Some
             done: false,
+        }
+    }
+}
+
+/// Returns the name of a project directory, used as the default archive root.
+fn project_dir_name(project_path: &Path) -> &str {
+    project_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("project")
+}
+
+/// Builds the tarball for the files of a publish plan in memory.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory.
+/// * `root` - The directory entries are placed under; empty for the archive root.
+/// * `plan` - The files to archive, relative to `project_path`.
+/// * `preserve_permissions` - Whether to keep each file's unix mode instead of `0o644`.
+/// * `compress` - Whether to gzip the tarball.
+/// * `options` - The publish options, for the header format and uncompressed extensions.
+///
+/// # Returns
+/// A `Result` containing the archive bytes or a `SurgeError` if any file cannot be read.
+fn build_archive(
+    project_path: &Path,
+    root: &Path,
+    plan: &PublishPlan,
+    preserve_permissions: bool,
+    compress: bool,
+    options: &PublishOptions,
+) -> Result<Vec<u8>, SurgeError> {
+    build_tarball(compress, |tar| {
+        for planned in &plan.files {
+            let path = project_path.join(&planned.path);
+            trace!("Processing file: {}", path.display());
+            let read_error = |e: std::io::Error| SurgeError::Archive {
+                path: planned.path.display().to_string(),
+                message: e.to_string(),
+            };
+
+            let (size, mode, mtime) =
+                archived_header(&path, preserve_permissions).map_err(read_error)?;
+            debug!(
+                "Adding file to tar: {} (size: {}, mode: {:o})",
+                path.display(),
+                size,
+                mode
+            );
+
+            let file = File::open(&path).map_err(read_error)?;
+            let tar_path = root.join(&planned.path);
+            tar.get_mut()
+                .set_stored(has_extension(
+                    &planned.path,
+                    &options.skip_compression_extensions,
+                ))
+                .map_err(|e| SurgeError::Io(format!("gzip write failed: {}", e)))?;
+            append_file(tar, &tar_path, size, mode, mtime, file, options.tar_format)
+                .map_err(read_error)?;
+        }
+        Ok(())
+    })
+}
+
+/// Returns the size, mode, and modification time a file is archived with.
+///
+/// # Arguments
+/// * `path` - Path to the file.
+/// * `preserve_permissions` - Whether to keep the file's unix mode instead of `0o644`.
+///
+/// # Returns
+/// A `Result` containing the size in bytes, the sanitized mode, and the modification time in
+/// seconds since the Unix epoch, or the I/O error from reading the file's metadata.
+fn archived_header(path: &Path, preserve_permissions: bool) -> std::io::Result<(u64, u32, u64)> {
+    let metadata = fs::metadata(path)?;
+    let mtime = metadata
+        .modified()
+        .map(|t| t.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs())
+        .unwrap_or(0);
+    let mode = archived_mode(file_mode(&metadata), preserve_permissions);
+    Ok((metadata.len(), mode, mtime))
+}
+
+/// The gzip level of the publish tarball.
+const GZIP_LEVEL: Compression = Compression::new(6);
+
+/// Returns whether a path has one of `extensions`, which are lowercase and without a `.`.
+fn has_extension(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            extensions
+                .iter()
+                .any(|stored| stored.eq_ignore_ascii_case(extension))
         })
+}
+
+/// The in-memory buffer a tarball is written to, gzip-compressed or raw.
+pub(crate) enum TarSink {
+    Gzip(GzEncoder<Vec<u8>>, Compression),
+    Raw(Vec<u8>),
+}
+
+impl TarSink {
+    fn new(compress: bool) -> Self {
+        if compress {
+            TarSink::Gzip(GzEncoder::new(Vec::new(), GZIP_LEVEL), GZIP_LEVEL)
+        } else {
+            TarSink::Raw(Vec::new())
+        }
+    }
+
+    /// Sets whether the next writes are stored without compression.
+    ///
+    /// The level of a gzip member cannot change midway, so switching finishes the current
+    /// member and starts a new one at the other level; gzip readers decode concatenated members
+    /// as one stream. Does nothing for a raw tarball.
+    fn set_stored(&mut self, stored: bool) -> std::io::Result<()> {
+        let TarSink::Gzip(encoder, level) = self else {
+            return Ok(());
+        };
+        let next = if stored {
+            Compression::none()
+        } else {
+            GZIP_LEVEL
+        };
+        if *level == next {
+            return Ok(());
+        }
+        let finished = std::mem::replace(encoder, GzEncoder::new(Vec::new(), next)).finish()?;
+        *encoder = GzEncoder::new(finished, next);
+        *level = next;
+        Ok(())
+    }
+
+    /// Finalizes the compression, if any, and returns the archive bytes.
+    fn finish(self) -> std::io::Result<Vec<u8>> {
+        match self {
+            TarSink::Gzip(encoder, _) => encoder.finish(),
+            TarSink::Raw(buffer) => Ok(buffer),
+        }
+    }
+}
+
+impl Write for TarSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            TarSink::Gzip(encoder, _) => Write::write(encoder, buf),
+            TarSink::Raw(buffer) => Write::write(buffer, buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            TarSink::Gzip(encoder, _) => Write::flush(encoder),
+            TarSink::Raw(buffer) => Write::flush(buffer),
+        }
+    }
+}
+
+/// Builds a tarball in memory, gzip-compressed unless `compress` is `false`.
+///
+/// # Arguments
+/// * `compress` - Whether to gzip the tarball.
+/// * `append` - Adds the entries to the tar builder, typically with [`append_file`].
+///
+/// # Returns
+/// A `Result` containing the archive bytes, the first `SurgeError` from `append`, or a
+/// `SurgeError::Io` if the tar or gzip stream cannot be finalized.
+pub(crate) fn build_tarball(
+    compress: bool,
+    append: impl FnOnce(&mut Builder<&mut TarSink>) -> Result<(), SurgeError>,
+) -> Result<Vec<u8>, SurgeError> {
+    let mut sink = TarSink::new(compress);
+
+    // Build tar in a block to drop it before sink.finish()
+    {
+        let mut tar = Builder::new(&mut sink);
+        append(&mut tar)?;
+        tar.finish()
+            .map_err(|e| SurgeError::Io(format!("tar finalize failed: {}", e)))?;
+    } // Drop tar to release sink borrow
+
+    sink.finish()
+        .map_err(|e| SurgeError::Io(format!("gzip finalize failed: {}", e)))
+}
+
+/// The mode given to archived files unless permissions are preserved.
+const DEFAULT_FILE_MODE: u32 = 0o644;
+
+/// Returns the mode to archive a file with.
+///
+/// When preserving permissions, the setuid, setgid, and sticky bits and group/other write
+/// permissions are dropped, and the file is always readable; the executable bits are kept.
+///
+/// # Arguments
+/// * `mode` - The file's unix mode.
+/// * `preserve` - Whether to keep the mode rather than use `0o644`.
+///
+/// # Returns
+/// The sanitized mode.
+pub(crate) fn archived_mode(mode: u32, preserve: bool) -> u32 {
+    if preserve {
+        (mode & 0o755) | DEFAULT_FILE_MODE
+    } else {
+        DEFAULT_FILE_MODE
+    }
+}
+
+/// Returns the unix mode of a file.
+#[cfg(unix)]
+fn file_mode(metadata: &fs::Metadata) -> u32 {
+    metadata.permissions().mode()
+}
+
+/// Returns the default mode, since only unix has file modes.
+#[cfg(not(unix))]
+fn file_mode(_metadata: &fs::Metadata) -> u32 {
+    DEFAULT_FILE_MODE
+}
+
+/// Appends a regular file to a tarball.
+///
+/// # Arguments
+/// * `tar` - The tar builder.
+/// * `tar_path` - The path of the entry inside the tarball.
+/// * `size` - The size of the file in bytes.
+/// * `mode` - The unix mode of the entry.
+/// * `mtime` - The modification time in seconds since the Unix epoch.
+/// * `data` - The file contents.
+/// * `format` - The header format, see [`TarFormat`].
+///
+/// # Returns
+/// A `Result` that is `Ok` if the entry was written, or the I/O error from reading `data` or
+/// writing the entry. With `TarFormat::Ustar`, a path too long for the header is an
+/// `InvalidInput` error.
+pub(crate) fn append_file(
+    tar: &mut Builder<impl Write>,
+    tar_path: &Path,
+    size: u64,
+    mode: u32,
+    mtime: u64,
+    mut data: impl Read,
+    format: TarFormat,
+) -> std::io::Result<()> {
+    // Set up tar header
+    let mut header = match format {
+        TarFormat::Gnu => Header::new_gnu(),
+        _ => Header::new_ustar(),
+    };
+    header.set_size(size);
+    header.set_mode(mode);
+    header.set_mtime(mtime);
+
+    if format == TarFormat::Gnu {
+        return tar.append_data(&mut header, tar_path, &mut data);
+    }
+
+    let fits = header.set_path(tar_path).is_ok();
+    let extended = match format {
+        TarFormat::Ustar if !fits => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "path is too long for a USTAR header; use TarFormat::Auto, Gnu or Pax",
+            ));
+        }
+        TarFormat::Auto => !fits,
+        TarFormat::Pax => true,
+        TarFormat::Ustar | TarFormat::Gnu => false,
+    };
+    if extended {
+        let path = tar_path_bytes(tar_path);
+        append_pax_path(tar, &path, mtime)?;
+        if !fits {
+            // Readers take the path from the extended header; keep a truncated one here
+            header = Header::new_ustar();
+            header.set_size(size);
+            header.set_mode(mode);
+            header.set_mtime(mtime);
+            let name = &mut header.as_old_mut().name;
+            let len = path.len().min(name.len());
+            name[..len].copy_from_slice(&path[..len]);
+        }
     }
+    header.set_cksum();
+    tar.append(&header, &mut data)
+}
+
+/// Returns the bytes of a path inside a tarball, with `/` as the separator.
+fn tar_path_bytes(tar_path: &Path) -> Vec<u8> {
+    let components: Vec<_> = tar_path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+    components.join("/").into_bytes()
+}
+
+/// Appends a PAX extended header recording `path` for the entry that follows it.
+fn append_pax_path(tar: &mut Builder<impl Write>, path: &[u8], mtime: u64) -> std::io::Result<()> {
+    // A record is "<length> path=<value>\n", where the length counts its own digits
+    let rest = " path=\n".len() + path.len();
+    let mut length = rest + 1;
+    while rest + length.to_string().len() != length {
+        length = rest + length.to_string().len();
+    }
+    let mut record = format!("{} path=", length).into_bytes();
+    record.extend_from_slice(path);
+    record.push(b'\n');
+
+    let mut header = Header::new_ustar();
+    header.set_entry_type(tar::EntryType::XHeader);
+    header.set_path("PaxHeader")?;
+    header.set_size(record.len() as u64);
+    header.set_mode(DEFAULT_FILE_MODE);
+    header.set_mtime(mtime);
+    header.set_cksum();
+    tar.append(&header, &record[..])
 }
 
 /// Implements the `Stream` trait to produce chunks of the `.tar.gz` archive.
+///
+/// Chunks are read while the archive task is still writing, so archives larger than the duplex
+/// buffer do not stall. Once the reader reaches EOF the task's result is checked, surfacing any
+/// archiving error as the final item.
 impl Stream for TarGzStream {
     type Item = Result<Bytes, SurgeError>;
 
@@ -322,22 +1441,33 @@ impl Stream for TarGzStream {
             return std::task::Poll::Ready(None);
         }
 
-        // Poll the tarball creation task if it exists
+        // Poll the reader for chunks
+        match futures_util::ready!(Pin::new(&mut self.reader).poll_next(cx)) {
+            Some(Ok(bytes)) => {
+                debug!("Returning chunk of {} bytes", bytes.len());
+                return std::task::Poll::Ready(Some(Ok(bytes)));
+            }
+            Some(Err(e)) => {
+                error!("Stream read error: {}", e);
+                self.done = true;
+                return std::task::Poll::Ready(Some(Err(SurgeError::Io(e.to_string()))));
+            }
+            None => trace!("Reader reached EOF, checking tarball task"),
+        }
+
+        // The writer is closed: poll the tarball creation task for its result
         if let Some(task) = self.task.as_mut() {
-            match futures_util::ready!(Pin::new(task).poll(cx)) {
-                Ok(Ok(())) => {
-                    self.task = None; // Clear the task to prevent re-polling
-                    debug!("Tarball creation task completed successfully");
-                }
+            let result = futures_util::ready!(Pin::new(task).poll(cx));
+            self.task = None; // Clear the task to prevent re-polling
+            match result {
+                Ok(Ok(())) => debug!("Tarball creation task completed successfully"),
                 Ok(Err(e)) => {
                     error!("Tarball creation failed: {}", e);
-                    self.task = None; // Clear the task
                     self.done = true;
                     return std::task::Poll::Ready(Some(Err(e)));
                 }
                 Err(e) => {
                     error!("Task panicked: {}", e);
-                    self.task = None; // Clear the task
                     self.done = true;
                     return std::task::Poll::Ready(Some(Err(SurgeError::Io(format!(
                         "Task panicked: {}",
@@ -347,32 +1477,16 @@ impl Stream for TarGzStream {
             }
         }
 
-        // Poll the reader for chunks
-        match Pin::new(&mut self.reader).poll_next(cx) {
-            std::task::Poll::Ready(Some(Ok(bytes))) => {
-                debug!("Returning chunk of {} bytes", bytes.len());
-                std::task::Poll::Ready(Some(Ok(bytes)))
-            }
-            std::task::Poll::Ready(Some(Err(e))) => {
-                error!("Stream read error: {}", e);
-                self.done = true;
-                std::task::Poll::Ready(Some(Err(SurgeError::Io(e.to_string()))))
-            }
-            std::task::Poll::Ready(None) => {
-                debug!("Stream is complete");
-                self.done = true;
-                std::task::Poll::Ready(None)
-            }
-            std::task::Poll::Pending => {
-                trace!("Stream is pending");
-                std::task::Poll::Pending
-            }
-        }
+        debug!("Stream is complete");
+        self.done = true;
+        std::task::Poll::Ready(None)
     }
 }
 
 /// Publishes a project directory as a `.tar.gz` archive to a remote endpoint.
 ///
+/// Shorthand for [`publish_with`] with default options plus `headers` and `argv`.
+///
 /// # Arguments
 /// * `client` - The `SurgeSdk` client for making HTTP requests.
 /// * `project_path` - Path to the project directory.
@@ -390,12 +1504,22 @@ pub async fn publish(
     auth: &Auth,
     headers: Option<Vec<(String, String)>>,
     argv: Option<&[String]>,
-) -> Result<impl Stream<Item = Result<Event, SurgeError>>, SurgeError> {
-    publish_common(client, project_path, domain, auth, headers, argv, false).await
+) -> Result<impl Stream<Item = Result<Event, SurgeError>> + use<>, SurgeError> {
+    let options = PublishOptions::from_args(headers, argv);
+    publish_common(
+        client,
+        PublishSource::Directory(project_path),
+        domain,
+        auth,
+        &options,
+    )
+    .await
 }
 
 /// Publishes a work-in-progress (WIP) version of a project to a preview domain.
 ///
+/// Shorthand for [`publish_with`] with `headers`, `argv`, and [`PublishOptions::with_wip`].
+///
 /// # Arguments
 /// * `client` - The `SurgeSdk` client for making HTTP requests.
 /// * `project_path` - Path to the project directory.
@@ -413,33 +1537,442 @@ pub async fn publish_wip(
     auth: &Auth,
     headers: Option<Vec<(String, String)>>,
     argv: Option<&[String]>,
-) -> Result<impl Stream<Item = Result<Event, SurgeError>>, SurgeError> {
-    publish_common(client, project_path, domain, auth, headers, argv, true).await
+) -> Result<impl Stream<Item = Result<Event, SurgeError>> + use<>, SurgeError> {
+    let options = PublishOptions::from_args(headers, argv).with_wip(true);
+    publish_common(
+        client,
+        PublishSource::Directory(project_path),
+        domain,
+        auth,
+        &options,
+    )
+    .await
+}
+
+/// Publishes a project as a resumable, multi-part upload.
+///
+/// The Surge API does not currently expose a multi-part or resumable upload protocol: a deploy
+/// is always a single streamed `PUT` of the whole tarball. Until the server supports
+/// acknowledging individual parts, this function falls back to [`publish`] and behaves
+/// identically to it. It exists so callers can opt in today and transparently benefit once the
+/// server gains support.
+///
+/// # Arguments
+/// * `client` - The `SurgeSdk` client for making HTTP requests.
+/// * `project_path` - Path to the project directory.
+/// * `domain` - Target domain for publishing.
+/// * `auth` - Authentication credentials.
+/// * `headers` - Optional custom HTTP headers.
+/// * `argv` - Optional command-line arguments for the request.
+///
+/// # Returns
+/// A `Result` containing a stream of `Event`s or a `SurgeError` if the request fails.
+pub async fn publish_multipart(
+    client: &SurgeSdk,
+    project_path: &Path,
+    domain: &str,
+    auth: &Auth,
+    headers: Option<Vec<(String, String)>>,
+    argv: Option<&[String]>,
+) -> Result<impl Stream<Item = Result<Event, SurgeError>> + use<>, SurgeError> {
+    debug!("Multi-part uploads are not supported by the server, falling back to a single upload");
+    let options = PublishOptions::from_args(headers, argv);
+    publish_common(
+        client,
+        PublishSource::Directory(project_path),
+        domain,
+        auth,
+        &options,
+    )
+    .await
+}
+
+/// Publishes a project directory using explicit [`PublishOptions`].
+///
+/// # Arguments
+/// * `client` - The `SurgeSdk` client for making HTTP requests.
+/// * `project_path` - Path to the project directory.
+/// * `domain` - Target domain for publishing.
+/// * `auth` - Authentication credentials.
+/// * `options` - Options controlling packaging and request headers.
+///
+/// # Returns
+/// A `Result` containing a stream of `Event`s or a `SurgeError` if the request fails.
+pub async fn publish_with(
+    client: &SurgeSdk,
+    project_path: &Path,
+    domain: &str,
+    auth: &Auth,
+    options: &PublishOptions,
+) -> Result<impl Stream<Item = Result<Event, SurgeError>> + use<>, SurgeError> {
+    publish_common(
+        client,
+        PublishSource::Directory(project_path),
+        domain,
+        auth,
+        options,
+    )
+    .await
+}
+
+/// Publishes the contents of a `.zip`, `.tar`, or `.tar.gz` archive without extracting it.
+///
+/// The format is detected from the archive's magic bytes, falling back to its extension.
+/// `.surgeignore` rules are read from a `.surgeignore` entry at the root of the archive and
+/// applied to the entry paths, along with the junk patterns. The archive is unpacked in memory
+/// on a blocking thread; one whose files add up to more than [`MAX_ARCHIVE_UNPACKED_BYTES`] is
+/// rejected with `SurgeError::InvalidProject`.
+///
+/// # Arguments
+/// * `client` - The `SurgeSdk` client for making HTTP requests.
+/// * `archive` - Path to the archive file.
+/// * `domain` - Target domain for publishing.
+/// * `auth` - Authentication credentials.
+/// * `options` - Options controlling packaging and request headers.
+///
+/// # Returns
+/// A `Result` containing a stream of `Event`s or a `SurgeError` if the archive cannot be read or
+/// the request fails.
+pub async fn publish_archive(
+    client: &SurgeSdk,
+    archive: &Path,
+    domain: &str,
+    auth: &Auth,
+    options: &PublishOptions,
+) -> Result<impl Stream<Item = Result<Event, SurgeError>> + use<>, SurgeError> {
+    let source = PublishSource::Archive(archive);
+    publish_common(client, source, domain, auth, options).await
+}
+
+/// Publishes a single file, such as a standalone HTML page.
+///
+/// The file is uploaded under its own name, so it is served at `/<file name>`; name it
+/// `index.html` to serve it at the domain root. Ignore rules do not apply to it.
+///
+/// # Arguments
+/// * `client` - The `SurgeSdk` client for making HTTP requests.
+/// * `file` - Path to the file.
+/// * `domain` - Target domain for publishing.
+/// * `auth` - Authentication credentials.
+/// * `options` - Options controlling packaging and request headers.
+///
+/// # Returns
+/// A `Result` containing a stream of `Event`s or a `SurgeError` if `file` is not a file or the
+/// request fails.
+pub async fn publish_file(
+    client: &SurgeSdk,
+    file: &Path,
+    domain: &str,
+    auth: &Auth,
+    options: &PublishOptions,
+) -> Result<impl Stream<Item = Result<Event, SurgeError>> + use<>, SurgeError> {
+    let source = PublishSource::File(file);
+    publish_common(client, source, domain, auth, options).await
+}
+
+/// Returns the URL that a publish to `domain` is sent to.
+///
+/// Work-in-progress publishes are sent to a timestamped preview domain (`<millis>-<domain>`)
+/// chosen when the request is made; pass that preview domain to get its URL.
+///
+/// # Arguments
+/// * `client` - The `SurgeSdk` client whose endpoint is used.
+/// * `domain` - Target domain for publishing.
+///
+/// # Returns
+/// A `Result` containing the target `Url` or a `SurgeError` if `domain` is not a valid path.
+/// `domain` is normalized first, see [`normalize_domain`].
+pub fn publish_url(client: &SurgeSdk, domain: &str) -> Result<Url, SurgeError> {
+    Ok(client.config.endpoint.join(&normalize_domain(domain))?)
+}
+
+/// Publishes a project directory and returns the raw, un-parsed response.
+///
+/// Unlike [`publish_with`], the response status is not checked and the body is not decoded,
+/// so callers can post-process the stream themselves (for example, teeing it to a file).
+/// Use [`ndjson_event_stream`] to decode it into `Event`s.
+///
+/// # Arguments
+/// * `client` - The `SurgeSdk` client for making HTTP requests.
+/// * `project_path` - Path to the project directory.
+/// * `domain` - Target domain for publishing.
+/// * `auth` - Authentication credentials.
+/// * `options` - Options controlling packaging and request headers.
+///
+/// # Returns
+/// A `Result` containing the `reqwest::Response` or a `SurgeError` if the request could not be
+/// sent.
+pub async fn publish_raw(
+    client: &SurgeSdk,
+    project_path: &Path,
+    domain: &str,
+    auth: &Auth,
+    options: &PublishOptions,
+) -> Result<reqwest::Response, SurgeError> {
+    send_publish(
+        client,
+        PublishSource::Directory(project_path),
+        domain,
+        auth,
+        options,
+    )
+    .await
 }
 
 /// Builds a gitignore matcher for `.surgeignore` rules.
 ///
 /// # Arguments
 /// * `project_path` - Path to the project directory.
+/// * `options` - The publish options; junk patterns are added first when `exclude_junk` is set.
 ///
 /// # Returns
 /// A `Result` containing a `Gitignore` matcher or a `SurgeError` if the `.surgeignore` file is invalid.
-fn build_custom_gitignore(project_path: &Path) -> Result<ignore::gitignore::Gitignore, SurgeError> {
-    let mut ignore_builder = GitignoreBuilder::new(project_path);
+pub(crate) fn build_custom_gitignore(
+    project_path: &Path,
+    options: &PublishOptions,
+) -> Result<ignore::gitignore::Gitignore, SurgeError> {
     let surgeignore_path = project_path.join(".surgeignore");
 
-    if surgeignore_path.exists() {
+    let surgeignore = if surgeignore_path.exists() {
         debug!("Reading .surgeignore at: {:?}", surgeignore_path);
-        for line in fs::read_to_string(&surgeignore_path)
-            .map_err(|e| SurgeError::Io(e.to_string()))?
-            .lines()
+        Some(fs::read_to_string(&surgeignore_path).map_err(|e| SurgeError::Io(e.to_string()))?)
+    } else {
+        debug!(".surgeignore not found, using default ignore rules");
+        None
+    };
+
+    build_ignore_matcher(project_path, surgeignore.as_deref(), options)
+}
+
+/// Reports whether each of `paths` would be ignored by a publish, like `git check-ignore`.
+///
+/// Uses the same matcher as `calculate_metadata`: the junk patterns (unless disabled) followed by
+/// the project's `.surgeignore` and `ignore_rules`. A path is ignored if it or any of its parent
+/// directories is.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory.
+/// * `paths` - The paths to check, relative to `project_path` or absolute within it.
+/// * `options` - The publish options that determine the ignore rules.
+///
+/// # Returns
+/// A `Result` containing each path with `true` if it would be ignored, or a `SurgeError` if the
+/// rules cannot be read or a path lies outside the project.
+pub fn check_ignore(
+    project_path: &Path,
+    paths: &[&Path],
+    options: &PublishOptions,
+) -> Result<Vec<(PathBuf, bool)>, SurgeError> {
+    let gitignore = build_custom_gitignore(project_path, options)?;
+
+    paths
+        .iter()
+        .map(|path| {
+            let relative = if path.is_absolute() {
+                path.strip_prefix(project_path).map_err(|_| {
+                    SurgeError::Config(format!(
+                        "Path {} is outside the project directory",
+                        path.display()
+                    ))
+                })?
+            } else {
+                path
+            };
+            let is_dir = project_path.join(relative).is_dir();
+            let matched = gitignore.matched_path_or_any_parents(relative, is_dir);
+            trace!("check_ignore {}: {:?}", relative.display(), matched);
+            Ok((path.to_path_buf(), matched.is_ignore()))
+        })
+        .collect()
+}
+
+/// Proposes content-hashed names for the files matching `patterns`, for cache busting.
+///
+/// The hash is inserted before the last extension, so `css/app.css` becomes
+/// `css/app.3f8a09c2.css`; a file without an extension gets it appended. Only files that
+/// would be published with `options` are considered. Nothing is renamed: the mapping is
+/// returned so the caller can rename the files and rewrite references to them.
+///
+/// The hash is the SHA-256 of the file contents, as in [`compute_manifest`], truncated to its
+/// first 8 hex digits.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory.
+/// * `patterns` - Patterns in `.gitignore` syntax selecting the files, e.g. `*.css`.
+/// * `options` - The publish options that determine which files are included.
+///
+/// # Returns
+/// A `Result` mapping each selected path to its fingerprinted path, both relative to
+/// `project_path`, or a `SurgeError` if a pattern is invalid or a file cannot be read.
+///
+/// # Example
+/// ```rust,no_run
+/// use surge_sdk::{PublishOptions, stream::fingerprint_assets};
+/// use std::path::Path;
+///
+/// let renames =
+///     fingerprint_assets(Path::new("./dist"), &["*.css", "*.js"], &PublishOptions::default())
+///         .unwrap();
+/// for (from, to) in &renames {
+///     println!("{} -> {}", from.display(), to.display());
+/// }
+/// ```
+pub fn fingerprint_assets(
+    project_path: &Path,
+    patterns: &[&str],
+    options: &PublishOptions,
+) -> Result<HashMap<PathBuf, PathBuf>, SurgeError> {
+    let mut builder = GitignoreBuilder::new(project_path);
+    for pattern in patterns {
+        builder
+            .add_line(None, pattern)
+            .map_err(|e| SurgeError::Ignore(e.to_string()))?;
+    }
+    let selected = builder
+        .build()
+        .map_err(|e| SurgeError::Ignore(e.to_string()))?;
+
+    let mut renames = HashMap::new();
+    for file in plan(project_path, options)?.files {
+        if !selected
+            .matched_path_or_any_parents(&file.path, false)
+            .is_ignore()
         {
+            continue;
+        }
+
+        let path = project_path.join(&file.path);
+        let mut fingerprint =
+            sha256_hex(File::open(&path).map_err(|e| {
+                SurgeError::Io(format!("Failed to open {}: {}", path.display(), e))
+            })?)
+            .map_err(|e| SurgeError::Io(format!("Failed to read {}: {}", path.display(), e)))?;
+        fingerprint.truncate(8);
+
+        let name = match (file.path.file_stem(), file.path.extension()) {
+            (Some(stem), Some(ext)) => format!(
+                "{}.{}.{}",
+                stem.to_string_lossy(),
+                fingerprint,
+                ext.to_string_lossy()
+            ),
+            _ => format!(
+                "{}.{}",
+                file.path.file_name().unwrap_or_default().to_string_lossy(),
+                fingerprint
+            ),
+        };
+        trace!("Fingerprinted {} as {}", file.path.display(), name);
+        let renamed = file.path.with_file_name(name);
+        renames.insert(file.path, renamed);
+    }
+
+    debug!("Fingerprinted {} files", renames.len());
+    Ok(renames)
+}
+
+/// Computes the manifest a project would have once published, for comparison with a live one.
+///
+/// Keys are the published paths (`/css/app.css`), as in `SurgeSdk::manifest`. Entries carry
+/// the file size and `sha256sum`, which is what [`ManifestDiff`](crate::ManifestDiff) compares;
+/// `md5sum` is left empty.
+///
+/// # Arguments
+/// * `project_path` - Path to the project directory.
+/// * `options` - The publish options that determine which files are included.
+///
+/// # Returns
+/// A `Result` containing the `ManifestResponse`, or a `SurgeError` if the project cannot be
+/// planned or a file cannot be read.
+pub fn compute_manifest(
+    project_path: &Path,
+    options: &PublishOptions,
+) -> Result<ManifestResponse, SurgeError> {
+    plan(project_path, options)?
+        .files
+        .into_iter()
+        .map(|file| {
+            let path = project_path.join(&file.path);
+            let sha256_sum = sha256_hex(File::open(&path).map_err(|e| {
+                SurgeError::Io(format!("Failed to open {}: {}", path.display(), e))
+            })?)
+            .map_err(|e| SurgeError::Io(format!("Failed to read {}: {}", path.display(), e)))?;
+            let key: Vec<_> = file
+                .path
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect();
+            let entry = ManifestResponseValue {
+                size: file.size as i64,
+                md5_sum: String::new(),
+                sha256_sum,
+            };
+            Ok((format!("/{}", key.join("/")), entry))
+        })
+        .collect()
+}
+
+/// Computes the hex-encoded SHA-256 digest of everything read from `reader`.
+fn sha256_hex(mut reader: impl Read) -> std::io::Result<String> {
+    let mut context = ring::digest::Context::new(&ring::digest::SHA256);
+    let mut buffer = [0u8; 8192];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        context.update(&buffer[..read]);
+    }
+    Ok(context
+        .finish()
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Builds a gitignore matcher from junk patterns, the contents of a `.surgeignore` file, and
+/// the caller's ignore rules, in that order.
+///
+/// # Arguments
+/// * `root` - The directory the patterns are relative to.
+/// * `surgeignore` - The contents of the `.surgeignore` file, if there is one.
+/// * `options` - The publish options; junk patterns are added first when `exclude_junk` is set,
+///   and `ignore_rules` last.
+///
+/// # Returns
+/// A `Result` containing a `Gitignore` matcher or a `SurgeError` if a pattern is invalid.
+pub(crate) fn build_ignore_matcher(
+    root: &Path,
+    surgeignore: Option<&str>,
+    options: &PublishOptions,
+) -> Result<ignore::gitignore::Gitignore, SurgeError> {
+    let mut ignore_builder = GitignoreBuilder::new(root);
+
+    if options.exclude_junk {
+        for pattern in JUNK_PATTERNS {
             ignore_builder
-                .add_line(None, line)
+                .add_line(None, pattern)
                 .map_err(|e| SurgeError::Ignore(e.to_string()))?;
         }
-    } else {
-        debug!(".surgeignore not found, using default ignore rules");
+    }
+
+    let surgeignore = surgeignore.filter(|_| !options.replace_surgeignore);
+    for line in surgeignore.into_iter().flat_map(str::lines) {
+        let line = if options.expand_env_in_ignore {
+            expand_env(line, options.unset_env)
+        } else {
+            line.to_string()
+        };
+        ignore_builder
+            .add_line(None, &line)
+            .map_err(|e| SurgeError::Ignore(e.to_string()))?;
+    }
+
+    for rule in &options.ignore_rules {
+        ignore_builder
+            .add_line(None, rule)
+            .map_err(|e| SurgeError::Ignore(e.to_string()))?;
     }
 
     ignore_builder
@@ -447,98 +1980,552 @@ fn build_custom_gitignore(project_path: &Path) -> Result<ignore::gitignore::Giti
         .map_err(|e| SurgeError::Ignore(e.to_string()))
 }
 
+/// Substitutes `${VAR}` references in a `.surgeignore` line from the environment.
+///
+/// # Arguments
+/// * `line` - The raw `.surgeignore` line.
+/// * `unset_env` - How references to unset variables are handled.
+///
+/// # Returns
+/// The line with every set variable substituted.
+fn expand_env(line: &str, unset_env: UnsetEnvPolicy) -> String {
+    let mut expanded = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        let name = &after[..end];
+        match std::env::var(name) {
+            Ok(value) => expanded.push_str(&value),
+            Err(_) => {
+                if unset_env == UnsetEnvPolicy::Warn {
+                    warn!("Environment variable {} in .surgeignore is not set", name);
+                }
+                expanded.push_str(&rest[start..start + end + 3]);
+            }
+        }
+        rest = &after[end + 1..];
+    }
+
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Where the files of a publish are read from.
+#[derive(Debug, Clone, Copy)]
+enum PublishSource<'a> {
+    /// A project directory on disk.
+    Directory(&'a Path),
+    /// A `.zip`, `.tar`, or `.tar.gz` archive.
+    Archive(&'a Path),
+    /// A single file, archived relative to its parent directory.
+    File(&'a Path),
+}
+
+impl PublishSource<'_> {
+    fn path(&self) -> &Path {
+        match self {
+            PublishSource::Directory(path)
+            | PublishSource::Archive(path)
+            | PublishSource::File(path) => path,
+        }
+    }
+
+    /// Returns the directory the planned file paths are relative to.
+    fn base_dir(&self) -> &Path {
+        match self {
+            PublishSource::File(path) => path.parent().unwrap_or(Path::new("")),
+            _ => self.path(),
+        }
+    }
+}
+
 async fn publish_common(
     client: &SurgeSdk,
-    project_path: &Path,
+    source: PublishSource<'_>,
     domain: &str,
     auth: &Auth,
-    headers: Option<Vec<(String, String)>>,
-    argv: Option<&[String]>,
-    is_wip: bool,
-) -> Result<impl Stream<Item = Result<Event, SurgeError>>, SurgeError> {
+    options: &PublishOptions,
+) -> Result<impl Stream<Item = Result<Event, SurgeError>> + use<>, SurgeError> {
+    let options = &*client.publish_options(options);
+    validate_headers(options.headers.as_deref().unwrap_or_default())?;
+    if let Some(message) = &options.message {
+        validate_headers(&[("message".to_string(), message.clone())])?;
+    }
+    if !client.config.minimal_publish_headers {
+        // Fail before any settings are applied or files are read
+        argv_header(client, options)?;
+    }
+
+    if let Some(commit) = options
+        .source_commit
+        .as_deref()
+        .filter(|_| options.skip_same_commit)
+    {
+        if is_commit_live(client, domain, auth, commit).await {
+            info!(
+                "Commit {} is already live on {}, skipping upload",
+                commit, domain
+            );
+            return Ok(futures_util::stream::empty().left_stream());
+        }
+    }
+
+    let sends_timestamp =
+        !client.config.minimal_publish_headers && options.override_timestamp.is_none();
+    let mut retried = false;
+
+    let (res, skew) = loop {
+        let res = send_publish(client, source, domain, auth, options).await?;
+        let skew = clock_skew(res.headers()).filter(|_| sends_timestamp);
+        if res.status().is_success() {
+            break (res, skew);
+        }
+
+        let status = res.status();
+        if let Some(skew) = skew {
+            return Err(SurgeError::Config(format!(
+                "client clock skew detected: the local clock is {}s {} the server's \
+                 (request failed with status {})",
+                skew.num_seconds().abs(),
+                if skew.num_seconds() > 0 {
+                    "ahead of"
+                } else {
+                    "behind"
+                },
+                status
+            )));
+        }
+        let text = response_text(res).await?;
+        if options.retry_stale_upload && !retried && is_stale_upload(status, &text) {
+            warn!(
+                "Server rejected the upload as stale ({}), rebuilding the tarball and retrying",
+                status
+            );
+            retried = true;
+            continue;
+        }
+        error!("Request failed with status {}: {}", status, text);
+        return Err(SurgeError::api(
+            Some(status.as_u16()),
+            format!("Request failed with status: {}", status),
+            Value::String(text),
+        ));
+    };
+
+    if let Some(skew) = skew {
+        warn!(
+            "Client clock differs from the server's by {}s; the timestamp header may be rejected",
+            skew.num_seconds()
+        );
+    }
+
+    info!(
+        "Successfully uploaded {}tarball for domain: {}",
+        if options.wip { "WIP " } else { "" },
+        domain
+    );
+
+    let events = ndjson_event_stream_with(res, client.config.lossy_event_decoding);
+    let events = match &options.webhook {
+        Some(webhook) => {
+            notify_webhook(events, domain, webhook.clone(), client.client.clone()).left_stream()
+        }
+        None => events.right_stream(),
+    };
+    Ok(events.right_stream())
+}
+
+/// Passes a publish event stream through, posting its [`DeploySummary`] to `webhook` once the
+/// stream ends without an error.
+///
+/// # Arguments
+/// * `events` - The publish event stream.
+/// * `domain` - The domain that was published.
+/// * `webhook` - The URL to post the summary to.
+/// * `http` - The HTTP client to post with.
+///
+/// # Returns
+/// A stream yielding the same items as `events`.
+fn notify_webhook<S>(
+    events: S,
+    domain: &str,
+    webhook: Url,
+    http: reqwest::Client,
+) -> impl Stream<Item = Result<Event, SurgeError>> + use<S>
+where
+    S: Stream<Item = Result<Event, SurgeError>>,
+{
+    // `None` once an error is seen, so failed deploys are not reported
+    let summary = Arc::new(Mutex::new(Some(DeploySummary::new(domain))));
+    let recorder = Arc::clone(&summary);
+
+    // Boxed so the returned stream stays `Unpin` like the event stream it wraps
+    let finished = futures_util::stream::once(Box::pin(async move {
+        let Some(summary) = summary.lock().ok().and_then(|mut summary| summary.take()) else {
+            return;
+        };
+        let body = match summary.to_json_envelope() {
+            Ok(body) => body,
+            Err(e) => {
+                warn!(
+                    "Failed to serialize the deploy summary for the webhook: {}",
+                    e
+                );
+                return;
+            }
+        };
+        tokio::spawn(async move {
+            let result = http
+                .post(webhook.as_str())
+                .header("Content-Type", "application/json")
+                .timeout(WEBHOOK_TIMEOUT)
+                .body(body)
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status);
+            match result {
+                Ok(_) => debug!("Deploy webhook {} notified", webhook),
+                Err(e) => warn!("Deploy webhook {} failed: {}", webhook, e),
+            }
+        });
+    }))
+    .filter_map(|()| futures_util::future::ready(None));
+
+    events
+        .inspect(move |event| {
+            if let Ok(mut summary) = recorder.lock() {
+                match event {
+                    Ok(event) => {
+                        if let Some(summary) = summary.as_mut() {
+                            summary.record(event);
+                        }
+                    }
+                    Err(_) => *summary = None,
+                }
+            }
+        })
+        .chain(finished)
+}
+
+/// Returns `true` if the server rejected an upload because its `file-count` or `project-size`
+/// header does not match the tarball, which happens when files change during the upload.
+fn is_stale_upload(status: reqwest::StatusCode, body: &str) -> bool {
+    let body = body.to_ascii_lowercase();
+    matches!(status.as_u16(), 400 | 409 | 422)
+        && (body.contains("file-count") || body.contains("project-size"))
+        && (body.contains("mismatch") || body.contains("does not match"))
+}
+
+/// Returns how far the local clock is ahead of the server's `Date` header, if by more than
+/// [`MAX_CLOCK_SKEW_SECS`].
+fn clock_skew(headers: &reqwest::header::HeaderMap) -> Option<chrono::Duration> {
+    let server_date = CacheDiagnostics::from_headers(headers).date?;
+    let skew = chrono::Utc::now() - server_date;
+    debug!("Clock skew against the server: {}s", skew.num_seconds());
+    (skew.num_seconds().abs() > MAX_CLOCK_SKEW_SECS).then_some(skew)
+}
+
+/// Checks that custom headers have valid HTTP names and values.
+///
+/// # Returns
+/// `Ok(())`, or `SurgeError::Config` naming the first invalid header.
+fn validate_headers(headers: &[(String, String)]) -> Result<(), SurgeError> {
+    for (name, value) in headers {
+        if reqwest::header::HeaderName::from_bytes(name.as_bytes()).is_err() {
+            return Err(SurgeError::Config(format!(
+                "Invalid header name: {:?}",
+                name
+            )));
+        }
+        if reqwest::header::HeaderValue::from_str(value).is_err() {
+            return Err(SurgeError::Config(format!(
+                "Invalid value for header {}",
+                name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that a WIP prefix can be joined to the first label of `domain`.
+fn validate_wip_prefix(prefix: &str, domain: &str) -> Result<(), SurgeError> {
+    if prefix.is_empty()
+        || prefix.starts_with('-')
+        || prefix.ends_with('-')
+        || !prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(SurgeError::Config(format!(
+            "Invalid WIP prefix {:?}: use ASCII letters, digits and inner hyphens",
+            prefix
+        )));
+    }
+    let label = domain.split('.').next().unwrap_or_default();
+    if prefix.len() + 1 + label.len() > 63 {
+        return Err(SurgeError::Config(format!(
+            "WIP prefix {:?} makes the label of {} longer than 63 characters",
+            prefix, domain
+        )));
+    }
+    Ok(())
+}
+
+/// Returns `true` if the live deployment of `domain` records `commit`.
+///
+/// Any failure to fetch the metadata is treated as "not live", so the deploy goes ahead.
+async fn is_commit_live(client: &SurgeSdk, domain: &str, auth: &Auth, commit: &str) -> bool {
+    match client.metadata(domain, None, auth).await {
+        Ok(metadata) => metadata.records_commit(commit),
+        Err(e) => {
+            debug!("Could not fetch metadata for {}: {}", domain, e);
+            false
+        }
+    }
+}
+
+/// Builds and sends the publish request, returning the response without inspecting it.
+async fn send_publish(
+    client: &SurgeSdk,
+    source: PublishSource<'_>,
+    domain: &str,
+    auth: &Auth,
+    options: &PublishOptions,
+) -> Result<reqwest::Response, SurgeError> {
     info!(
         "Publishing {}to domain: {}",
-        if is_wip { "WIP " } else { "" },
+        if options.wip { "WIP " } else { "" },
         domain
     );
-    debug!("Project path: {:?}", project_path);
+    debug!("Publish source: {:?}", source);
 
-    let target_domain = if is_wip {
-        format!("{}-{}", chrono::Utc::now().timestamp_millis(), domain)
+    let target_domain = if options.wip {
+        let prefix = match &options.wip_prefix {
+            Some(prefix) => {
+                validate_wip_prefix(prefix, domain)?;
+                prefix.clone()
+            }
+            None => chrono::Utc::now().timestamp_millis().to_string(),
+        };
+        format!("{}-{}", prefix, domain)
     } else {
         domain.to_string()
     };
-    let url = format!("{}{}", client.config.endpoint, target_domain);
-    debug!("URL: {}", url);
-
-    let metadata = calculate_metadata(project_path)?;
-    let timestamp = chrono::Utc::now().to_rfc3339();
-
-    let argv_json = match argv {
-        Some(args) => serde_json::to_string(&json!({
-            "_": args,
-            "e": client.config.endpoint.as_str(),
-            "endpoint": client.config.endpoint.as_str(),
-            "s": is_wip,
-            "stage": is_wip
-        }))?,
-        None => json!({
-            "_": [],
-            "e": client.config.endpoint.as_str(),
-            "endpoint": client.config.endpoint.as_str(),
-            "s": is_wip,
-            "stage": is_wip
-        })
-        .to_string(),
+    let url = publish_url(client, &target_domain)?;
+    info!("Publish URL: {}", url);
+
+    if let Some(baseline_rev) = options.baseline_rev {
+        check_live_rev(client, domain, auth, baseline_rev).await?;
+    }
+
+    let (plan, tarball) = match source {
+        PublishSource::Directory(path) => (plan(path, options)?, None),
+        PublishSource::File(path) => (plan_file(path, options)?, None),
+        PublishSource::Archive(path) => {
+            // Decompressing and repacking is CPU-bound, so keep it off the async executor
+            let path = path.to_path_buf();
+            let repack_options = options.clone();
+            let preserve_permissions = client.config.preserve_permissions;
+            let compress = client.config.compression_enabled;
+            let (plan, tarball) = tokio::task::spawn_blocking(move || {
+                archive::repack(
+                    &path,
+                    &repack_options,
+                    preserve_permissions,
+                    compress,
+                    MAX_ARCHIVE_UNPACKED_BYTES,
+                )
+            })
+            .await??;
+            (plan, Some(tarball))
+        }
     };
+    for warning in &plan.warnings {
+        warn!("{}", warning);
+    }
+    if plan.file_count() == 0 {
+        error!("No files to publish in {:?}", source.path());
+        return Err(SurgeError::InvalidProject(format!(
+            "No files to publish in {}",
+            source.path().display()
+        )));
+    }
 
-    let mut req = client
-        .client
-        .put(&url)
-        .header("Content-Type", "application/gzip")
-        .header("Accept", "application/ndjson")
-        .header("version", &client.config.version)
-        .header("timestamp", timestamp)
-        .header("stage", is_wip.to_string())
-        .header("ssl", "null")
-        .header("argv", argv_json)
-        .header("file-count", metadata.file_count.to_string())
-        .header("project-size", metadata.project_size.to_string());
+    if let Some(settings) = &options.settings {
+        debug!("Applying settings to {} before the upload", domain);
+        client.apply_settings(domain, settings, auth).await?;
+    }
+
+    let http = match options.connect_timeout {
+        Some(connect_timeout) => client
+            .client_builder()
+            .connect_timeout(connect_timeout)
+            .build()
+            .map_err(|e| SurgeError::Http(e.to_string()))?,
+        None => client.client.clone(),
+    };
+    let content_type = if client.config.compression_enabled {
+        "application/gzip"
+    } else {
+        "application/x-tar"
+    };
+    let mut req = http
+        .put(url.as_str())
+        .header("Accept", "application/ndjson");
+    if client.config.upload_mode == UploadMode::RawGzip {
+        req = req.header("Content-Type", content_type);
+    }
+
+    if client.config.minimal_publish_headers {
+        debug!("Minimal publish headers enabled, omitting version/timestamp/stage/ssl/argv");
+    } else {
+        req = with_cli_headers(req, client, options)?;
+    }
+
+    req = req
+        .header("file-count", plan.file_count().to_string())
+        .header("project-size", plan.total_size.to_string());
+
+    if let Some(platform) = &options.platform {
+        req = req.header("platform", platform);
+    }
+
+    if let Some(message) = &options.message {
+        req = req.header("message", message);
+    }
 
-    if let Some(headers) = headers {
+    if let Some(commit) = &options.source_commit {
+        req = req.header("x-source-commit", commit);
+    }
+
+    if let Some(headers) = &options.headers {
         debug!("Adding custom headers: {:?}", headers);
         for (key, value) in headers {
-            req = req.header(&key, value);
+            req = req.header(key, value);
         }
     }
 
-    let tar_gz_stream = TarGzStream::new(project_path, 8192)?;
-    req = req.body(Body::wrap_stream(tar_gz_stream));
+    let body = match tarball {
+        Some(tarball) => Body::from(tarball),
+        None => {
+            let config = &client.config;
+            let base_dir = source.base_dir();
+            let root = options.archive_root.resolve(project_dir_name(base_dir));
+            let tar_gz_stream = TarGzStream::from_plan(
+                base_dir,
+                root,
+                plan,
+                8192,
+                config.preserve_permissions,
+                config.compression_enabled,
+                options,
+            );
+            Body::wrap_stream(tar_gz_stream)
+        }
+    };
+    req = match client.config.upload_mode {
+        UploadMode::RawGzip => req.body(body),
+        UploadMode::Multipart => {
+            let file_name = if client.config.compression_enabled {
+                "project.tar.gz"
+            } else {
+                "project.tar"
+            };
+            let part = Part::stream(body)
+                .file_name(file_name)
+                .mime_str(content_type)
+                .map_err(|e| SurgeError::Http(e.to_string()))?;
+            req.multipart(Form::new().part("file", part))
+        }
+    };
     req = client.apply_auth(req, auth);
+    if let Some(read_timeout) = options.read_timeout {
+        req = req.timeout(read_timeout);
+    }
 
     debug!("Sending request to {}", url);
-    let res = req.send().await?;
+    let res = client.send_request(req).await?;
     debug!("Response status: {}", res.status());
+    Ok(res)
+}
 
-    if !res.status().is_success() {
-        let status = res.status();
-        let text = res.text().await?;
-        error!("Request failed with status {}: {}", status, text);
+/// Fails with a `409` `SurgeError::Api` if the live revision of `domain` is newer than
+/// `baseline_rev`.
+async fn check_live_rev(
+    client: &SurgeSdk,
+    domain: &str,
+    auth: &Auth,
+    baseline_rev: u64,
+) -> Result<(), SurgeError> {
+    let live_rev = match client.metadata(domain, None, auth).await {
+        Ok(metadata) => metadata.rev,
+        Err(e) => {
+            warn!(
+                "Could not fetch the live revision of {}, publishing anyway: {}",
+                domain, e
+            );
+            return Ok(());
+        }
+    };
+    debug!("Live revision: {}, baseline: {}", live_rev, baseline_rev);
+
+    if u64::try_from(live_rev).is_ok_and(|live_rev| live_rev > baseline_rev) {
+        error!(
+            "Live revision {} of {} is newer than baseline {}",
+            live_rev, domain, baseline_rev
+        );
         return Err(SurgeError::api(
-            Some(status.as_u16()),
-            format!("Request failed with status: {}", status),
-            Value::String(text),
+            Some(409),
+            format!(
+                "Live revision {} of {} is newer than baseline {}; refusing to publish",
+                live_rev, domain, baseline_rev
+            ),
+            json!({ "liveRev": live_rev, "baselineRev": baseline_rev }),
         ));
     }
+    Ok(())
+}
 
-    info!(
-        "Successfully uploaded {}tarball for domain: {}",
-        if is_wip { "WIP " } else { "" },
-        target_domain
-    );
+/// Decodes an NDJSON publish response into a stream of `Event`s.
+///
+/// This is the decoding used by [`publish`]; pair it with [`publish_raw`] to inspect the
+/// response before parsing it.
+///
+/// # Arguments
+/// * `res` - A response whose body is a stream of newline-delimited JSON events.
+///
+/// # Returns
+/// A stream of `Event`s, yielding a `SurgeError` for lines that fail to decode.
+pub fn ndjson_event_stream(
+    res: reqwest::Response,
+) -> impl Stream<Item = Result<Event, SurgeError>> + use<> {
+    ndjson_event_stream_with(res, false)
+}
 
-    let bytes_stream = res.bytes_stream().map(|res| {
+/// Decodes an NDJSON publish response into a stream of `Event`s, optionally tolerating
+/// invalid UTF-8.
+///
+/// # Arguments
+/// * `res` - A response whose body is a stream of newline-delimited JSON events.
+/// * `lossy` - Whether to replace invalid UTF-8 with `U+FFFD` instead of yielding an error.
+///
+/// # Returns
+/// A stream of `Event`s, yielding a `SurgeError` for lines that fail to decode.
+pub fn ndjson_event_stream_with(
+    res: reqwest::Response,
+    lossy: bool,
+) -> impl Stream<Item = Result<Event, SurgeError>> + use<> {
+    let bytes_stream = res.bytes_stream().map(move |res| {
         res.map_err(SurgeError::from).and_then(|bytes| {
+            if lossy {
+                return Ok(String::from_utf8_lossy(&bytes).into_owned());
+            }
             String::from_utf8(bytes.to_vec()).map_err(|err| SurgeError::Io(err.to_string()))
         })
     });
@@ -546,7 +2533,7 @@ async fn publish_common(
     let config = NdjsonConfig::default().with_empty_line_handling(EmptyLineHandling::IgnoreEmpty);
     let ndjson = ndjson_stream::from_fallible_stream_with_config::<Value, _>(bytes_stream, config);
 
-    Ok(Box::pin(ndjson.map(|line| match line {
+    Box::pin(ndjson.map(|line| match line {
         Ok(raw_json) => match serde_json::from_value::<RawEvent>(raw_json) {
             Ok(raw_event) => {
                 let event = Event::from(raw_event);
@@ -566,20 +2553,718 @@ async fn publish_common(
             error!("Stream error: {:?}", e);
             Err(SurgeError::Io(format!("NDJSON stream error: {}", e)))
         }
-    })))
+    }))
+}
+
+/// Drains a publish event stream into a [`DeploySummary`].
+///
+/// # Arguments
+/// * `domain` - The domain that was published.
+/// * `events` - The event stream returned by one of the publish functions.
+///
+/// # Returns
+/// A `Result` containing the `DeploySummary`, or the first `SurgeError` yielded by the stream.
+pub async fn summarize(
+    domain: &str,
+    events: impl Stream<Item = Result<Event, SurgeError>>,
+) -> Result<DeploySummary, SurgeError> {
+    let mut summary = DeploySummary::new(domain);
+    let mut events = std::pin::pin!(events);
+    while let Some(event) = events.next().await {
+        summary.record(&event?);
+    }
+    debug!("Deploy summary: {:?}", summary);
+    Ok(summary)
+}
+
+/// Reduces a publish event stream to `(current, total)` progress tuples.
+///
+/// `Event::Progress` events are mapped to `(written, total)`, with `written` capped at `total`;
+/// a tuple is only emitted when the whole-number percentage or the total changes. Once the
+/// `UploadFin` event arrives, a final `(total, total)` is emitted if it was not already. All
+/// other events, and errors, are dropped: keep the full event stream if failures matter.
+///
+/// # Arguments
+/// * `events` - The event stream returned by one of the publish functions.
+///
+/// # Returns
+/// A stream of `(current, total)` tuples, e.g. for `indicatif::ProgressBar::set_position`.
+///
+/// # Example
+/// ```rust,no_run
+/// use futures_util::StreamExt;
+/// use surge_sdk::{Auth, Config, SURGE_API, SurgeSdk, stream::progress_only};
+/// use std::path::Path;
+///
+/// # async fn example() -> Result<(), surge_sdk::SurgeError> {
+/// let sdk = SurgeSdk::new(Config::new(SURGE_API, "0.1.0")?)?;
+/// let auth = Auth::Token("your-api-token".to_string());
+/// let events = sdk
+///     .publish(Path::new("./dist"), "example.surge.sh", &auth, None, None)
+///     .await?;
+/// let mut progress = std::pin::pin!(progress_only(events));
+/// while let Some((current, total)) = progress.next().await {
+///     println!("{}/{}", current, total);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn progress_only<S>(events: S) -> impl Stream<Item = (u64, u64)>
+where
+    S: Stream<Item = Result<Event, SurgeError>>,
+{
+    let mut last: Option<(u64, u64)> = None;
+    let mut last_percent: Option<(u64, u64)> = None;
+    events.filter_map(move |event| {
+        let next = match event {
+            Ok(Event::Progress { written, total, .. }) => {
+                let current = written.min(total);
+                let percent = (current * 100).checked_div(total).unwrap_or(100);
+                if last_percent == Some((percent, total)) {
+                    None
+                } else {
+                    last_percent = Some((percent, total));
+                    Some((current, total))
+                }
+            }
+            Ok(Event::UploadFin(_)) => match last {
+                Some((current, total)) if current != total => Some((total, total)),
+                _ => None,
+            },
+            _ => None,
+        };
+        if next.is_some() {
+            last = next;
+        }
+        futures_util::future::ready(next)
+    })
+}
+
+/// Adds the custom headers the Surge CLI sends with every publish.
+///
+/// These are `version`, `timestamp`, `stage`, `ssl`, and `argv`. They are skipped entirely
+/// when `Config::minimal_publish_headers` is enabled.
+fn with_cli_headers(
+    req: reqwest::RequestBuilder,
+    client: &SurgeSdk,
+    options: &PublishOptions,
+) -> Result<reqwest::RequestBuilder, SurgeError> {
+    let timestamp = options
+        .override_timestamp
+        .clone()
+        .unwrap_or_else(|| chrono::Utc::now().to_rfc3339());
+    let version = options
+        .override_version
+        .as_deref()
+        .unwrap_or(&client.config.version);
+    Ok(req
+        .header("version", version)
+        .header("timestamp", timestamp)
+        .header("stage", options.wip.to_string())
+        .header("ssl", "null")
+        .header("argv", argv_header(client, options)?))
+}
+
+/// Serializes the `argv` header of a publish.
+///
+/// # Returns
+/// The header value, or `SurgeError::Config` if it exceeds [`MAX_ARGV_HEADER_BYTES`].
+fn argv_header(client: &SurgeSdk, options: &PublishOptions) -> Result<String, SurgeError> {
+    let mut argv = json!({
+        "_": options.argv.as_deref().unwrap_or_default(),
+        "e": client.config.endpoint.as_str(),
+        "endpoint": client.config.endpoint.as_str(),
+        "s": options.wip,
+        "stage": options.wip
+    });
+    if let Some(platform) = &options.platform {
+        argv["platform"] = json!(platform);
+    }
+    if let Some(message) = &options.message {
+        argv["message"] = json!(message);
+    }
+    let argv_json = serde_json::to_string(&argv)?;
+
+    if argv_json.len() > MAX_ARGV_HEADER_BYTES {
+        return Err(SurgeError::Config(format!(
+            "argv too large for header: {} bytes, the limit is {}",
+            argv_json.len(),
+            MAX_ARGV_HEADER_BYTES
+        )));
+    }
+    Ok(argv_json)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use regex::Regex;
     use std::path::Path;
 
+    /// Plans `project_path` and creates the `TarGzStream` for it.
+    fn tar_gz_stream(
+        project_path: &Path,
+        options: &PublishOptions,
+    ) -> Result<TarGzStream, SurgeError> {
+        let plan = plan(project_path, options)?;
+        let root = options.archive_root.resolve(project_dir_name(project_path));
+        Ok(TarGzStream::from_plan(
+            project_path,
+            root,
+            plan,
+            1024,
+            false,
+            true,
+            options,
+        ))
+    }
+
+    /// Builds the archive for `project_path` and returns the paths of its entries.
+    async fn archive_entries(project_path: &Path, options: &PublishOptions) -> Vec<String> {
+        let stream = tar_gz_stream(project_path, options).unwrap();
+        let chunks: Vec<Bytes> = stream.map(|chunk| chunk.unwrap()).collect().await;
+        let data = chunks.concat();
+        let decoder = flate2::read::MultiGzDecoder::new(&data[..]);
+        let mut archive = tar::Archive::new(decoder);
+        let mut entries: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    #[tokio::test]
+    async fn test_skip_compression_extensions() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("index.html"), "<h1>hello</h1>".repeat(1000)).unwrap();
+        fs::write(dir.path().join("photo.PNG"), vec![0u8; 64 * 1024]).unwrap();
+        fs::write(dir.path().join("style.css"), "body{}".repeat(1000)).unwrap();
+
+        let archive_size = |options: &PublishOptions| {
+            let plan = plan(dir.path(), options).unwrap();
+            build_archive(dir.path(), Path::new(""), &plan, false, true, options)
+                .unwrap()
+                .len()
+        };
+        let options = PublishOptions::default().with_skip_compression_extensions([".png"]);
+        assert_eq!(options.skip_compression_extensions, vec!["png"]);
+
+        // The zeroed image is stored as is instead of deflated to almost nothing
+        let compressed = archive_size(&PublishOptions::default());
+        let stored = archive_size(&options);
+        assert!(compressed < 4 * 1024, "compressed size {}", compressed);
+        assert!(stored > 64 * 1024, "stored size {}", stored);
+
+        // Every entry is still readable across the gzip members
+        let entries = archive_entries(dir.path(), &options).await;
+        let project = project_dir_name(dir.path());
+        assert_eq!(
+            entries,
+            vec![
+                format!("{}/index.html", project),
+                format!("{}/photo.PNG", project),
+                format!("{}/style.css", project),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_progress_only() {
+        let progress = |written, total| {
+            Ok(Event::Progress {
+                id: "upload".to_string(),
+                written,
+                total,
+                end: None,
+            })
+        };
+        let upload_fin = serde_json::from_value(json!({
+            "type": "uploadFin",
+            "config": { "force": null, "redirect": null, "cors": null, "hsts": null, "ttl": null }
+        }))
+        .unwrap();
+        let events = futures_util::stream::iter(vec![
+            progress(0, 1000),
+            progress(4, 1000),
+            Ok(Event::Unknown {
+                event_type: "queued".to_string(),
+                data: Value::Null,
+            }),
+            progress(500, 1000),
+            Err(SurgeError::Network("dropped".to_string())),
+            progress(504, 1000),
+            progress(990, 1000),
+            Ok(Event::UploadFin(upload_fin)),
+        ]);
+
+        let tuples: Vec<(u64, u64)> = progress_only(events).collect().await;
+        assert_eq!(
+            tuples,
+            vec![(0, 1000), (500, 1000), (990, 1000), (1000, 1000)]
+        );
+    }
+
+    #[test]
+    fn test_publish_url_single_slash() {
+        for endpoint in ["https://surge.surge.sh", "https://surge.surge.sh/"] {
+            let config = crate::Config::new(endpoint, "0.1.0").unwrap();
+            let client = SurgeSdk::new(config).unwrap();
+            let url = publish_url(&client, "example.surge.sh").unwrap();
+            assert_eq!(url.as_str(), "https://surge.surge.sh/example.surge.sh");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_many_small_files() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..100 {
+            let sub = dir.path().join(format!("dir{}", i));
+            fs::create_dir(&sub).unwrap();
+            for j in 0..100 {
+                fs::write(sub.join(format!("{}.txt", j)), "x").unwrap();
+            }
+        }
+
+        let options = PublishOptions::default().with_max_open_files(2);
+        let metadata = calculate_metadata_with(dir.path(), &options).unwrap();
+        assert_eq!(metadata.file_count, 10_000);
+        assert_eq!(metadata.project_size, 10_000);
+
+        let stream = tar_gz_stream(dir.path(), &options).unwrap();
+        let chunks: Vec<_> = stream.collect().await;
+        assert!(chunks.iter().all(Result::is_ok));
+
+        // The planner's walk is capped the same way for the other symlink policies
+        let skip = options
+            .clone()
+            .with_symlinks(SymlinkPolicy::Skip, DuplicatePolicy::default());
+        assert_eq!(plan(dir.path(), &skip).unwrap().file_count(), 10_000);
+    }
+
+    #[tokio::test]
+    async fn test_closed_reader_fails_producer() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("index.html"), "<h1>hello</h1>").unwrap();
+
+        let plan = plan(dir.path(), &PublishOptions::default()).unwrap();
+        let mut stream = TarGzStream::from_plan(
+            dir.path(),
+            PathBuf::new(),
+            plan,
+            16,
+            false,
+            true,
+            &PublishOptions::default(),
+        );
+        let task = stream.task.take().unwrap();
+        drop(stream);
+
+        let result = tokio::time::timeout(std::time::Duration::from_secs(5), task)
+            .await
+            .expect("producer task stalled")
+            .unwrap();
+        match result {
+            Err(SurgeError::Io(message)) => assert!(message.starts_with("tarball write failed")),
+            other => panic!("expected an Io error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_long_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let long_name = format!("{}.html", "x".repeat(120));
+        let deep_dir: PathBuf = (0..5).map(|i| format!("{}{}", i, "d".repeat(59))).collect();
+        fs::write(dir.path().join(&long_name), "long").unwrap();
+        fs::create_dir_all(dir.path().join(&deep_dir)).unwrap();
+        fs::write(dir.path().join(&deep_dir).join("index.html"), "deep").unwrap();
+        fs::write(dir.path().join("short.html"), "short").unwrap();
+
+        let root = project_dir_name(dir.path()).to_string();
+        let mut expected = vec![
+            format!("{}/{}", root, long_name),
+            format!(
+                "{}/{}/index.html",
+                root,
+                String::from_utf8(tar_path_bytes(&deep_dir)).unwrap()
+            ),
+            format!("{}/short.html", root),
+        ];
+        expected.sort();
+        assert!(
+            expected
+                .iter()
+                .all(|path| path.len() > 100 || path.ends_with("short.html"))
+        );
+
+        for format in [TarFormat::Auto, TarFormat::Pax, TarFormat::Gnu] {
+            let options = PublishOptions::default().with_tar_format(format);
+            assert_eq!(
+                archive_entries(dir.path(), &options).await,
+                expected,
+                "{:?}",
+                format
+            );
+        }
+
+        let options = PublishOptions::default().with_tar_format(TarFormat::Ustar);
+        let stream = tar_gz_stream(dir.path(), &options).unwrap();
+        let chunks: Vec<_> = stream.collect().await;
+        assert!(matches!(
+            chunks.last(),
+            Some(Err(SurgeError::Archive { message, .. })) if message.contains("USTAR")
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_list_tar_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_path = dir.path().join("site");
+        fs::create_dir_all(project_path.join("css")).unwrap();
+        fs::write(project_path.join("index.html"), "<h1>hello</h1>").unwrap();
+        fs::write(project_path.join("css/app.css"), "body{}").unwrap();
+        fs::write(project_path.join(".DS_Store"), "junk").unwrap();
+        let script = project_path.join("run.sh");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let options = PublishOptions::default();
+        let entries = list_tar_entries(&project_path, &options, true).unwrap();
+        let expected = |path: &str, size, mode| TarEntry {
+            path: path.to_string(),
+            size,
+            mode,
+        };
+        assert_eq!(
+            entries,
+            vec![
+                expected("site/css/app.css", 6, 0o644),
+                expected("site/index.html", 14, 0o644),
+                expected("site/run.sh", 10, 0o755),
+            ]
+        );
+
+        // The listing matches what is actually archived
+        let archived = archive_entries(&project_path, &options).await;
+        let listed: Vec<_> = entries.into_iter().map(|entry| entry.path).collect();
+        assert_eq!(archived, listed);
+
+        let entries = list_tar_entries(&project_path, &options, false).unwrap();
+        assert!(entries.iter().all(|entry| entry.mode == 0o644));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_preserve_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("run.sh");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o4777)).unwrap();
+
+        for (preserve, expected) in [(false, 0o644), (true, 0o755)] {
+            let plan = plan(dir.path(), &PublishOptions::default()).unwrap();
+            let stream = TarGzStream::from_plan(
+                dir.path(),
+                PathBuf::new(),
+                plan,
+                1024,
+                preserve,
+                true,
+                &PublishOptions::default(),
+            );
+            let chunks: Vec<Bytes> = stream.map(|chunk| chunk.unwrap()).collect().await;
+            let data = chunks.concat();
+            let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(&data[..]));
+            let entry = archive.entries().unwrap().next().unwrap().unwrap();
+            assert_eq!(entry.header().mode().unwrap(), expected);
+        }
+    }
+
     #[test]
     fn test_invalid_directory() {
-        let result = TarGzStream::new(Path::new("nonexistent"), 1024);
+        let result = tar_gz_stream(Path::new("nonexistent"), &PublishOptions::default());
         assert!(matches!(result, Err(SurgeError::Io(_))));
         if let Err(SurgeError::Io(msg)) = result {
             assert!(msg.contains("Invalid project directory"));
         }
     }
+
+    #[tokio::test]
+    async fn test_exclude_junk() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_path = dir.path().join("site");
+        fs::create_dir(&project_path).unwrap();
+        fs::write(project_path.join("index.html"), "hello").unwrap();
+        fs::write(project_path.join(".DS_Store"), "junk").unwrap();
+
+        let entries = archive_entries(&project_path, &PublishOptions::default()).await;
+        assert_eq!(entries, vec!["site/index.html"]);
+        let metadata = calculate_metadata(&project_path).unwrap();
+        assert_eq!(metadata.file_count, 1);
+
+        let options = PublishOptions::default().with_exclude_junk(false);
+        let entries = archive_entries(&project_path, &options).await;
+        assert_eq!(entries, vec!["site/.DS_Store", "site/index.html"]);
+        let metadata = calculate_metadata_with(&project_path, &options).unwrap();
+        assert_eq!(metadata.file_count, 2);
+    }
+
+    #[test]
+    fn test_check_ignore() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("build/assets")).unwrap();
+        fs::create_dir_all(dir.path().join("logs")).unwrap();
+        fs::write(
+            dir.path().join(".surgeignore"),
+            "build/\n*.log\n!keep.log\ndocs/**/*.tmp\n",
+        )
+        .unwrap();
+
+        let paths = [
+            Path::new("index.html"),
+            Path::new("build"),
+            Path::new("build/assets/app.js"),
+            Path::new("logs/debug.log"),
+            Path::new("logs/keep.log"),
+            Path::new("docs/a/b/draft.tmp"),
+            Path::new(".DS_Store"),
+        ];
+        let results = check_ignore(dir.path(), &paths, &PublishOptions::default()).unwrap();
+        let ignored: Vec<bool> = results.iter().map(|(_, ignored)| *ignored).collect();
+        assert_eq!(ignored, vec![false, true, true, true, false, true, true]);
+
+        let absolute = dir.path().join("logs/keep.log");
+        let results = check_ignore(
+            dir.path(),
+            &[absolute.as_path()],
+            &PublishOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(results, vec![(absolute.clone(), false)]);
+
+        let outside = check_ignore(
+            dir.path(),
+            &[Path::new("/elsewhere/file.txt")],
+            &PublishOptions::default(),
+        );
+        assert!(matches!(outside, Err(SurgeError::Config(_))));
+    }
+
+    #[test]
+    fn test_compute_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("css")).unwrap();
+        fs::write(dir.path().join("index.html"), "hello").unwrap();
+        fs::write(dir.path().join("css/app.css"), "").unwrap();
+
+        let manifest = compute_manifest(dir.path(), &PublishOptions::default()).unwrap();
+        assert_eq!(manifest.len(), 2);
+        let index = &manifest["/index.html"];
+        assert_eq!(index.size, 5);
+        assert_eq!(
+            index.sha256_sum,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert_eq!(
+            manifest["/css/app.css"].sha256_sum,
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("css")).unwrap();
+        fs::write(dir.path().join("css/app.css"), "body {}").unwrap();
+        fs::write(dir.path().join("app.min.js"), "run()").unwrap();
+        fs::write(dir.path().join("LICENSE"), "MIT").unwrap();
+        fs::write(dir.path().join("index.html"), "hello").unwrap();
+
+        let renames = fingerprint_assets(
+            dir.path(),
+            &["*.css", "*.js", "LICENSE"],
+            &PublishOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(renames.len(), 3);
+        assert!(!renames.contains_key(Path::new("index.html")));
+        let css = renames[Path::new("css/app.css")].to_str().unwrap();
+        assert!(
+            Regex::new(r"^css/app\.[0-9a-f]{8}\.css$")
+                .unwrap()
+                .is_match(css)
+        );
+        let js = renames[Path::new("app.min.js")].to_str().unwrap();
+        assert!(
+            Regex::new(r"^app\.min\.[0-9a-f]{8}\.js$")
+                .unwrap()
+                .is_match(js)
+        );
+        let license = renames[Path::new("LICENSE")].to_str().unwrap();
+        assert!(
+            Regex::new(r"^LICENSE\.[0-9a-f]{8}$")
+                .unwrap()
+                .is_match(license)
+        );
+
+        // Same contents, same fingerprint; different contents, different fingerprint
+        fs::write(dir.path().join("copy.css"), "body {}").unwrap();
+        fs::write(dir.path().join("other.css"), "main {}").unwrap();
+        let renames =
+            fingerprint_assets(dir.path(), &["*.css"], &PublishOptions::default()).unwrap();
+        let hash = |path: &str| {
+            let renamed = renames[Path::new(path)].to_str().unwrap().to_string();
+            renamed.rsplit('.').nth(1).unwrap().to_string()
+        };
+        assert_eq!(hash("copy.css"), hash("css/app.css"));
+        assert_ne!(hash("copy.css"), hash("other.css"));
+        assert_eq!(hash("copy.css"), sha256_hex(&b"body {}"[..]).unwrap()[..8]);
+    }
+
+    #[test]
+    fn test_ignore_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("index.html"), "hello").unwrap();
+        fs::write(dir.path().join("debug.log"), "trace").unwrap();
+
+        let options = PublishOptions::default().with_ignore_rules(["*.log"], false);
+        let metadata = calculate_metadata_with(dir.path(), &options).unwrap();
+        assert_eq!(metadata.file_count, 1);
+        let planned = plan(dir.path(), &options).unwrap();
+        assert_eq!(planned.files[0].path, PathBuf::from("index.html"));
+
+        // Appended rules take precedence over .surgeignore; replacing drops it entirely
+        fs::write(dir.path().join(".surgeignore"), "*.html\n").unwrap();
+        let metadata = calculate_metadata_with(dir.path(), &options).unwrap();
+        assert_eq!(metadata.file_count, 1);
+        let replaced = PublishOptions::default().with_ignore_rules(vec!["*.log".to_string()], true);
+        let planned = plan(dir.path(), &replaced).unwrap();
+        let paths: Vec<_> = planned.files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from(".surgeignore"), PathBuf::from("index.html")]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_single_file_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_path = dir.path().join("site");
+        fs::create_dir(&project_path).unwrap();
+        let page = project_path.join("page.html");
+        fs::write(&page, "hello").unwrap();
+        fs::write(project_path.join("other.html"), "not uploaded").unwrap();
+
+        let source = PublishSource::File(&page);
+        let plan = plan_file(&page, &PublishOptions::default()).unwrap();
+        assert_eq!(plan.file_count(), 1);
+        let root = PathBuf::from(project_dir_name(source.base_dir()));
+        let stream = TarGzStream::from_plan(
+            source.base_dir(),
+            root,
+            plan,
+            1024,
+            false,
+            true,
+            &PublishOptions::default(),
+        );
+        let chunks: Vec<Bytes> = stream.map(|chunk| chunk.unwrap()).collect().await;
+        let data = chunks.concat();
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(&data[..]));
+        let entries: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+            .collect();
+        assert_eq!(entries, vec!["site/page.html"]);
+    }
+
+    #[tokio::test]
+    async fn test_archive_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_path = dir.path().join("dist");
+        fs::create_dir_all(project_path.join("css")).unwrap();
+        fs::write(project_path.join("index.html"), "hello").unwrap();
+        fs::write(project_path.join("css/site.css"), "body {}").unwrap();
+
+        let options = PublishOptions::default().with_archive_root(Some("public".to_string()));
+        let entries = archive_entries(&project_path, &options).await;
+        assert_eq!(entries, vec!["public/css/site.css", "public/index.html"]);
+
+        let options = PublishOptions::default().with_archive_root(None);
+        let entries = archive_entries(&project_path, &options).await;
+        assert_eq!(entries, vec!["css/site.css", "index.html"]);
+    }
+
+    #[tokio::test]
+    async fn test_large_archive_does_not_stall() {
+        let dir = tempfile::tempdir().unwrap();
+        // Incompressible content, so the archive is far larger than the duplex buffer
+        let data: Vec<u8> = (0..256 * 1024).map(|_| rand::random::<u8>()).collect();
+        fs::write(dir.path().join("blob.bin"), data).unwrap();
+
+        let stream = tar_gz_stream(dir.path(), &PublishOptions::default()).unwrap();
+        let chunks = tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            stream.collect::<Vec<_>>().await
+        })
+        .await
+        .expect("archive stream stalled");
+        assert!(chunks.iter().all(Result::is_ok));
+    }
+
+    /// `/proc/self/mem` reports itself as a regular file but fails with `EIO` on read, which
+    /// simulates a file that becomes unreadable while it is being archived.
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_unreadable_file_yields_error() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("index.html"), "hello").unwrap();
+        std::os::unix::fs::symlink("/proc/self/mem", dir.path().join("broken.bin")).unwrap();
+
+        let stream = tar_gz_stream(dir.path(), &PublishOptions::default()).unwrap();
+        let items = tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            stream.collect::<Vec<_>>().await
+        })
+        .await
+        .expect("archive stream stalled");
+        let error = items.last().unwrap().as_ref().unwrap_err();
+        assert!(matches!(error, SurgeError::Archive { path, .. } if path == "broken.bin"));
+        assert!(
+            error
+                .to_string()
+                .starts_with("Failed to read broken.bin during tarball creation: ")
+        );
+    }
+
+    #[test]
+    fn test_expand_env_in_ignore() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_path = dir.path().join("site");
+        fs::create_dir(&project_path).unwrap();
+        fs::write(project_path.join("index.html"), "hello").unwrap();
+        fs::write(project_path.join("cache.bin"), "cache").unwrap();
+        fs::write(
+            project_path.join(".surgeignore"),
+            "${SURGE_SDK_TEST_IGNORED}\n${SURGE_SDK_TEST_UNSET}\n",
+        )
+        .unwrap();
+        // SAFETY: the variable name is unique to this test, so no other thread reads it.
+        unsafe { std::env::set_var("SURGE_SDK_TEST_IGNORED", "cache.bin") };
+
+        let metadata = calculate_metadata(&project_path).unwrap();
+        assert_eq!(metadata.file_count, 3);
+
+        let options =
+            PublishOptions::default().with_expand_env_in_ignore(true, UnsetEnvPolicy::Literal);
+        let metadata = calculate_metadata_with(&project_path, &options).unwrap();
+        assert_eq!(metadata.file_count, 2);
+
+        assert_eq!(
+            expand_env("${SURGE_SDK_TEST_UNSET}/x", UnsetEnvPolicy::Literal),
+            "${SURGE_SDK_TEST_UNSET}/x"
+        );
+    }
 }
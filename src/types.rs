@@ -12,6 +12,11 @@
 //! - `Auth`: An enum supporting token-based or username/password authentication for API requests.
 //! - `Event`: A struct representing events from NDJSON streaming endpoints, such as those used in
 //!   publishing or encryption operations, with a type identifier and arbitrary JSON data.
+//! - `DeploySummary`: The outcome of a publish, collected from its event stream.
+//! - `DnsRecordInput`: A validated DNS or zone record for `dns_add_record`/`zone_add_record`.
+//! - `LiveHeaders`: The status and headers served by a live deployment.
+//! - `CacheDiagnostics`: Cache-related response headers (`Date`, `Age`, `X-Cache`) captured for
+//!   investigating stale deployments.
 //!
 //! The types are implemented with `serde` for deserialization and include display formatting for
 //! easier debugging and logging. The module is intended to be used by other parts of the SDK,
@@ -53,9 +58,26 @@
 //!```
 //!
 
+use chrono::{DateTime, Utc};
+use log::debug;
+use reqwest::header::HeaderMap;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
-use std::fmt;
+use std::{
+    collections::HashMap,
+    fmt,
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+
+use crate::{
+    error::SurgeError,
+    responses::{BuildOutput, DeployConfig, ManifestDiff, UploadFinResponse},
+};
 
 /// Authentication credentials for API requests.
 ///
@@ -73,6 +95,173 @@ pub enum Auth {
     },
 }
 
+impl Auth {
+    /// Reads the credentials for `host` from the user's `.netrc` file.
+    ///
+    /// The file is `$NETRC` if set, otherwise `.netrc` in the home directory (`$HOME`, or
+    /// `%USERPROFILE%` on Windows), as with curl and git. The Surge CLI stores its credentials
+    /// there under `machine surge.surge.sh`.
+    ///
+    /// # Arguments
+    /// * `host` - The machine name to look up, e.g. `surge.surge.sh`.
+    ///
+    /// # Returns
+    /// `Auth::UserPass` with the entry's `login` and `password`, `SurgeError::Auth` if there is
+    /// no entry for `host`, or `SurgeError::Config` if the file cannot be found, read, or parsed.
+    pub fn from_netrc(host: &str) -> Result<Auth, SurgeError> {
+        let path = match std::env::var_os("NETRC") {
+            Some(path) => path.into(),
+            None => std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .map(|home| Path::new(&home).join(".netrc"))
+                .ok_or_else(|| {
+                    SurgeError::Config("Cannot locate .netrc: no home directory".to_string())
+                })?,
+        };
+        Self::from_netrc_file(&path, host)
+    }
+
+    /// Reads the credentials for `host` from a netrc file at `path`.
+    ///
+    /// A `machine` entry for `host` takes precedence over a `default` entry. `account` values
+    /// and `macdef` macros are ignored.
+    ///
+    /// # Arguments
+    /// * `path` - The netrc file.
+    /// * `host` - The machine name to look up.
+    ///
+    /// # Returns
+    /// See [`Auth::from_netrc`].
+    pub fn from_netrc_file(path: &Path, host: &str) -> Result<Auth, SurgeError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| SurgeError::Config(format!("Failed to read {}: {}", path.display(), e)))?;
+        let malformed = |reason: String| {
+            SurgeError::Config(format!("Malformed {}: {}", path.display(), reason))
+        };
+
+        // Each entry is (machine, login, password); `None` as the machine marks `default`
+        let mut entries: Vec<(Option<String>, Option<String>, Option<String>)> = Vec::new();
+        let mut lines = contents.lines();
+        while let Some(line) = lines.next() {
+            let mut tokens = line.split_whitespace();
+            while let Some(token) = tokens.next() {
+                let mut value = |key: &str| {
+                    tokens
+                        .next()
+                        .map(str::to_string)
+                        .ok_or_else(|| malformed(format!("`{}` without a value", key)))
+                };
+                match token {
+                    "machine" => entries.push((Some(value(token)?), None, None)),
+                    "default" => entries.push((None, None, None)),
+                    "login" | "password" | "account" => {
+                        let value = value(token)?;
+                        let entry = entries.last_mut().ok_or_else(|| {
+                            malformed(format!("`{}` outside a machine entry", token))
+                        })?;
+                        match token {
+                            "login" => entry.1 = Some(value),
+                            "password" => entry.2 = Some(value),
+                            _ => {}
+                        }
+                    }
+                    "macdef" => {
+                        // A macro runs until the next empty line
+                        for line in lines.by_ref() {
+                            if line.trim().is_empty() {
+                                break;
+                            }
+                        }
+                        break;
+                    }
+                    _ if token.starts_with('#') => break,
+                    _ => return Err(malformed(format!("unexpected token `{}`", token))),
+                }
+            }
+        }
+
+        let (_, login, password) = entries
+            .iter()
+            .find(|(machine, _, _)| machine.as_deref() == Some(host))
+            .or_else(|| entries.iter().find(|(machine, _, _)| machine.is_none()))
+            .ok_or_else(|| {
+                SurgeError::Auth(format!("No entry for {} in {}", host, path.display()))
+            })?;
+        match (login, password) {
+            (Some(username), Some(password)) => Ok(Auth::UserPass {
+                username: username.clone(),
+                password: password.clone(),
+            }),
+            _ => Err(malformed(format!(
+                "the entry for {} needs both a login and a password",
+                host
+            ))),
+        }
+    }
+}
+
+/// The response of a live deployment, captured by `SurgeSdk::inspect_live_headers`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveHeaders {
+    /// The URL of the final response, after any followed redirects.
+    pub url: url::Url,
+    /// The HTTP status of the final response.
+    pub status: u16,
+    /// The response headers, keyed by lowercase name. Repeated headers are joined with `, `.
+    pub headers: HashMap<String, String>,
+}
+
+impl LiveHeaders {
+    /// Returns the value of a header, looked up case-insensitively.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+}
+
+/// Cache-related response headers, useful when investigating stale deployments.
+///
+/// Captured from the `Date`, `Age`, and `X-Cache` headers of a response. Missing or malformed
+/// headers are left as `None`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CacheDiagnostics {
+    /// The `Date` header, parsed as an HTTP date.
+    pub date: Option<DateTime<Utc>>,
+    /// The `Age` header: seconds the response has been held in a cache.
+    pub age: Option<u64>,
+    /// The raw `X-Cache` header (e.g. `HIT` or `MISS`).
+    pub x_cache: Option<String>,
+}
+
+impl CacheDiagnostics {
+    /// Extracts cache diagnostics from a set of response headers.
+    ///
+    /// # Arguments
+    /// * `headers` - The response headers to inspect.
+    ///
+    /// # Returns
+    /// A `CacheDiagnostics` with every header that was present and well-formed.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header_str = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+        Self {
+            date: header_str("date")
+                .and_then(|v| httpdate::parse_http_date(v).ok())
+                .map(DateTime::<Utc>::from),
+            age: header_str("age").and_then(|v| v.trim().parse().ok()),
+            x_cache: header_str("x-cache").map(|v| v.trim().to_string()),
+        }
+    }
+
+    /// Returns `true` if the `X-Cache` header reports a cache hit.
+    pub fn is_hit(&self) -> bool {
+        self.x_cache
+            .as_deref()
+            .is_some_and(|v| v.to_ascii_uppercase().contains("HIT"))
+    }
+}
+
 // FIX: Change comments lang in the future
 /// Evento deserializado en bruto, usado como paso previo para mapear al enum `Event`.
 ///
@@ -149,8 +338,13 @@ pub struct Config {
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Metadata {
-    #[serde(rename = "buildTime")]
-    pub build_time: Option<String>,
+    /// How long the build took, in seconds.
+    #[serde(
+        rename = "buildTime",
+        default,
+        deserialize_with = "crate::responses::lenient_f64"
+    )]
+    pub build_time: Option<f64>,
     #[serde(rename = "cliVersion")]
     pub cli_version: String,
     pub cmd: String,
@@ -179,6 +373,20 @@ pub struct Metadata {
     pub upload_start_time: u64,
 }
 
+impl Metadata {
+    /// Returns the known keys of `output`, see [`BuildOutput`].
+    pub fn build_output(&self) -> BuildOutput {
+        BuildOutput::from_value(&self.output)
+    }
+
+    /// Returns the known keys of `config`, see [`DeployConfig`].
+    pub fn deploy_config(&self) -> DeployConfig {
+        serde_json::to_value(&self.config)
+            .map(|config| DeployConfig::from_value(&config))
+            .unwrap_or_default()
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct InfoEventData {
     pub certs: Vec<CertDetails>,
@@ -199,6 +407,20 @@ pub struct SubscriptionEventData {
     pub data: Option<Value>,
 }
 
+/// The `type` strings of the events the SDK decodes into a dedicated `Event` variant.
+///
+/// Any other type becomes `Event::Unknown`, which usually means the server added an event
+/// worth supporting.
+pub const EVENT_TYPES: &[&str] = &[
+    "cert",
+    "progress",
+    "info",
+    "ip",
+    "subscription",
+    "uploadFin",
+    "fin",
+];
+
 #[derive(Debug, Deserialize)]
 pub enum Event {
     Cert(CertEventData),
@@ -211,6 +433,8 @@ pub enum Event {
     Info(InfoEventData),
     Ip(IpEventData),
     Subscription(SubscriptionEventData),
+    /// The terminal event sent once the upload has been processed.
+    UploadFin(UploadFinResponse),
     Unknown {
         event_type: String,
         data: Value,
@@ -305,14 +529,79 @@ impl From<RawEvent> for Event {
                     },
                 }
             }
-            _ => Event::Unknown {
-                event_type: raw.event_type,
-                data: raw.data,
-            },
+            "uploadFin" | "fin" => {
+                // `type` is consumed by `RawEvent`, but `UploadFinResponse` records it too
+                let mut value = raw.data.clone();
+                if let Some(object) = value.as_object_mut() {
+                    object.insert("type".to_string(), Value::String(raw.event_type.clone()));
+                }
+                match serde_json::from_value::<UploadFinResponse>(value) {
+                    Ok(data) => Event::UploadFin(data),
+                    Err(_) => Event::Unknown {
+                        event_type: raw.event_type,
+                        data: raw.data,
+                    },
+                }
+            }
+            _ => {
+                debug!("Received an event of unknown type: {}", raw.event_type);
+                Event::Unknown {
+                    event_type: raw.event_type,
+                    data: raw.data,
+                }
+            }
         }
     }
 }
 
+impl Event {
+    /// Parses an event from one JSON object of an NDJSON event stream.
+    ///
+    /// # Arguments
+    /// * `value` - The event object, with its `type` field.
+    ///
+    /// # Returns
+    /// A `Result` containing the `Event`, or `SurgeError::Json` if `value` has no string `type`.
+    /// Events of an unknown type, or whose data does not match their type, become
+    /// `Event::Unknown`.
+    ///
+    /// # Example
+    /// ```
+    /// use serde_json::json;
+    /// use surge_sdk::Event;
+    ///
+    /// let event = Event::from_json(json!({ "type": "fin", "config": {} })).unwrap();
+    /// assert!(event.is_terminal());
+    ///
+    /// let event = Event::from_json(json!({ "type": "queued" })).unwrap();
+    /// assert!(matches!(event, Event::Unknown { .. }));
+    /// ```
+    pub fn from_json(value: Value) -> Result<Self, SurgeError> {
+        let raw: RawEvent = serde_json::from_value(value)?;
+        Ok(raw.into())
+    }
+
+    /// Returns the event types the SDK decodes, see [`EVENT_TYPES`].
+    ///
+    /// # Example
+    /// ```
+    /// use surge_sdk::Event;
+    ///
+    /// assert!(Event::known_types().contains(&"progress"));
+    /// ```
+    pub fn known_types() -> &'static [&'static str] {
+        EVENT_TYPES
+    }
+
+    /// Returns `true` if this event marks the end of a publish.
+    ///
+    /// # Returns
+    /// `true` for `Event::UploadFin`, `false` otherwise.
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Event::UploadFin(_))
+    }
+}
+
 impl fmt::Display for Event {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -367,6 +656,13 @@ impl fmt::Display for Event {
             }
             Event::Ip(data) => write!(f, "[Event: ip] IP: {}", data.ip),
             Event::Subscription(_) => write!(f, "[Event: subscription] Subscription event"),
+            Event::UploadFin(data) => {
+                write!(
+                    f,
+                    "[Event: {}] Upload finished",
+                    data.upload_fin_response_type
+                )
+            }
             Event::Unknown { event_type, data } => write!(
                 f,
                 "[Event: {}] {}",
@@ -376,3 +672,328 @@ impl fmt::Display for Event {
         }
     }
 }
+
+/// The outcome of a publish, collected from its event stream.
+///
+/// Built by [`crate::stream::summarize`]. Fields that depend on the final `info` event are
+/// `None` if the server did not send one.
+///
+/// For passing a summary between processes, use [`to_json_envelope`](Self::to_json_envelope)
+/// and [`from_json_envelope`](Self::from_json_envelope), which wrap it in a versioned format.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeploySummary {
+    /// The domain that was published.
+    pub domain: String,
+    /// The URLs the deployment is served from.
+    pub urls: Vec<String>,
+    /// The revision created by the publish.
+    pub rev: Option<u64>,
+    /// The number of public files in the deployment.
+    pub file_count: Option<u64>,
+    /// The total size of the public files, in bytes.
+    pub total_size: Option<u64>,
+    /// The number of events received.
+    pub events: usize,
+}
+
+/// The current schema version of the [`DeploySummary`] JSON envelope.
+///
+/// Bumped whenever the envelope changes in a way older readers cannot parse.
+pub const DEPLOY_SUMMARY_SCHEMA: u32 = 1;
+
+/// The versioned JSON envelope written by [`DeploySummary::to_json_envelope`].
+#[derive(Serialize)]
+struct DeploySummaryEnvelope<'a> {
+    schema: u32,
+    #[serde(flatten)]
+    summary: &'a DeploySummary,
+}
+
+impl DeploySummary {
+    /// Creates an empty summary for `domain`.
+    pub fn new(domain: impl Into<String>) -> Self {
+        Self {
+            domain: domain.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Serializes the summary into a versioned JSON envelope.
+    ///
+    /// The envelope is a JSON object with a `schema` field set to [`DEPLOY_SUMMARY_SCHEMA`]
+    /// alongside the camelCase summary fields, e.g. `{"schema":1,"domain":"example.com",...}`.
+    ///
+    /// # Returns
+    /// A `Result` containing the JSON string or a `SurgeError`.
+    pub fn to_json_envelope(&self) -> Result<String, SurgeError> {
+        Ok(serde_json::to_string(&DeploySummaryEnvelope {
+            schema: DEPLOY_SUMMARY_SCHEMA,
+            summary: self,
+        })?)
+    }
+
+    /// Parses a summary from a versioned JSON envelope.
+    ///
+    /// # Arguments
+    /// * `json` - The envelope produced by [`to_json_envelope`](Self::to_json_envelope).
+    ///
+    /// # Returns
+    /// A `Result` containing the `DeploySummary`, or `SurgeError::Json` if the JSON is invalid
+    /// or its `schema` is missing or not [`DEPLOY_SUMMARY_SCHEMA`].
+    ///
+    /// # Example
+    /// ```
+    /// use surge_sdk::DeploySummary;
+    ///
+    /// let summary = DeploySummary::new("example.surge.sh");
+    /// let json = summary.to_json_envelope().unwrap();
+    /// assert_eq!(DeploySummary::from_json_envelope(&json).unwrap(), summary);
+    /// assert!(DeploySummary::from_json_envelope(r#"{"schema":99}"#).is_err());
+    /// ```
+    pub fn from_json_envelope(json: &str) -> Result<Self, SurgeError> {
+        let mut value: Value = serde_json::from_str(json)?;
+        let schema = value
+            .as_object_mut()
+            .and_then(|object| object.remove("schema"))
+            .ok_or_else(|| SurgeError::Json("DeploySummary envelope has no schema".to_string()))?;
+
+        if schema.as_u64() != Some(u64::from(DEPLOY_SUMMARY_SCHEMA)) {
+            return Err(SurgeError::Json(format!(
+                "Unsupported DeploySummary schema {}, expected {}",
+                schema, DEPLOY_SUMMARY_SCHEMA
+            )));
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Updates the summary with a single event.
+    ///
+    /// # Arguments
+    /// * `event` - An event from a publish stream.
+    pub fn record(&mut self, event: &Event) {
+        self.events += 1;
+        if let Event::Info(data) = event {
+            self.urls = data.urls.iter().map(|u| u.domain.clone()).collect();
+            self.rev = Some(data.metadata.rev);
+            self.file_count = Some(data.metadata.public_file_count);
+            self.total_size = Some(data.metadata.public_total_size);
+        }
+    }
+}
+
+/// The smallest TTL, in seconds, accepted by [`DnsRecordInput::with_ttl`].
+pub const MIN_DNS_TTL: u32 = 60;
+
+/// The largest TTL, in seconds, accepted by [`DnsRecordInput::with_ttl`] (one week).
+pub const MAX_DNS_TTL: u32 = 604_800;
+
+/// How a local project relates to the live deployment of a domain, see [`DeployStatus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncState {
+    /// The local files match the live deployment.
+    InSync,
+    /// The local project has changes made after the live deployment was uploaded.
+    Ahead,
+    /// The live deployment differs and is newer than every local change.
+    Behind,
+}
+
+/// The result of `SurgeSdk::status`: the `git status` of a deployment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeployStatus {
+    /// The domain that was compared.
+    pub domain: String,
+    /// Whether the local project is ahead of, behind, or in sync with the live deployment.
+    pub state: SyncState,
+    /// The files that differ, from the live deployment to the local project: `added` files
+    /// exist only locally, `removed` files only in the live deployment.
+    pub diff: ManifestDiff,
+}
+
+/// How polling helpers such as `wait_for_cert` space their requests.
+///
+/// The first poll happens immediately. The delay before the next one starts at `initial` and
+/// doubles after every poll, up to `max`, so long waits do not hammer the server.
+///
+/// # Example
+/// ```
+/// use std::time::Duration;
+/// use surge_sdk::PollBackoff;
+///
+/// let backoff = PollBackoff::new(Duration::from_secs(1), Duration::from_secs(5));
+/// assert_eq!(backoff.delay(0), Duration::from_secs(1));
+/// assert_eq!(backoff.delay(2), Duration::from_secs(4));
+/// assert_eq!(backoff.delay(3), Duration::from_secs(5));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollBackoff {
+    /// The delay after the first poll.
+    pub initial: Duration,
+    /// The longest delay between two polls.
+    pub max: Duration,
+}
+
+impl Default for PollBackoff {
+    /// Starts at one second and is capped at thirty.
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(30),
+        }
+    }
+}
+
+impl PollBackoff {
+    /// Creates a backoff starting at `initial` and capped at `max`.
+    ///
+    /// # Arguments
+    /// * `initial` - The delay after the first poll.
+    /// * `max` - The longest delay between two polls; raised to `initial` if lower.
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max: max.max(initial),
+        }
+    }
+
+    /// Returns the delay after the zero-based poll `attempt`.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.initial
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max)
+    }
+}
+
+/// A number of retries shared by every step of a logical operation.
+///
+/// `Config::max_retries` bounds the retries of each request on its own, so a flow that polls
+/// and retries, like `wait_for_cert_with_budget`, could otherwise send up to
+/// `polls * (max_retries + 1)` requests. A budget is spent by each re-poll and by each retry of
+/// a failed request within the operation; once it is exhausted, the next failure or pending poll
+/// ends the operation. Clones share the same budget, so one budget can bound several calls.
+///
+/// The budget only bounds how many attempts are made. Each attempt is still bounded by the
+/// request timeout (`Config::with_timeout`), and polling by its own `timeout`, so the worst case
+/// wall time is roughly the polling timeout plus one request timeout.
+///
+/// # Example
+/// ```
+/// use surge_sdk::RetryBudget;
+///
+/// let budget = RetryBudget::new(2);
+/// let shared = budget.clone();
+/// assert!(shared.try_spend());
+/// assert!(budget.try_spend());
+/// assert!(!budget.try_spend());
+/// assert!(budget.is_exhausted());
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryBudget {
+    remaining: Arc<AtomicU32>,
+}
+
+impl RetryBudget {
+    /// Creates a budget allowing `retries` retries in total.
+    pub fn new(retries: u32) -> Self {
+        Self {
+            remaining: Arc::new(AtomicU32::new(retries)),
+        }
+    }
+
+    /// Creates a budget that is never exhausted, leaving retries to the per-request limits.
+    pub fn unlimited() -> Self {
+        Self::new(u32::MAX)
+    }
+
+    /// Returns how many retries are left.
+    pub fn remaining(&self) -> u32 {
+        self.remaining.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if no retries are left.
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Spends one retry.
+    ///
+    /// # Returns
+    /// `true` if a retry was left and has been spent, `false` if the budget is exhausted.
+    pub fn try_spend(&self) -> bool {
+        self.remaining
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+            .is_ok()
+    }
+}
+
+/// A DNS record to add to a domain.
+///
+/// Typed alternative to passing raw JSON to `dns_add`/`zone_add`. The TTL is validated when
+/// set, since out-of-range values are otherwise silently rejected by the server.
+///
+/// # Example
+/// ```
+/// use surge_sdk::DnsRecordInput;
+///
+/// let record = DnsRecordInput::new("A", "www", "192.0.2.1").with_ttl(3600).unwrap();
+/// assert_eq!(record.ttl, Some(3600));
+/// assert!(DnsRecordInput::new("A", "www", "192.0.2.1").with_ttl(0).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DnsRecordInput {
+    /// The record type (e.g. `A`, `CNAME`, `TXT`).
+    #[serde(rename = "type")]
+    pub record_type: String,
+    /// The record name, relative to the domain.
+    pub name: String,
+    /// The record value.
+    pub value: String,
+    /// The TTL in seconds; the server default is used when `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+}
+
+/// A zone record to add to a domain. Zone records share the DNS record shape.
+pub type ZoneRecordInput = DnsRecordInput;
+
+impl DnsRecordInput {
+    /// Creates a record without an explicit TTL.
+    ///
+    /// # Arguments
+    /// * `record_type` - The record type (e.g. `A`, `CNAME`, `TXT`).
+    /// * `name` - The record name.
+    /// * `value` - The record value.
+    pub fn new(
+        record_type: impl Into<String>,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        Self {
+            record_type: record_type.into(),
+            name: name.into(),
+            value: value.into(),
+            ttl: None,
+        }
+    }
+
+    /// Sets the TTL, validating it against [`MIN_DNS_TTL`]..=[`MAX_DNS_TTL`].
+    ///
+    /// # Arguments
+    /// * `ttl` - The TTL in seconds.
+    ///
+    /// # Returns
+    /// The modified `DnsRecordInput`, or `SurgeError::Config` if the TTL is out of range.
+    pub fn with_ttl(mut self, ttl: i64) -> Result<Self, SurgeError> {
+        if !(i64::from(MIN_DNS_TTL)..=i64::from(MAX_DNS_TTL)).contains(&ttl) {
+            return Err(SurgeError::Config(format!(
+                "Invalid DNS TTL {}: must be between {} and {} seconds",
+                ttl, MIN_DNS_TTL, MAX_DNS_TTL
+            )));
+        }
+        self.ttl = Some(ttl as u32);
+        Ok(self)
+    }
+}
@@ -0,0 +1,38 @@
+/*
+  src/ui.rs
+*/
+//! Terminal rendering helpers for the Surge SDK.
+//!
+//! Colored counterparts to the plain-text formatters found on response types, intended for
+//! command-line tools built on the SDK. Coloring follows the `colored` crate's environment
+//! handling, so `NO_COLOR` and `CLICOLOR_FORCE` are honored.
+
+use colored::Colorize;
+
+use crate::responses::ManifestDiff;
+
+/// Renders a `ManifestDiff` with the same layout as [`ManifestDiff::to_text`], coloring added
+/// files green, removed files red, and changed files yellow.
+///
+/// # Arguments
+/// * `diff` - The diff to render.
+///
+/// # Returns
+/// The rendered diff as a `String` containing ANSI color codes.
+pub fn manifest_diff(diff: &ManifestDiff) -> String {
+    let mut text = String::new();
+    for (marker, path) in diff.entries() {
+        let line = format!("{} {}", marker, path);
+        let line = match marker {
+            '+' => line.green(),
+            '-' => line.red(),
+            _ => line.yellow(),
+        };
+        text.push_str(&format!("{}\n", line));
+    }
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    text.push_str(&diff.summary().bold().to_string());
+    text
+}
@@ -15,6 +15,8 @@
 //! - `generate_domain`: Creates a `.surge.sh` domain name, optionally appending a random number.
 //! - `json_to_argv`: Converts a JSON object into a vector of command-line arguments.
 //! - `words_from`: A helper function to parse static word lists into trimmed vectors.
+//! - `epoch_to_datetime`: Converts an epoch timestamp in seconds or milliseconds to a `DateTime`.
+//! - `deploy_urls`: Computes the URLs a deployment to a domain is expected to be served at.
 //!
 //! The module uses word lists (`adjectives.txt`, `nouns.txt`, `verbs.txt`) included at compile time
 //! to generate identifiers and relies on the `rand` crate for randomization. It also includes a test
@@ -34,9 +36,17 @@
 //! println!("Arguments: {:?}", args); // e.g., ["dist/", "--endpoint", "https://surge.surge.sh", "--stage", "false"]
 //! ```
 
+use chrono::{DateTime, Utc};
+use log::debug;
 use rand::Rng;
 use rand::prelude::IndexedRandom;
 use serde_json::Value;
+use std::{future::Future, time::Duration};
+
+use crate::{
+    error::SurgeError,
+    types::{PollBackoff, RetryBudget},
+};
 
 const ADJECTIVES: &str = include_str!(".././dict/adjectives.txt");
 const NOUNS: &str = include_str!(".././dict/nouns.txt");
@@ -147,8 +157,149 @@ pub fn json_to_argv(json: &str) -> Vec<String> {
     args
 }
 
+/// Epoch values at or above this magnitude are treated as milliseconds.
+///
+/// `100_000_000_000` seconds is in the year 5138, while the same number of milliseconds is in
+/// 1973, so no realistic timestamp is ambiguous.
+const EPOCH_MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// Converts an epoch timestamp to a `DateTime<Utc>`, detecting whether it is in seconds or
+/// milliseconds.
+///
+/// The Surge API is inconsistent: some responses report epoch seconds and others epoch
+/// milliseconds. Values whose magnitude is at least `100_000_000_000` are read as milliseconds.
+///
+/// # Arguments
+/// * `value` - The epoch timestamp, in seconds or milliseconds.
+///
+/// # Returns
+/// The corresponding `DateTime<Utc>`, or the Unix epoch if the value is out of range.
+///
+/// # Example
+/// ```
+/// use surge_sdk::utils::epoch_to_datetime;
+///
+/// assert_eq!(epoch_to_datetime(1_700_000_000), epoch_to_datetime(1_700_000_000_000));
+/// ```
+pub fn epoch_to_datetime(value: i64) -> DateTime<Utc> {
+    let datetime = if value.abs() >= EPOCH_MILLIS_THRESHOLD {
+        DateTime::from_timestamp_millis(value)
+    } else {
+        DateTime::from_timestamp(value, 0)
+    };
+    datetime.unwrap_or_default()
+}
+
+/// Normalizes a domain passed by a caller before it is used in a URL.
+///
+/// Surrounding whitespace, a leading `http://` or `https://`, and trailing `/` are removed, and
+/// the host is lowercased, so `"Foo.surge.sh/"` addresses the same endpoints as
+/// `"foo.surge.sh"` instead of producing paths like `foo.surge.sh//certs`. A `www.` prefix is
+/// kept: the apex and `www.` domains are separate deployments.
+///
+/// # Arguments
+/// * `domain` - The domain as given by the caller.
+///
+/// # Returns
+/// The normalized domain.
+///
+/// # Example
+/// ```
+/// use surge_sdk::utils::normalize_domain;
+///
+/// assert_eq!(normalize_domain(" https://Foo.Surge.sh// "), "foo.surge.sh");
+/// assert_eq!(normalize_domain("www.example.com"), "www.example.com");
+/// ```
+pub fn normalize_domain(domain: &str) -> String {
+    domain
+        .trim()
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/')
+        .to_ascii_lowercase()
+}
+
+/// Computes the URLs a deployment to `domain` is expected to be served at.
+///
+/// `.surge.sh` domains are served at their own name only. A custom apex domain (two labels, such
+/// as `example.com`) is also expected at its `www.` variant, and a `www.` domain at its apex. The
+/// apex check is a heuristic that does not consult the public suffix list, so domains under
+/// suffixes such as `co.uk` are treated as subdomains.
+///
+/// # Arguments
+/// * `domain` - The target domain; a leading `http(s)://` and trailing `/` are ignored.
+///
+/// # Returns
+/// The `https://` URLs, with the given domain first.
+///
+/// # Example
+/// ```
+/// use surge_sdk::utils::deploy_urls;
+///
+/// assert_eq!(deploy_urls("foo.surge.sh"), vec!["https://foo.surge.sh"]);
+/// assert_eq!(
+///     deploy_urls("example.com"),
+///     vec!["https://example.com", "https://www.example.com"]
+/// );
+/// ```
+pub fn deploy_urls(domain: &str) -> Vec<String> {
+    let host = normalize_domain(domain);
+
+    let mut urls = vec![format!("https://{}", host)];
+    if host.ends_with(".surge.sh") {
+        return urls;
+    }
+    if let Some(apex) = host.strip_prefix("www.") {
+        urls.push(format!("https://{}", apex));
+    } else if host.split('.').count() == 2 {
+        urls.push(format!("https://www.{}", host));
+    }
+    urls
+}
+
+/// Calls `poll` until it reports success or `timeout` elapses, sleeping per `backoff` in between.
+///
+/// # Arguments
+/// * `timeout` - How long to keep polling; the last sleep is shortened to end at the deadline.
+/// * `backoff` - The delays between polls.
+/// * `budget` - Spent by every poll after the first; polling stops once it is exhausted.
+/// * `poll` - Returns `Ok(value)` when done, or `Err(state)` with the observed state otherwise.
+///
+/// # Returns
+/// `Ok(Ok(value))` once `poll` succeeds, `Ok(Err(state))` with the last observed state if the
+/// timeout elapses or the budget runs out first, or the first `SurgeError` returned by `poll`.
+pub(crate) async fn poll_with_backoff<T, S, F, Fut>(
+    timeout: Duration,
+    backoff: PollBackoff,
+    budget: &RetryBudget,
+    mut poll: F,
+) -> Result<Result<T, S>, SurgeError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Result<T, S>, SurgeError>>,
+{
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut attempt = 0;
+    loop {
+        let state = match poll().await? {
+            Ok(value) => return Ok(Ok(value)),
+            Err(state) => state,
+        };
+
+        let now = tokio::time::Instant::now();
+        if now >= deadline || !budget.try_spend() {
+            return Ok(Err(state));
+        }
+        let delay = backoff.delay(attempt).min(deadline - now);
+        debug!("Polling again in {:?}", delay);
+        tokio::time::sleep(delay).await;
+        attempt = attempt.saturating_add(1);
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::utils::{deploy_urls, epoch_to_datetime, poll_with_backoff};
     use crate::{SURGE_API, generate_domain, json_to_argv};
     use regex::Regex;
 
@@ -198,4 +349,91 @@ mod tests {
         let json = r#"{ invalid: json }"#;
         json_to_argv(json);
     }
+
+    /// Tests that epoch seconds and milliseconds are both detected.
+    #[test]
+    fn test_epoch_to_datetime() {
+        let expected = "2023-11-14T22:13:20Z";
+        assert_eq!(
+            epoch_to_datetime(1_700_000_000).to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            expected
+        );
+        assert_eq!(
+            epoch_to_datetime(1_700_000_000_000).to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            expected
+        );
+        assert_eq!(
+            epoch_to_datetime(1_700_000_000_250).timestamp_subsec_millis(),
+            250
+        );
+        assert_eq!(epoch_to_datetime(i64::MAX).timestamp(), 0);
+    }
+
+    /// Tests the served URLs of surge, apex, `www.`, and subdomain targets.
+    #[test]
+    fn test_deploy_urls() {
+        assert_eq!(
+            deploy_urls("https://Foo.surge.sh/"),
+            vec!["https://foo.surge.sh"]
+        );
+        assert_eq!(
+            deploy_urls("example.com"),
+            vec!["https://example.com", "https://www.example.com"]
+        );
+        assert_eq!(
+            deploy_urls("www.example.com"),
+            vec!["https://www.example.com", "https://example.com"]
+        );
+        assert_eq!(
+            deploy_urls("blog.example.com"),
+            vec!["https://blog.example.com"]
+        );
+    }
+
+    /// Tests that the delays between polls double up to the cap.
+    #[tokio::test(start_paused = true)]
+    async fn test_poll_with_backoff() {
+        use crate::types::{PollBackoff, RetryBudget};
+        use std::time::Duration;
+        use tokio::time::Instant;
+
+        let backoff = PollBackoff::new(Duration::from_secs(1), Duration::from_secs(4));
+        let unlimited = RetryBudget::unlimited();
+        let start = Instant::now();
+        let mut polls = Vec::new();
+        let result = poll_with_backoff(Duration::from_secs(60), backoff, &unlimited, || {
+            polls.push(Instant::now() - start);
+            let done = polls.len() == 5;
+            async move { Ok::<_, crate::SurgeError>(if done { Ok(()) } else { Err("pending") }) }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result, Ok(()));
+        let secs: Vec<u64> = polls.iter().map(Duration::as_secs).collect();
+        // Gaps of 1, 2, 4, then 4 again once capped
+        assert_eq!(secs, vec![0, 1, 3, 7, 11]);
+
+        let start = Instant::now();
+        let result = poll_with_backoff(Duration::from_secs(5), backoff, &unlimited, || async {
+            Ok::<Result<(), _>, crate::SurgeError>(Err("pending"))
+        })
+        .await
+        .unwrap();
+        assert_eq!(result, Err("pending"));
+        assert_eq!((Instant::now() - start).as_secs(), 5);
+
+        // A budget of two allows three polls, well before the timeout
+        let budget = RetryBudget::new(2);
+        let mut count = 0;
+        let result = poll_with_backoff(Duration::from_secs(60), backoff, &budget, || {
+            count += 1;
+            async { Ok::<Result<(), _>, crate::SurgeError>(Err("pending")) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(result, Err("pending"));
+        assert_eq!(count, 3);
+        assert!(budget.is_exhausted());
+    }
 }
@@ -38,6 +38,61 @@ async fn test_login_success() {
     assert_eq!(response.token, "abc123");
 }
 
+#[cfg(feature = "metrics")]
+#[tokio::test]
+async fn test_metrics_recorder() {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use surge_sdk::metrics::MetricsRecorder;
+
+    type Request = (String, String, Option<u16>, Duration);
+
+    #[derive(Debug, Default)]
+    struct Captured(Mutex<Vec<Request>>);
+
+    impl MetricsRecorder for Captured {
+        fn record_request(
+            &self,
+            method: &str,
+            path: &str,
+            status: Option<u16>,
+            duration: Duration,
+        ) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((method.to_string(), path.to_string(), status, duration));
+        }
+    }
+
+    let recorder = Arc::new(Captured::default());
+    let config_recorder = Arc::clone(&recorder);
+    let mut test_server =
+        TestServer::with_config(|config| config.with_metrics_recorder(config_recorder)).await;
+    let _m = test_server
+        .server
+        .mock("POST", "/token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(json!({ "email": "test@example.com", "token": "abc123" }).to_string())
+        .create_async()
+        .await;
+
+    test_server
+        .client
+        .login(&Auth::Token("abc123".to_string()))
+        .await
+        .unwrap();
+
+    let captured = recorder.0.lock().unwrap();
+    assert_eq!(captured.len(), 1);
+    let (method, path, status, duration) = &captured[0];
+    assert_eq!(method, "POST");
+    assert_eq!(path, "/token");
+    assert_eq!(*status, Some(200));
+    assert!(*duration < Duration::from_secs(30));
+}
+
 #[tokio::test]
 async fn test_login_failure() {
     let mut test_server = TestServer::new().await;
@@ -71,6 +126,93 @@ async fn test_login_failure() {
     ));
 }
 
+#[tokio::test]
+async fn test_login_failure_gzip_body() {
+    use flate2::{Compression, write::GzEncoder};
+    use std::io::Write;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(
+            json!({
+                "errors": ["Invalid credentials"],
+                "details": { "field": "password" },
+                "status": 401
+            })
+            .to_string()
+            .as_bytes(),
+        )
+        .unwrap();
+    let body = encoder.finish().unwrap();
+
+    let mut test_server = TestServer::new().await;
+    let _m = test_server
+        .server
+        .mock("POST", "/token")
+        .with_status(401)
+        .with_header("content-type", "application/json")
+        .with_header("content-encoding", "gzip")
+        .with_body(body)
+        .create_async()
+        .await;
+
+    let result = test_server
+        .client
+        .login(&Auth::UserPass {
+            username: "test@example.com".to_string(),
+            password: "wrong".to_string(),
+        })
+        .await;
+
+    match result {
+        Err(SurgeError::Api {
+            status,
+            message,
+            details,
+        }) => {
+            assert_eq!(status, Some(401));
+            assert_eq!(message, "Invalid credentials");
+            assert_eq!(details, json!({ "field": "password" }));
+        }
+        other => panic!("expected an Api error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_token_expiry() {
+    let auth = Auth::Token("abc123".to_string());
+    let soon = chrono::Utc::now().timestamp_millis() + 60 * 60 * 1000;
+    for (body, expiry, expires_soon) in [
+        (json!({ "email": "test@example.com" }), None, false),
+        (
+            json!({ "tokenExpiresAt": "2100-01-01T00:00:00Z" }),
+            Some(4_102_444_800),
+            false,
+        ),
+        (json!({ "token_expires_at": soon }), Some(soon / 1000), true),
+    ] {
+        let mut test_server = TestServer::new().await;
+        let _m = test_server
+            .server
+            .mock("GET", "/account")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .expect(2)
+            .create_async()
+            .await;
+
+        let result = test_server.client.token_expiry(&auth).await.unwrap();
+        assert_eq!(result.map(|datetime| datetime.timestamp()), expiry);
+        let result = test_server
+            .client
+            .token_expires_soon(std::time::Duration::from_secs(24 * 60 * 60), &auth)
+            .await
+            .unwrap();
+        assert_eq!(result, expires_soon);
+    }
+}
+
 #[tokio::test]
 async fn test_account_success() {
     let mut test_server = TestServer::new().await;
@@ -119,6 +261,64 @@ async fn test_account_success() {
     assert_eq!(response.plan.id, "student-00");
 }
 
+#[tokio::test]
+async fn test_preview_plan_change() {
+    let mut test_server = TestServer::new().await;
+    let preview = test_server
+        .server
+        .mock("POST", "/plan/preview")
+        .match_body(mockito::Matcher::Json(json!({ "plan": "plus-30" })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "amount_due": 1250,
+                "next_payment_attempt": 1767225600,
+                "recurringAmount": 3000,
+                "currency": "usd"
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let auth = Auth::Token("abc123".to_string());
+
+    let quote = test_server
+        .client
+        .preview_plan_change("plus-30", &auth)
+        .await
+        .unwrap();
+    assert_eq!(quote.immediate_charge, Some(1250));
+    assert_eq!(quote.recurring_amount, Some(3000));
+    assert_eq!(quote.currency.as_deref(), Some("usd"));
+    assert_eq!(
+        quote.next_billing_datetime().unwrap().to_rfc3339(),
+        "2026-01-01T00:00:00+00:00"
+    );
+    preview.assert_async().await;
+    preview.remove_async().await;
+
+    let _unsupported = test_server
+        .server
+        .mock("POST", "/plan/preview")
+        .with_status(404)
+        .with_body("Not Found")
+        .create_async()
+        .await;
+    let result = test_server
+        .client
+        .preview_plan_change("plus-30", &auth)
+        .await;
+    match result {
+        Err(SurgeError::Api {
+            status: Some(404),
+            message,
+            ..
+        }) => assert!(message.contains("not supported")),
+        other => panic!("expected an unsupported error, got {:?}", other),
+    }
+}
+
 #[tokio::test]
 async fn test_list_no_domain() {
     let mut test_server = TestServer::new().await;
@@ -173,6 +373,320 @@ async fn test_list_no_domain() {
     assert_eq!(list_response[0].domain, "test.surge.sh");
 }
 
+#[tokio::test]
+async fn test_list_global() {
+    let mut test_server = TestServer::new().await;
+    let _m = test_server
+        .server
+        .mock("GET", "/list")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!([{
+                "domain": "test.surge.sh",
+                "planName": "Plus",
+                "rev": 123456,
+                "cmd": "surge",
+                "email": "test@example.com",
+                "platform": "surge.sh",
+                "cliVersion": "0.1.0",
+                "output": {},
+                "config": {},
+                "message": null,
+                "buildTime": "2.5",
+                "ip": "127.0.0.1",
+                "privateFileList": [],
+                "publicFileCount": 5,
+                "publicTotalSize": 1000,
+                "privateFileCount": 0,
+                "privateTotalSize": 0,
+                "uploadStartTime": 1234567890,
+                "uploadEndTime": 1234567891,
+                "plansuploadDuratiod": 1.0,
+                "preview": null,
+                "timeAgoInWords": "Just now"
+            }])
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let deployments = test_server
+        .client
+        .list_global(&Auth::Token("abc123".to_string()))
+        .await
+        .unwrap();
+
+    assert_eq!(deployments.len(), 1);
+    assert_eq!(deployments[0].domain, "test.surge.sh");
+    assert_eq!(deployments[0].build_time, Some(2.5));
+}
+
+#[tokio::test]
+async fn test_list_domain() {
+    let mut test_server = TestServer::new().await;
+    let _m = test_server
+        .server
+        .mock("GET", "/test.surge.sh/list")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!([{
+                "rev": 2,
+                "platform": "surge.sh",
+                "email": "test@example.com",
+                "cmd": "surge",
+                "publicFileCount": 5,
+                "publicTotalSize": 1000,
+                "buildTime": null,
+                "msg": null,
+                "current": true,
+                "preview": "2-test.surge.sh",
+                "friendlySize": "1 KB",
+                "timeAgoInWords": "Just now"
+            }])
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let _missing = test_server
+        .server
+        .mock("GET", "/missing.surge.sh/list")
+        .with_status(404)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "errors": ["project not found"],
+                "details": {},
+                "status": 404
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let auth = Auth::Token("abc123".to_string());
+
+    let revisions = test_server
+        .client
+        .list_domain("test.surge.sh", &auth)
+        .await
+        .unwrap();
+    assert_eq!(revisions.len(), 1);
+    assert_eq!(revisions[0].rev, 2);
+    assert!(revisions[0].current);
+
+    let result = test_server
+        .client
+        .list_domain("missing.surge.sh", &auth)
+        .await;
+    assert!(matches!(
+        result,
+        Err(SurgeError::Api {
+            status: Some(404),
+            ..
+        })
+    ));
+}
+
+#[tokio::test]
+async fn test_list_stream() {
+    use futures_util::StreamExt;
+
+    let entry = |name: &str| {
+        json!({
+            "domain": name,
+            "planName": "Plus",
+            "rev": 1,
+            "cmd": "surge",
+            "email": "test@example.com",
+            "platform": "surge.sh",
+            "cliVersion": "0.1.0",
+            "output": {},
+            "config": {},
+            "message": "a [bracketed], \"quoted\" message",
+            "buildTime": null,
+            "ip": "127.0.0.1",
+            "privateFileList": [],
+            "publicFileCount": 1,
+            "publicTotalSize": 5,
+            "privateFileCount": 0,
+            "privateTotalSize": 0,
+            "uploadStartTime": 1234567890,
+            "uploadEndTime": 1234567891,
+            "plansuploadDuratiod": 1.0,
+            "preview": null,
+            "timeAgoInWords": "Just now"
+        })
+    };
+    let body = serde_json::to_vec_pretty(&json!([entry("a.com"), entry("b.com"), entry("c.com")]))
+        .unwrap();
+
+    let mut test_server = TestServer::new().await;
+    let _list = test_server
+        .server
+        .mock("GET", "/list")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        // Small chunks, so entries are split across reads
+        .with_chunked_body(move |w| {
+            for chunk in body.chunks(7) {
+                w.write_all(chunk)?;
+                w.flush()?;
+            }
+            Ok(())
+        })
+        .create_async()
+        .await;
+    let auth = Auth::Token("abc123".to_string());
+
+    let entries: Vec<_> = test_server
+        .client
+        .list_stream(&auth)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+    let domains: Vec<_> = entries
+        .into_iter()
+        .map(|entry| entry.unwrap().domain)
+        .collect();
+    assert_eq!(domains, vec!["a.com", "b.com", "c.com"]);
+
+    let _truncated = test_server
+        .server
+        .mock("GET", "/list")
+        .with_status(200)
+        .with_body(format!("[{},", entry("a.com")))
+        .create_async()
+        .await;
+    let entries: Vec<_> = test_server
+        .client
+        .list_stream(&auth)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+    assert!(entries[0].is_ok());
+    assert!(matches!(entries[1], Err(SurgeError::Json(_))));
+
+    // Elements must be separated by exactly one comma
+    let (a, b) = (entry("a.com"), entry("b.com"));
+    for (body, decoded) in [
+        (format!("[{}{}]", a, b), 1),
+        (format!("[{} {}]", a, b), 1),
+        (format!("[{},,{}]", a, b), 1),
+        (format!("[{},]", a), 1),
+        (format!("[,{}]", a), 0),
+    ] {
+        let malformed = test_server
+            .server
+            .mock("GET", "/list")
+            .with_status(200)
+            .with_body(&body)
+            .create_async()
+            .await;
+        let entries: Vec<_> = test_server
+            .client
+            .list_stream(&auth)
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(entries.len(), decoded + 1, "{}", body);
+        assert!(entries[..decoded].iter().all(Result::is_ok), "{}", body);
+        assert!(
+            matches!(entries[decoded], Err(SurgeError::Json(_))),
+            "{}",
+            body
+        );
+        malformed.remove_async().await;
+    }
+}
+
+#[test]
+fn test_list_result_serde_round_trip() {
+    let list: Vec<surge_sdk::ListResponse> = serde_json::from_value(json!([{
+        "domain": "test.surge.sh",
+        "planName": "Plus",
+        "rev": 123456,
+        "cmd": "surge",
+        "email": "test@example.com",
+        "platform": "surge.sh",
+        "cliVersion": "0.1.0",
+        "output": {},
+        "config": {},
+        "message": null,
+        "buildTime": null,
+        "ip": "127.0.0.1",
+        "privateFileList": [],
+        "publicFileCount": 5,
+        "publicTotalSize": 1000,
+        "privateFileCount": 0,
+        "privateTotalSize": 0,
+        "uploadStartTime": 1234567890,
+        "uploadEndTime": 1234567891,
+        "plansuploadDuratiod": 1.0,
+        "preview": null,
+        "timeAgoInWords": "Just now"
+    }]))
+    .unwrap();
+    let result = surge_sdk::ListResult::Global(list);
+
+    let serialized = serde_json::to_value(&result).unwrap();
+    assert_eq!(serialized["kind"], "global");
+    assert_eq!(serialized["data"][0]["domain"], "test.surge.sh");
+
+    let restored: surge_sdk::ListResult = serde_json::from_value(serialized.clone()).unwrap();
+    assert!(matches!(&restored, surge_sdk::ListResult::Global(list) if list.len() == 1));
+    assert_eq!(serde_json::to_value(&restored).unwrap(), serialized);
+}
+
+#[tokio::test]
+async fn test_bust() {
+    let mut test_server = TestServer::new().await;
+    let _ok = test_server
+        .server
+        .mock("DELETE", "/test.surge.sh/cache")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(json!({ "purged": 12 }).to_string())
+        .create_async()
+        .await;
+    let _denied = test_server
+        .server
+        .mock("DELETE", "/other.surge.sh/cache")
+        .with_status(403)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "errors": ["Not authorized"],
+                "details": {},
+                "status": 403
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let auth = Auth::Token("test-token".to_string());
+    let bust = test_server
+        .client
+        .bust("test.surge.sh", &auth)
+        .await
+        .unwrap();
+    assert_eq!(bust.purged, Some(12));
+    assert_eq!(bust.purged_count(), 12);
+
+    let result = test_server.client.bust("other.surge.sh", &auth).await;
+    assert!(matches!(
+        result,
+        Err(SurgeError::Api {
+            status: Some(403),
+            ..
+        })
+    ));
+}
+
 #[tokio::test]
 async fn test_teardown_success() {
     let mut test_server = TestServer::new().await;
@@ -430,3 +944,2870 @@ async fn test_dns() {
         "DNS may only be managed on apex domains."
     );
 }
+
+#[tokio::test]
+async fn test_aliases() {
+    let mut test_server = TestServer::new().await;
+    let _names = test_server
+        .server
+        .mock("GET", "/test.surge.sh/aliases")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(json!(["example.com", "example.net"]).to_string())
+        .create_async()
+        .await;
+    let _wrapped = test_server
+        .server
+        .mock("GET", "/other.surge.sh/aliases")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(json!({ "aliases": [{ "domain": "example.org" }] }).to_string())
+        .create_async()
+        .await;
+    let _missing = test_server
+        .server
+        .mock("GET", "/missing.surge.sh/aliases")
+        .with_status(404)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({ "errors": ["project not found"], "details": {}, "status": 404 }).to_string(),
+        )
+        .create_async()
+        .await;
+
+    let auth = Auth::Token("abc123".to_string());
+    let aliases = test_server
+        .client
+        .aliases("test.surge.sh", &auth)
+        .await
+        .unwrap();
+    assert_eq!(aliases, vec!["example.com", "example.net"]);
+
+    let aliases = test_server
+        .client
+        .aliases("other.surge.sh", &auth)
+        .await
+        .unwrap();
+    assert_eq!(aliases, vec!["example.org"]);
+
+    let result = test_server.client.aliases("missing.surge.sh", &auth).await;
+    assert!(matches!(result, Err(SurgeError::Api { .. })));
+}
+
+#[tokio::test]
+async fn test_add_and_remove_alias() {
+    let mut test_server = TestServer::new().await;
+    let settings = json!({
+        "force": null,
+        "redirect": null,
+        "cors": null,
+        "hsts": null,
+        "ttl": null
+    })
+    .to_string();
+    let add = test_server
+        .server
+        .mock("PUT", "/test.surge.sh/aliases/example.com")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(&settings)
+        .create_async()
+        .await;
+    let list = test_server
+        .server
+        .mock("GET", "/test.surge.sh/aliases")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(json!(["example.com"]).to_string())
+        .create_async()
+        .await;
+    let remove = test_server
+        .server
+        .mock("DELETE", "/test.surge.sh/aliases/example.com")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(&settings)
+        .create_async()
+        .await;
+    let rejected = test_server
+        .server
+        .mock("PUT", "/test.surge.sh/aliases/taken.com")
+        .with_status(409)
+        .with_header("content-type", "application/json")
+        .with_body(json!({ "errors": ["alias in use"], "details": {}, "status": 409 }).to_string())
+        .create_async()
+        .await;
+    let auth = Auth::Token("abc123".to_string());
+    let client = &test_server.client;
+
+    client
+        .add_alias("test.surge.sh", "example.com", &auth)
+        .await
+        .unwrap();
+    assert_eq!(
+        client.aliases("test.surge.sh", &auth).await.unwrap(),
+        vec!["example.com"]
+    );
+    client
+        .remove_alias("test.surge.sh", "example.com", &auth)
+        .await
+        .unwrap();
+    let result = client.add_alias("test.surge.sh", "taken.com", &auth).await;
+    assert!(matches!(
+        result,
+        Err(SurgeError::Api {
+            status: Some(409),
+            ..
+        })
+    ));
+
+    add.assert_async().await;
+    list.assert_async().await;
+    remove.assert_async().await;
+    rejected.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_add_domain_alias() {
+    let mut test_server = TestServer::new().await;
+    let m = test_server
+        .server
+        .mock("PUT", "/test.surge.sh/aliases/example.com")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "force": null,
+                "redirect": null,
+                "cors": null,
+                "hsts": null,
+                "ttl": 3600
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let settings = test_server
+        .client
+        .add_domain_alias(
+            "https://Test.surge.sh/",
+            "Example.com",
+            &Auth::Token("abc123".to_string()),
+        )
+        .await
+        .unwrap();
+
+    // The alias is added under the deployment's own route
+    m.assert_async().await;
+    assert_eq!(settings.ttl, Some(3600));
+}
+
+#[tokio::test]
+async fn test_remove_domain_alias() {
+    let mut test_server = TestServer::new().await;
+    let m = test_server
+        .server
+        .mock("DELETE", "/test.surge.sh/aliases/example.com")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "force": null,
+                "redirect": null,
+                "cors": null,
+                "hsts": null,
+                "ttl": null
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let settings = test_server
+        .client
+        .remove_domain_alias(
+            "test.surge.sh",
+            "example.com",
+            &Auth::Token("abc123".to_string()),
+        )
+        .await
+        .unwrap();
+
+    m.assert_async().await;
+    assert!(settings.ttl.is_none());
+}
+
+#[tokio::test]
+async fn test_files_cache_diagnostics() {
+    let mut test_server = TestServer::new().await;
+    let _m = test_server
+        .server
+        .mock("GET", "/test.surge.sh/manifest.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("age", "120")
+        .with_header("x-cache", "HIT")
+        .with_body(
+            json!({
+                "/index.html": {
+                    "size": 5,
+                    "md5sum": "5d41402abc4b2a76b9719d911017c592",
+                    "sha256sum": "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+                }
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let (manifest, diagnostics) = test_server
+        .client
+        .files_with_diagnostics("test.surge.sh", &Auth::Token("abc123".to_string()))
+        .await
+        .unwrap();
+
+    assert_eq!(manifest.len(), 1);
+    assert_eq!(diagnostics.age, Some(120));
+    assert_eq!(diagnostics.x_cache.as_deref(), Some("HIT"));
+    assert!(diagnostics.is_hit());
+    assert!(diagnostics.date.is_some());
+}
+
+#[tokio::test]
+async fn test_publish_minimal_headers() {
+    use futures_util::StreamExt;
+    use mockito::Matcher;
+
+    let mut test_server =
+        TestServer::with_config(|config| config.with_minimal_publish_headers(true)).await;
+    let m = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .match_header("content-type", "application/gzip")
+        .match_header("file-count", "1")
+        .match_header("project-size", "5")
+        .match_header("version", Matcher::Missing)
+        .match_header("timestamp", Matcher::Missing)
+        .match_header("stage", Matcher::Missing)
+        .match_header("ssl", Matcher::Missing)
+        .match_header("argv", Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("{\"type\":\"progress\",\"id\":\"upload\",\"written\":5,\"total\":5}\n")
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+
+    let auth = Auth::Token("abc123".to_string());
+    let stream = surge_sdk::publish(
+        &test_server.client,
+        dir.path(),
+        "test.surge.sh",
+        &auth,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    let events: Vec<_> = stream.collect().await;
+
+    m.assert_async().await;
+    assert_eq!(events.len(), 1);
+    assert!(events[0].is_ok());
+}
+
+#[tokio::test]
+async fn test_status() {
+    use surge_sdk::SyncState;
+
+    let hello = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+    let entry = |sha: &str| json!({ "size": 5, "md5sum": "m", "sha256sum": sha });
+    let now_ms = chrono::Utc::now().timestamp_millis();
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    let mut test_server = TestServer::new().await;
+    let in_sync = test_server
+        .server
+        .mock("GET", "/test.surge.sh/manifest.json")
+        .with_status(200)
+        .with_body(json!({ "/index.html": entry(hello) }).to_string())
+        .expect(1)
+        .create_async()
+        .await;
+    let _manifest = test_server
+        .server
+        .mock("GET", "/test.surge.sh/manifest.json")
+        .with_status(200)
+        .with_body(json!({ "/index.html": entry(hello), "/old.html": entry("x") }).to_string())
+        .create_async()
+        .await;
+    // Uploaded long ago: the local files are newer
+    let old_deploy = test_server
+        .server
+        .mock("GET", "/test.surge.sh/metadata.json")
+        .with_status(200)
+        .with_body(metadata_body(100, 1_000_000_000_000, 1_000_000_000_000).to_string())
+        .expect(2)
+        .create_async()
+        .await;
+
+    let status = test_server
+        .client
+        .status(dir.path(), "test.surge.sh", &auth)
+        .await
+        .unwrap();
+    assert_eq!(status.state, SyncState::InSync);
+    assert!(status.diff.is_empty());
+    in_sync.assert_async().await;
+
+    fs::write(dir.path().join("new.html"), "new").await.unwrap();
+    let status = test_server
+        .client
+        .status(dir.path(), "test.surge.sh", &auth)
+        .await
+        .unwrap();
+    assert_eq!(status.state, SyncState::Ahead);
+    assert_eq!(status.diff.added, vec!["/new.html"]);
+    assert_eq!(status.diff.removed, vec!["/old.html"]);
+    assert!(status.diff.changed.is_empty());
+    old_deploy.assert_async().await;
+
+    // Uploaded after the local change: the live deployment is newer
+    let _new_deploy = test_server
+        .server
+        .mock("GET", "/test.surge.sh/metadata.json")
+        .with_status(200)
+        .with_body(metadata_body(101, now_ms + 60_000, now_ms + 60_000).to_string())
+        .create_async()
+        .await;
+    let status = test_server
+        .client
+        .status(dir.path(), "test.surge.sh", &auth)
+        .await
+        .unwrap();
+    assert_eq!(status.state, SyncState::Behind);
+}
+
+#[tokio::test]
+async fn test_diff_revisions() {
+    let mut test_server = TestServer::new().await;
+    let entry = |sha: &str| json!({ "size": 5, "md5sum": "m", "sha256sum": sha });
+    let _old = test_server
+        .server
+        .mock("GET", "/test.surge.sh/rev1/manifest.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "/index.html": entry("a"),
+                "/removed.js": entry("b"),
+                "/changed.css": entry("c"),
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let _new = test_server
+        .server
+        .mock("GET", "/test.surge.sh/manifest.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "/index.html": entry("a"),
+                "/added.html": entry("d"),
+                "/changed.css": entry("e"),
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let diff = test_server
+        .client
+        .diff_revisions(
+            "test.surge.sh",
+            "rev1",
+            None,
+            &Auth::Token("abc123".to_string()),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(diff.added, vec!["/added.html"]);
+    assert_eq!(diff.removed, vec!["/removed.js"]);
+    assert_eq!(diff.changed, vec!["/changed.css"]);
+    assert_eq!(
+        diff.to_text(),
+        "+ /added.html\n- /removed.js\n~ /changed.css\n\n3 files changed (1 added, 1 removed, 1 changed)"
+    );
+}
+
+#[tokio::test]
+async fn test_publish_metadata_headers() {
+    let mut test_server = TestServer::new().await;
+    let m = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .match_header("file-count", "2")
+        .match_header("project-size", "11")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("")
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    fs::write(dir.path().join("app.js"), "world!")
+        .await
+        .unwrap();
+
+    let auth = Auth::Token("abc123".to_string());
+    let _stream = test_server
+        .client
+        .publish(dir.path(), "test.surge.sh", &auth, None, None)
+        .await
+        .unwrap();
+
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_publish_excludes_heavy_dirs() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let package = dir.path().join("node_modules").join("left-pad");
+    fs::create_dir_all(&package).await.unwrap();
+    fs::write(package.join("index.js"), "module.exports = 1")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    // node_modules is left out by default
+    let mut test_server = TestServer::new().await;
+    let m = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .match_header("file-count", "1")
+        .match_header("project-size", "5")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("")
+        .create_async()
+        .await;
+    let _stream = test_server
+        .client
+        .publish(dir.path(), "test.surge.sh", &auth, None, None)
+        .await
+        .unwrap();
+    m.assert_async().await;
+
+    // and published when allowed
+    let mut test_server = TestServer::with_config(|c| c.with_allow_heavy_dirs(true)).await;
+    let m = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .match_header("file-count", "2")
+        .match_header("project-size", "23")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("")
+        .create_async()
+        .await;
+    let _stream = test_server
+        .client
+        .publish(dir.path(), "test.surge.sh", &auth, None, None)
+        .await
+        .unwrap();
+    m.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_publish_to_channel() {
+    use surge_sdk::{Event, PublishOptions};
+    use tokio::sync::mpsc;
+    use tokio_util::sync::CancellationToken;
+
+    let mut test_server = TestServer::new().await;
+    let _m = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body(concat!(
+            "{\"type\":\"progress\",\"id\":\"upload\",\"written\":5,\"total\":5}\n",
+            "{\"type\":\"progress\",\"id\":\"upload\",\"written\":5,\"total\":5,\"end\":true}\n",
+        ))
+        .expect(2)
+        .create_async()
+        .await;
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+    let options = PublishOptions::default();
+
+    let (tx, mut rx) = mpsc::channel(8);
+    let handle = test_server.client.publish_to_channel(
+        dir.path(),
+        "test.surge.sh",
+        &auth,
+        &options,
+        tx,
+        CancellationToken::new(),
+    );
+    let mut received = Vec::new();
+    while let Some(event) = rx.recv().await {
+        received.push(event.unwrap());
+    }
+    let summary = handle.await.unwrap().unwrap();
+    assert_eq!(received.len(), 2);
+    assert!(matches!(received[0], Event::Progress { written: 5, .. }));
+    assert_eq!(summary.events, 2);
+
+    // A closed channel does not stop the publish
+    let (tx, rx) = mpsc::channel(1);
+    drop(rx);
+    let handle = test_server.client.publish_to_channel(
+        dir.path(),
+        "test.surge.sh",
+        &auth,
+        &options,
+        tx,
+        CancellationToken::new(),
+    );
+    assert_eq!(handle.await.unwrap().unwrap().events, 2);
+
+    // A cancelled publish sends nothing
+    let (tx, mut rx) = mpsc::channel(8);
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    let handle = test_server.client.publish_to_channel(
+        dir.path(),
+        "test.surge.sh",
+        &auth,
+        &options,
+        tx,
+        cancel,
+    );
+    let result = handle.await.unwrap();
+    assert!(matches!(result, Err(SurgeError::Event(message)) if message.contains("cancelled")));
+    assert!(rx.recv().await.is_none());
+}
+
+#[tokio::test]
+async fn test_list_tar_entries_matches_published_tarball() {
+    use std::sync::{Arc, Mutex};
+    use surge_sdk::{PublishOptions, stream::list_tar_entries};
+
+    let dir = tempdir().unwrap();
+    let project = dir.path().join("site");
+    let package = project.join("node_modules").join("left-pad");
+    fs::create_dir_all(&package).await.unwrap();
+    fs::write(package.join("index.js"), "module.exports = 1")
+        .await
+        .unwrap();
+    fs::write(project.join("index.html"), "hello")
+        .await
+        .unwrap();
+
+    let uploaded = Arc::new(Mutex::new(Vec::new()));
+    let captured = uploaded.clone();
+    let mut test_server = TestServer::new().await;
+    let m = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .match_request(move |request| {
+            *captured.lock().unwrap() = request.body().unwrap().clone();
+            true
+        })
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("")
+        .create_async()
+        .await;
+    let auth = Auth::Token("abc123".to_string());
+    let _stream = test_server
+        .client
+        .publish(&project, "test.surge.sh", &auth, None, None)
+        .await
+        .unwrap();
+    m.assert_async().await;
+
+    let body = uploaded.lock().unwrap().clone();
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(&body[..]));
+    let published: Vec<String> = archive
+        .entries()
+        .unwrap()
+        .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+        .collect();
+    let listed: Vec<String> = list_tar_entries(&project, &PublishOptions::default(), false)
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.path)
+        .collect();
+    assert_eq!(published, vec!["site/index.html"]);
+    assert_eq!(listed, published);
+
+    let metadata = surge_sdk::calculate_metadata(&project).unwrap();
+    assert_eq!(metadata.file_count, 1);
+    let manifest =
+        surge_sdk::stream::compute_manifest(&project, &PublishOptions::default()).unwrap();
+    assert_eq!(manifest.keys().collect::<Vec<_>>(), vec!["/index.html"]);
+}
+
+#[tokio::test]
+async fn test_publish_empty_project() {
+    let test_server = TestServer::new().await;
+    let dir = tempdir().unwrap();
+
+    let auth = Auth::Token("abc123".to_string());
+    let result = test_server
+        .client
+        .publish(dir.path(), "test.surge.sh", &auth, None, None)
+        .await;
+
+    assert!(matches!(result, Err(SurgeError::InvalidProject(_))));
+}
+
+#[tokio::test]
+async fn test_publish_raw() {
+    use surge_sdk::stream::{PublishOptions, publish_raw};
+
+    let mut test_server = TestServer::new().await;
+    let body = "{\"type\":\"progress\",\"id\":\"upload\",\"written\":5,\"total\":5}\n";
+    let _m = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body(body)
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+
+    let auth = Auth::Token("abc123".to_string());
+    let res = publish_raw(
+        &test_server.client,
+        dir.path(),
+        "test.surge.sh",
+        &auth,
+        &PublishOptions::default(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.text().await.unwrap(), body);
+}
+
+fn metadata_body(rev: i64, upload_start: i64, upload_end: i64) -> serde_json::Value {
+    json!({
+        "rev": rev,
+        "cmd": "surge",
+        "email": "test@example.com",
+        "platform": "surge.sh",
+        "cliVersion": "0.24.6",
+        "output": {},
+        "config": { "pdf": false },
+        "message": null,
+        "buildTime": null,
+        "ip": "127.0.0.1",
+        "privateFileList": [],
+        "publicFileCount": 1,
+        "publicTotalSize": 5,
+        "privateFileCount": 0,
+        "privateTotalSize": 0,
+        "uploadStartTime": upload_start,
+        "uploadEndTime": upload_end,
+        "uploadDuration": 2.5,
+        "preview": "1700000000000-test.surge.sh"
+    })
+}
+
+#[tokio::test]
+async fn test_metadata_upload_times() {
+    let mut test_server = TestServer::new().await;
+    let _m = test_server
+        .server
+        .mock("GET", "/test.surge.sh/metadata.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(metadata_body(1700000000000, 1700000000000, 1700000002500).to_string())
+        .create_async()
+        .await;
+
+    let metadata = test_server
+        .client
+        .metadata("test.surge.sh", None, &Auth::Token("abc123".to_string()))
+        .await
+        .unwrap();
+
+    assert_eq!(metadata.uploaded_at().timestamp(), 1_700_000_000);
+    assert_eq!(metadata.upload_elapsed().num_milliseconds(), 2_500);
+}
+
+#[tokio::test]
+async fn test_metadata_deploy_config() {
+    let mut body = metadata_body(100, 0, 0);
+    body["config"] = json!({ "pdf": true, "hsts": { "maxAge": 600 }, "spa": true });
+    body["output"] = json!({ "log": ["built"] });
+    let mut test_server = TestServer::new().await;
+    let _m = test_server
+        .server
+        .mock("GET", "/test.surge.sh/metadata.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(body.to_string())
+        .create_async()
+        .await;
+
+    let metadata = test_server
+        .client
+        .metadata("test.surge.sh", None, &Auth::Token("abc123".to_string()))
+        .await
+        .unwrap();
+
+    assert!(metadata.config.pdf);
+    let config = metadata.deploy_config();
+    assert_eq!(config.pdf, Some(true));
+    assert_eq!(config.settings.hsts.unwrap().max_age, Some(600));
+    assert_eq!(config.settings.extra["spa"], json!(true));
+    assert_eq!(metadata.build_output().log, vec!["built"]);
+}
+
+#[tokio::test]
+async fn test_deployment_info() {
+    let mut test_server = TestServer::new().await;
+    let _m = test_server
+        .server
+        .mock("GET", "/test.surge.sh/1700000000000/info.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "urls": [
+                    { "name": "test.surge.sh", "domain": "test.surge.sh" },
+                    { "name": "1700000000000.test.surge.sh", "domain": "test.surge.sh" }
+                ],
+                "certs": [],
+                "instances": [{
+                    "type": "HTTP",
+                    "provider": "Surge",
+                    "domain": "test.surge.sh",
+                    "location": "New York, US",
+                    "status": "up",
+                    "statusColor": "green",
+                    "confirmation": "verified",
+                    "confirmationColor": "green",
+                    "ip": "138.197.235.123",
+                    "info": "nyc"
+                }]
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let info = test_server
+        .client
+        .deployment_info(
+            "test.surge.sh",
+            Some("1700000000000"),
+            &Auth::Token("abc123".to_string()),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        info.url_names(),
+        vec!["test.surge.sh", "1700000000000.test.surge.sh"]
+    );
+    assert!(info.certs.is_empty());
+    assert_eq!(info.instances.len(), 1);
+    assert_eq!(info.instances[0].ip, "138.197.235.123");
+}
+
+#[tokio::test]
+async fn test_publish_redirect() {
+    let mut test_server = TestServer::new().await;
+    let publish = test_server
+        .server
+        .mock("PUT", "/old.surge.sh")
+        .match_header("file-count", "2")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("{\"type\":\"progress\",\"id\":\"upload\",\"written\":1,\"total\":1}\n")
+        .create_async()
+        .await;
+    let settings = test_server
+        .server
+        .mock("PUT", "/old.surge.sh/settings")
+        .match_body(mockito::Matcher::Json(json!({
+            "redirect": [{ "from": "/", "to": "https://new.surge.sh/", "status": 301 }]
+        })))
+        .with_status(200)
+        .with_body("{}")
+        .create_async()
+        .await;
+
+    let auth = Auth::Token("abc123".to_string());
+    let summary = test_server
+        .client
+        .publish_redirect("old.surge.sh", "new.surge.sh", 301, &auth)
+        .await
+        .unwrap();
+
+    publish.assert_async().await;
+    settings.assert_async().await;
+    assert_eq!(summary.domain, "old.surge.sh");
+    assert_eq!(summary.events, 1);
+
+    let result = test_server
+        .client
+        .publish_redirect("old.surge.sh", "new.surge.sh", 200, &auth)
+        .await;
+    assert!(matches!(result, Err(SurgeError::Config(_))));
+}
+
+#[tokio::test]
+async fn test_publish_redirect_rejected() {
+    let mut test_server = TestServer::new().await;
+    let settings = test_server
+        .server
+        .mock("PUT", "/old.surge.sh/settings")
+        .with_status(422)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({ "errors": ["invalid redirect"], "details": {}, "status": 422 }).to_string(),
+        )
+        .create_async()
+        .await;
+    let publish = test_server
+        .server
+        .mock("PUT", "/old.surge.sh")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let auth = Auth::Token("abc123".to_string());
+    let result = test_server
+        .client
+        .publish_redirect("old.surge.sh", "new.surge.sh", 301, &auth)
+        .await;
+
+    // The rejected redirect fails the call before anything goes live
+    assert!(matches!(
+        result,
+        Err(SurgeError::Api {
+            status: Some(422),
+            ..
+        })
+    ));
+    settings.assert_async().await;
+    publish.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_dns_add_record_ttl() {
+    use surge_sdk::DnsRecordInput;
+
+    let result = DnsRecordInput::new("A", "www", "192.0.2.1").with_ttl(30);
+    assert!(matches!(result, Err(SurgeError::Config(_))));
+    assert!(
+        DnsRecordInput::new("A", "www", "192.0.2.1")
+            .with_ttl(-1)
+            .is_err()
+    );
+
+    let mut test_server = TestServer::new().await;
+    let m = test_server
+        .server
+        .mock("POST", "/example.com/dns")
+        .match_body(mockito::Matcher::Json(json!({
+            "type": "A",
+            "name": "www",
+            "value": "192.0.2.1",
+            "ttl": 3600
+        })))
+        .with_status(200)
+        .with_body("{}")
+        .create_async()
+        .await;
+
+    let record = DnsRecordInput::new("A", "www", "192.0.2.1")
+        .with_ttl(3600)
+        .unwrap();
+    test_server
+        .client
+        .dns_add_record("example.com", &record, &Auth::Token("abc123".to_string()))
+        .await
+        .unwrap();
+
+    m.assert_async().await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_retry_honors_retry_after() {
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    let mut test_server = TestServer::with_config(|config| config.with_max_retries(1)).await;
+    let limited = test_server
+        .server
+        .mock("GET", "/example.com/dns")
+        .with_status(429)
+        .with_header("retry-after", "120")
+        .expect(1)
+        .create_async()
+        .await;
+    let ok = test_server
+        .server
+        .mock("GET", "/example.com/dns")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("[]")
+        .create_async()
+        .await;
+
+    // While the client waits on the mock server, the paused clock would otherwise jump straight
+    // to the request timeout; a short ticker keeps it moving in small steps instead
+    let ticker = tokio::spawn(async {
+        let mut interval = tokio::time::interval(Duration::from_millis(10));
+        loop {
+            interval.tick().await;
+        }
+    });
+
+    let started = Instant::now();
+    let response = test_server
+        .client
+        .dns("example.com", &Auth::Token("abc123".to_string()))
+        .await
+        .unwrap();
+    let elapsed = started.elapsed();
+    ticker.abort();
+
+    // The retry waited for `Retry-After` on the tokio clock, not the shorter backoff
+    assert!(
+        elapsed >= Duration::from_secs(120),
+        "retried after {:?}",
+        elapsed
+    );
+    assert!(
+        elapsed < Duration::from_secs(150),
+        "retried after {:?}",
+        elapsed
+    );
+    assert_eq!(response, json!([]));
+    limited.assert_async().await;
+    ok.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_retry_after_too_long_is_not_waited_for() {
+    use std::time::Duration;
+
+    let mut test_server = TestServer::with_config(|config| config.with_max_retries(3)).await;
+    let auth = Auth::Token("abc123".to_string());
+    for retry_after in ["86400", "Fri, 31 Dec 2100 23:59:59 GMT"] {
+        let limited = test_server
+            .server
+            .mock("GET", "/example.com/info.json")
+            .with_status(429)
+            .with_header("retry-after", retry_after)
+            .with_body(json!({ "errors": ["slow down"], "details": {}, "status": 429 }).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        // The 429 is returned at once instead of sleeping until the server's deadline
+        let result = tokio::time::timeout(
+            Duration::from_secs(10),
+            test_server
+                .client
+                .deployment_info("example.com", None, &auth),
+        )
+        .await
+        .expect("waited for an oversized Retry-After");
+        assert!(
+            matches!(
+                result,
+                Err(SurgeError::Api {
+                    status: Some(429),
+                    ..
+                })
+            ),
+            "{:?}",
+            result
+        );
+        limited.assert_async().await;
+        limited.remove_async().await;
+    }
+}
+
+#[tokio::test]
+async fn test_all_certs() {
+    let mut test_server = TestServer::new().await;
+    let domain = |name: &str| {
+        json!({
+            "domain": name,
+            "planName": "Plus",
+            "rev": 1,
+            "cmd": "surge",
+            "email": "test@example.com",
+            "platform": "surge.sh",
+            "cliVersion": "0.1.0",
+            "output": {},
+            "config": {},
+            "message": null,
+            "buildTime": null,
+            "ip": "127.0.0.1",
+            "privateFileList": [],
+            "publicFileCount": 1,
+            "publicTotalSize": 5,
+            "privateFileCount": 0,
+            "privateTotalSize": 0,
+            "uploadStartTime": 1234567890,
+            "uploadEndTime": 1234567891,
+            "plansuploadDuratiod": 1.0,
+            "preview": null,
+            "timeAgoInWords": "Just now"
+        })
+    };
+    let _list = test_server
+        .server
+        .mock("GET", "/list")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(json!([domain("a.com"), domain("b.com"), domain("c.com")]).to_string())
+        .create_async()
+        .await;
+    let _a = test_server
+        .server
+        .mock("GET", "/a.com/certs")
+        .with_status(200)
+        .with_body(
+            json!({ "certs": [{
+                "subject": "a.com",
+                "issuer": "Let's Encrypt",
+                "notBefore": "2025-01-01T00:00:00.000Z",
+                "notAfter": "2025-04-01T00:00:00.000Z",
+                "expInDays": 20,
+                "subjectAltNames": ["a.com"],
+                "certName": "a.com",
+                "autoRenew": true
+            }]})
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let _b = test_server
+        .server
+        .mock("GET", "/b.com/certs")
+        .with_status(200)
+        .with_body(json!({ "certs": [] }).to_string())
+        .create_async()
+        .await;
+
+    let failing = test_server
+        .server
+        .mock("GET", "/c.com/certs")
+        .with_status(404)
+        .with_body(json!({ "errors": ["not found"], "details": {}, "status": 404 }).to_string())
+        .create_async()
+        .await;
+
+    let auth = Auth::Token("abc123".to_string());
+    let inventory = test_server.client.all_certs_collect(&auth).await.unwrap();
+
+    assert_eq!(inventory.len(), 3);
+    assert_eq!(inventory[0].0, "a.com");
+    assert_eq!(inventory[0].1.as_ref().unwrap()[0].exp_in_days, 20);
+    assert_eq!(inventory[1].0, "b.com");
+    assert!(inventory[1].1.as_ref().unwrap().is_empty());
+    assert_eq!(inventory[2].0, "c.com");
+    assert!(inventory[2].1.is_err());
+
+    let result = test_server.client.all_certs(&auth).await;
+    assert!(result.is_err());
+
+    failing.remove_async().await;
+    let _c = test_server
+        .server
+        .mock("GET", "/c.com/certs")
+        .with_status(200)
+        .with_body(json!({ "certs": [] }).to_string())
+        .create_async()
+        .await;
+    let inventory = test_server.client.all_certs(&auth).await.unwrap();
+    let domains: Vec<_> = inventory
+        .iter()
+        .map(|(domain, _)| domain.as_str())
+        .collect();
+    assert_eq!(domains, ["a.com", "b.com", "c.com"]);
+    assert_eq!(inventory[0].1[0].exp_in_days, 20);
+    assert!(inventory[2].1.is_empty());
+}
+
+#[tokio::test]
+async fn test_publish_aborts_when_live_rev_is_newer() {
+    use surge_sdk::PublishOptions;
+
+    let mut test_server = TestServer::new().await;
+    let _metadata = test_server
+        .server
+        .mock("GET", "/test.surge.sh/metadata.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(metadata_body(200, 0, 0).to_string())
+        .create_async()
+        .await;
+    let upload = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+
+    let auth = Auth::Token("abc123".to_string());
+    let options = PublishOptions::default().fail_if_newer_than(100);
+    let result = test_server
+        .client
+        .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(SurgeError::Api {
+            status: Some(409),
+            ..
+        })
+    ));
+    upload.assert_async().await;
+}
+
+#[test]
+fn test_deploy_summary_envelope() {
+    use surge_sdk::DeploySummary;
+
+    let summary = DeploySummary {
+        urls: vec!["example.surge.sh".to_string()],
+        rev: Some(1700000000000),
+        file_count: Some(3),
+        total_size: Some(1024),
+        events: 5,
+        ..DeploySummary::new("example.surge.sh")
+    };
+
+    let envelope = summary.to_json_envelope().unwrap();
+    let value: serde_json::Value = serde_json::from_str(&envelope).unwrap();
+    assert_eq!(value["schema"], 1);
+    assert_eq!(value["domain"], "example.surge.sh");
+    assert_eq!(value["fileCount"], 3);
+    assert_eq!(
+        DeploySummary::from_json_envelope(&envelope).unwrap(),
+        summary
+    );
+
+    let future = envelope.replace("\"schema\":1", "\"schema\":2");
+    assert!(matches!(
+        DeploySummary::from_json_envelope(&future),
+        Err(SurgeError::Json(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_publish_source_commit() {
+    use surge_sdk::PublishOptions;
+
+    let mut test_server = TestServer::new().await;
+    let mut live = metadata_body(100, 0, 0);
+    live["message"] = json!("Deploy abc123");
+    let _metadata = test_server
+        .server
+        .mock("GET", "/test.surge.sh/metadata.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(live.to_string())
+        .create_async()
+        .await;
+    let upload = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .match_header("x-source-commit", "def456")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("")
+        .expect(1)
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    // Same commit as the live deployment: nothing is uploaded
+    let options = PublishOptions::default()
+        .with_source_commit("abc123")
+        .with_skip_same_commit(true);
+    let events = test_server
+        .client
+        .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+        .await
+        .unwrap();
+    let summary = surge_sdk::stream::summarize("test.surge.sh", events)
+        .await
+        .unwrap();
+    assert_eq!(summary.events, 0);
+
+    // A new commit is uploaded with the header
+    let options = PublishOptions::default()
+        .with_source_commit("def456")
+        .with_skip_same_commit(true);
+    let _events = test_server
+        .client
+        .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+        .await
+        .unwrap();
+
+    upload.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_publish_source_commit_requires_whole_word() {
+    use surge_sdk::PublishOptions;
+
+    let mut test_server = TestServer::new().await;
+    let mut live = metadata_body(100, 0, 0);
+    live["message"] = json!("Release v1.2 (abc123def)");
+    let _metadata = test_server
+        .server
+        .mock("GET", "/test.surge.sh/metadata.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(live.to_string())
+        .create_async()
+        .await;
+    let upload = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("")
+        .expect(2)
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    // Neither a substring of a word nor a prefix of the recorded commit counts as live
+    for commit in ["1", "abc123"] {
+        let options = PublishOptions::default()
+            .with_source_commit(commit)
+            .with_skip_same_commit(true);
+        let _events = test_server
+            .client
+            .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+            .await
+            .unwrap();
+    }
+    upload.assert_async().await;
+
+    // The whole commit, stripped of its parentheses, does
+    let options = PublishOptions::default()
+        .with_source_commit("abc123def")
+        .with_skip_same_commit(true);
+    let events = test_server
+        .client
+        .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+        .await
+        .unwrap();
+    let summary = surge_sdk::stream::summarize("test.surge.sh", events)
+        .await
+        .unwrap();
+    assert_eq!(summary.events, 0);
+    upload.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_publish_platform() {
+    use surge_sdk::PublishOptions;
+
+    let mut test_server = TestServer::new().await;
+    let upload = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .match_header("platform", "pages.example.com")
+        .match_header(
+            "argv",
+            mockito::Matcher::Regex("\"platform\":\"pages.example.com\"".to_string()),
+        )
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("")
+        .create_async()
+        .await;
+    let mut recorded = metadata_body(100, 0, 0);
+    recorded["platform"] = json!("pages.example.com");
+    let _metadata = test_server
+        .server
+        .mock("GET", "/test.surge.sh/metadata.json")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(recorded.to_string())
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    let options = PublishOptions::default().with_platform("pages.example.com");
+    let _events = test_server
+        .client
+        .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+        .await
+        .unwrap();
+    upload.assert_async().await;
+
+    let metadata = test_server
+        .client
+        .metadata("test.surge.sh", None, &auth)
+        .await
+        .unwrap();
+    assert_eq!(metadata.platform, "pages.example.com");
+}
+
+#[tokio::test]
+async fn test_publish_message() {
+    use futures_util::StreamExt;
+    use surge_sdk::{Event, PublishOptions};
+
+    let mut metadata = metadata_body(100, 0, 0);
+    metadata["current"] = json!(true);
+    metadata["message"] = json!("deploy v1.2.3");
+    let info = json!({
+        "type": "info",
+        "certs": [],
+        "config": { "pdf": false },
+        "instances": [],
+        "metadata": metadata,
+        "urls": [],
+    });
+
+    let mut test_server = TestServer::new().await;
+    let upload = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .match_header("message", "deploy v1.2.3")
+        .match_header(
+            "argv",
+            mockito::Matcher::Regex("\"message\":\"deploy v1.2.3\"".to_string()),
+        )
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body(format!("{}\n", info))
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    let options = PublishOptions::default().with_message("deploy v1.2.3");
+    let events: Vec<_> = test_server
+        .client
+        .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+    upload.assert_async().await;
+
+    match events.as_slice() {
+        [Ok(Event::Info(data))] => {
+            assert_eq!(data.metadata.message.as_deref(), Some("deploy v1.2.3"))
+        }
+        other => panic!("expected a single info event, got {:?}", other),
+    }
+
+    let options = PublishOptions::default().with_message("line one\nline two");
+    let result = test_server
+        .client
+        .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+        .await;
+    assert!(matches!(result, Err(SurgeError::Config(_))));
+}
+
+#[tokio::test]
+async fn test_publish_with_wip() {
+    use surge_sdk::PublishOptions;
+
+    let mut test_server = TestServer::new().await;
+    let upload = test_server
+        .server
+        .mock(
+            "PUT",
+            mockito::Matcher::Regex(r"^/\d+-test\.surge\.sh$".to_string()),
+        )
+        .match_header("stage", "true")
+        .match_header("message", "preview")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("")
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    let options = PublishOptions::default()
+        .with_wip(true)
+        .with_message("preview");
+    let _events = test_server
+        .client
+        .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+        .await
+        .unwrap();
+    upload.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_publish_with_wip_prefix() {
+    use surge_sdk::PublishOptions;
+
+    let mut test_server = TestServer::new().await;
+    let upload = test_server
+        .server
+        .mock("PUT", "/pr-123-test.surge.sh")
+        .match_header("stage", "true")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("")
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    let options = PublishOptions::default().with_wip_prefix("pr-123");
+    let _events = test_server
+        .client
+        .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+        .await
+        .unwrap();
+    upload.assert_async().await;
+
+    for prefix in ["", "-pr", "pr_123", "pr.123", &"x".repeat(60)] {
+        let options = PublishOptions::default().with_wip_prefix(prefix);
+        let result = test_server
+            .client
+            .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+            .await;
+        assert!(
+            matches!(result, Err(SurgeError::Config(_))),
+            "prefix {:?} was accepted",
+            prefix
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_publish_lossy_event_decoding() {
+    use futures_util::StreamExt;
+
+    let mut body = b"{\"type\":\"error\",\"message\":\"caf".to_vec();
+    body.push(0xe9); // latin-1 'é'
+    body.extend_from_slice(
+        b"\"}\n{\"type\":\"progress\",\"id\":\"upload\",\"written\":5,\"total\":5}\n",
+    );
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    for lossy in [false, true] {
+        let mut test_server =
+            TestServer::with_config(|config| config.with_lossy_event_decoding(lossy)).await;
+        let _m = test_server
+            .server
+            .mock("PUT", "/test.surge.sh")
+            .with_status(200)
+            .with_header("content-type", "application/ndjson")
+            .with_body(body.clone())
+            .create_async()
+            .await;
+
+        let stream = surge_sdk::publish(
+            &test_server.client,
+            dir.path(),
+            "test.surge.sh",
+            &auth,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let events: Vec<_> = stream.collect().await;
+
+        if lossy {
+            assert_eq!(events.len(), 2);
+            assert!(events.iter().all(Result::is_ok));
+            assert!(format!("{:?}", events[0]).contains('\u{FFFD}'));
+        } else {
+            assert!(matches!(events.as_slice(), [Err(SurgeError::Io(_))]));
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_publish_archive_zip() {
+    use futures_util::StreamExt;
+    use std::io::Write;
+    use surge_sdk::PublishOptions;
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    let mut test_server = TestServer::new().await;
+    let m = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .match_header("content-type", "application/gzip")
+        .match_header("file-count", "2")
+        .match_header("project-size", "12")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("{\"type\":\"progress\",\"id\":\"upload\",\"written\":12,\"total\":12}\n")
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    let archive = dir.path().join("artifact.zip");
+    let mut zip = ZipWriter::new(std::fs::File::create(&archive).unwrap());
+    for (name, contents) in [
+        ("index.html", "hello"),
+        ("css/site.css", "body {}"),
+        (".DS_Store", "junk"),
+    ] {
+        zip.start_file(name, SimpleFileOptions::default()).unwrap();
+        zip.write_all(contents.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+
+    let auth = Auth::Token("abc123".to_string());
+    let options = PublishOptions::default();
+    let stream = test_server
+        .client
+        .publish_archive(&archive, "test.surge.sh", &auth, &options)
+        .await
+        .unwrap();
+    let events: Vec<_> = stream.collect().await;
+
+    m.assert_async().await;
+    assert_eq!(events.len(), 1);
+    assert!(events[0].is_ok());
+}
+
+#[tokio::test]
+async fn test_domain_names() {
+    let mut test_server = TestServer::new().await;
+    let _m = test_server
+        .server
+        .mock("GET", "/list")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!([
+                { "domain": "a.com", "rev": 1, "privateFileList": ["x"], "output": {} },
+                { "domain": "b.com", "rev": 2 }
+            ])
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let auth = Auth::Token("abc123".to_string());
+    let names = test_server.client.domain_names(&auth).await.unwrap();
+
+    assert_eq!(names, vec!["a.com", "b.com"]);
+}
+
+#[test]
+fn test_upload_fin_event() {
+    use surge_sdk::types::RawEvent;
+
+    let raw: RawEvent = serde_json::from_value(json!({
+        "type": "uploadFin",
+        "config": { "force": null, "redirect": null, "cors": null, "hsts": null, "ttl": null }
+    }))
+    .unwrap();
+    let event = surge_sdk::Event::from(raw);
+
+    assert!(
+        matches!(&event, surge_sdk::Event::UploadFin(fin) if fin.upload_fin_response_type == "uploadFin")
+    );
+    assert!(event.is_terminal());
+    assert_eq!(event.to_string(), "[Event: uploadFin] Upload finished");
+}
+
+#[tokio::test]
+async fn test_publish_with_settings() {
+    use futures_util::StreamExt;
+    use mockito::Matcher;
+    use surge_sdk::{DomainSettings, PublishOptions, RedirectRule};
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+    let options = PublishOptions::default().with_settings(DomainSettings {
+        redirect: Some(vec![RedirectRule {
+            from: "/old".to_string(),
+            to: "/new".to_string(),
+            status: 301,
+        }]),
+        ..Default::default()
+    });
+    let settings_body = json!({ "redirect": [{ "from": "/old", "to": "/new", "status": 301 }] });
+
+    // A failed settings request aborts the publish before anything is uploaded
+    let mut test_server = TestServer::new().await;
+    let settings = test_server
+        .server
+        .mock("PUT", "/test.surge.sh/settings")
+        .match_body(Matcher::Json(settings_body.clone()))
+        .with_status(500)
+        .create_async()
+        .await;
+    let upload = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let result = surge_sdk::stream::publish_with(
+        &test_server.client,
+        dir.path(),
+        "test.surge.sh",
+        &auth,
+        &options,
+    )
+    .await;
+    assert!(result.is_err());
+    settings.assert_async().await;
+    upload.assert_async().await;
+
+    // Otherwise the settings are applied and the upload follows
+    let mut test_server = TestServer::new().await;
+    let settings = test_server
+        .server
+        .mock("PUT", "/test.surge.sh/settings")
+        .match_body(Matcher::Json(settings_body))
+        .with_status(200)
+        .create_async()
+        .await;
+    let upload = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("{\"type\":\"progress\",\"id\":\"upload\",\"written\":5,\"total\":5}\n")
+        .create_async()
+        .await;
+
+    let stream = surge_sdk::stream::publish_with(
+        &test_server.client,
+        dir.path(),
+        "test.surge.sh",
+        &auth,
+        &options,
+    )
+    .await
+    .unwrap();
+    let events: Vec<_> = stream.collect().await;
+    settings.assert_async().await;
+    upload.assert_async().await;
+    assert_eq!(events.len(), 1);
+}
+
+#[tokio::test]
+async fn test_audit_since() {
+    use chrono::{TimeZone, Utc};
+
+    let mut test_server = TestServer::new().await;
+    let _m = test_server
+        .server
+        .mock("GET", "/test.surge.sh/audit")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(
+            json!({
+                "1700000300000": { "rev": 3 },
+                "1600000000000": { "rev": 1 },
+                "1700000200000": { "rev": 2 },
+                "not-a-timestamp": { "rev": 4 }
+            })
+            .to_string(),
+        )
+        .create_async()
+        .await;
+
+    let auth = Auth::Token("abc123".to_string());
+    let since = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+    let entries = test_server
+        .client
+        .audit_since("test.surge.sh", since, &auth)
+        .await
+        .unwrap();
+
+    let revs: Vec<_> = entries.iter().map(|(_, entry)| entry.rev).collect();
+    assert_eq!(revs, vec![2, 3]);
+    assert_eq!(entries[0].0, "1700000200000");
+}
+
+#[tokio::test]
+async fn test_download_file() {
+    use futures_util::TryStreamExt;
+
+    let mut test_server = TestServer::new().await;
+    let _m = test_server
+        .server
+        .mock("GET", "/test.surge.sh/files/css/my%20site.css")
+        .match_header("authorization", mockito::Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "text/css")
+        .with_body("body { color: red; }")
+        .create_async()
+        .await;
+    let _missing = test_server
+        .server
+        .mock("GET", "/test.surge.sh/files/missing.html")
+        .with_status(404)
+        .with_body("Not found")
+        .create_async()
+        .await;
+
+    let auth = Auth::Token("abc123".to_string());
+    let stream = test_server
+        .client
+        .download_file("test.surge.sh", "/css/my site.css", &auth)
+        .await
+        .unwrap();
+    let chunks: Vec<_> = stream.try_collect().await.unwrap();
+    assert_eq!(chunks.concat(), b"body { color: red; }");
+
+    let result = test_server
+        .client
+        .download_file("test.surge.sh", "missing.html", &auth)
+        .await;
+    assert!(matches!(
+        result,
+        Err(SurgeError::Api {
+            status: Some(404),
+            ..
+        })
+    ));
+
+    let result = test_server
+        .client
+        .download_file("test.surge.sh", "../secret", &auth)
+        .await;
+    assert!(matches!(result, Err(SurgeError::Config(_))));
+}
+
+#[test]
+fn test_known_event_types() {
+    use surge_sdk::{EVENT_TYPES, Event, types::RawEvent};
+
+    assert_eq!(Event::known_types(), EVENT_TYPES);
+    for event_type in ["cert", "progress", "info", "ip", "subscription"] {
+        assert!(EVENT_TYPES.contains(&event_type));
+    }
+
+    let raw: RawEvent = serde_json::from_value(json!({ "type": "queued", "position": 3 })).unwrap();
+    match Event::from(raw) {
+        Event::Unknown { event_type, .. } => assert!(!EVENT_TYPES.contains(&event_type.as_str())),
+        other => panic!("expected an unknown event, got {:?}", other),
+    }
+}
+
+/// A backoff short enough to keep polling tests fast.
+fn quick_backoff() -> surge_sdk::PollBackoff {
+    let delay = std::time::Duration::from_millis(10);
+    surge_sdk::PollBackoff::new(delay, delay)
+}
+
+#[tokio::test]
+async fn test_certs_normalizes_domain() {
+    let mut test_server = TestServer::new().await;
+    let certs = test_server
+        .server
+        .mock("GET", "/foo.surge.sh/certs")
+        .with_status(200)
+        .with_body(json!({ "certs": [] }).to_string())
+        .expect(2)
+        .create_async()
+        .await;
+    let auth = Auth::Token("abc123".to_string());
+
+    for domain in ["foo.surge.sh/", "Foo.Surge.sh"] {
+        let response = test_server.client.certs(domain, &auth).await.unwrap();
+        assert!(response.certs.is_empty());
+    }
+    certs.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_wait_for_cert() {
+    let mut test_server = TestServer::new().await;
+    // The first poll sees no certificate, later polls see the issued one
+    let pending = test_server
+        .server
+        .mock("GET", "/example.com/certs")
+        .with_status(200)
+        .with_body(json!({ "certs": [] }).to_string())
+        .expect(1)
+        .create_async()
+        .await;
+    let issued = test_server
+        .server
+        .mock("GET", "/example.com/certs")
+        .with_status(200)
+        .with_body(
+            json!({ "certs": [{
+                "subject": "example.com",
+                "issuer": "Let's Encrypt",
+                "notBefore": "2025-01-01T00:00:00.000Z",
+                "notAfter": (chrono::Utc::now() + chrono::Duration::days(90)).to_rfc3339(),
+                "expInDays": 90,
+                "subjectAltNames": ["example.com"],
+                "certName": "example.com",
+                "autoRenew": true
+            }]})
+            .to_string(),
+        )
+        .expect(1)
+        .create_async()
+        .await;
+
+    let cert = test_server
+        .client
+        .wait_for_cert(
+            "example.com",
+            &Auth::Token("abc123".to_string()),
+            std::time::Duration::from_secs(10),
+            quick_backoff(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(cert.cert_name, "example.com");
+    pending.assert_async().await;
+    issued.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_encrypt() {
+    use futures_util::StreamExt;
+    use surge_sdk::Event;
+
+    let mut test_server = TestServer::new().await;
+    let encrypt = test_server
+        .server
+        .mock("PUT", "/example.com/encrypt")
+        .match_header("accept", "application/ndjson")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body(concat!(
+            "{\"type\":\"progress\",\"id\":\"encrypt\",\"written\":1,\"total\":2}\n",
+            "{\"type\":\"cert\",\"data\":{\"issuer\":\"Let's Encrypt\",",
+            "\"altnames\":[\"example.com\"],\"expiresInWords\":\"in 3 months\"}}\n",
+        ))
+        .expect(2)
+        .create_async()
+        .await;
+    let _certs = test_server
+        .server
+        .mock("GET", "/example.com/certs")
+        .with_status(200)
+        .with_body(
+            json!({ "certs": [{
+                "subject": "example.com",
+                "issuer": "Let's Encrypt",
+                "notBefore": "2025-01-01T00:00:00.000Z",
+                "notAfter": (chrono::Utc::now() + chrono::Duration::days(90)).to_rfc3339(),
+                "expInDays": 90,
+                "subjectAltNames": ["example.com"],
+                "certName": "example.com",
+                "autoRenew": true
+            }]})
+            .to_string(),
+        )
+        .create_async()
+        .await;
+    let auth = Auth::Token("abc123".to_string());
+
+    let events: Vec<Event> = test_server
+        .client
+        .encrypt("example.com", &auth)
+        .await
+        .unwrap()
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+    assert_eq!(events.len(), 2);
+    match &events[1] {
+        Event::Cert(cert) => {
+            assert_eq!(cert.issuer, "Let's Encrypt");
+            assert_eq!(cert.alt_names, vec!["example.com"]);
+        }
+        other => panic!("expected a cert event, got {:?}", other),
+    }
+
+    let cert = test_server
+        .client
+        .encrypt_and_wait(
+            "example.com",
+            &auth,
+            std::time::Duration::from_secs(10),
+            quick_backoff(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(cert.cert_name, "example.com");
+    encrypt.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_wait_for_cert_times_out() {
+    let mut test_server = TestServer::new().await;
+    let _m = test_server
+        .server
+        .mock("GET", "/example.com/certs")
+        .with_status(200)
+        .with_body(json!({ "certs": [] }).to_string())
+        .create_async()
+        .await;
+
+    let result = test_server
+        .client
+        .wait_for_cert(
+            "example.com",
+            &Auth::Token("abc123".to_string()),
+            std::time::Duration::ZERO,
+            quick_backoff(),
+        )
+        .await;
+
+    assert!(matches!(result, Err(SurgeError::Network(_))));
+}
+
+#[tokio::test]
+async fn test_wait_for_cert_with_budget() {
+    let mut test_server = TestServer::new().await;
+    // A budget of two retries allows the first poll plus two more, whatever the timeout
+    let pending = test_server
+        .server
+        .mock("GET", "/example.com/certs")
+        .with_status(200)
+        .with_body(json!({ "certs": [] }).to_string())
+        .expect(3)
+        .create_async()
+        .await;
+
+    let budget = surge_sdk::RetryBudget::new(2);
+    let result = test_server
+        .client
+        .wait_for_cert_with_budget(
+            "example.com",
+            &Auth::Token("abc123".to_string()),
+            std::time::Duration::from_secs(60),
+            quick_backoff(),
+            &budget,
+        )
+        .await;
+
+    match result {
+        Err(SurgeError::Network(message)) => assert!(message.contains("retry budget")),
+        other => panic!("expected an exhausted budget, got {:?}", other),
+    }
+    assert_eq!(budget.remaining(), 0);
+    pending.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_wait_for_dns() {
+    use surge_sdk::DnsRecordInput;
+
+    let mut test_server = TestServer::new().await;
+    let pending = test_server
+        .server
+        .mock("GET", "/example.com/dns")
+        .with_status(200)
+        .with_body(json!([{ "type": "A", "name": "www", "value": "192.0.2.9" }]).to_string())
+        .expect(1)
+        .create_async()
+        .await;
+    let _live = test_server
+        .server
+        .mock("GET", "/example.com/dns")
+        .with_status(200)
+        .with_body(
+            json!({ "records": [{ "type": "cname", "name": "www.example.com.", "value": "na-west1.surge.sh" }] })
+                .to_string(),
+        )
+        .create_async()
+        .await;
+    let auth = Auth::Token("abc123".to_string());
+
+    let expected = DnsRecordInput::new("CNAME", "www", "na-west1.surge.sh");
+    test_server
+        .client
+        .wait_for_dns(
+            "example.com",
+            &expected,
+            std::time::Duration::from_secs(30),
+            quick_backoff(),
+            &auth,
+        )
+        .await
+        .unwrap();
+    pending.assert_async().await;
+
+    let missing = DnsRecordInput::new("A", "@", "192.0.2.1");
+    let result = test_server
+        .client
+        .wait_for_dns(
+            "example.com",
+            &missing,
+            std::time::Duration::ZERO,
+            quick_backoff(),
+            &auth,
+        )
+        .await;
+    match result {
+        Err(SurgeError::Network(message)) => assert!(message.contains("na-west1.surge.sh")),
+        other => panic!("expected a timeout, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_publish_override_cli_headers() {
+    use surge_sdk::PublishOptions;
+
+    let mut test_server = TestServer::new().await;
+    let upload = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .match_header("timestamp", "2025-01-01T00:00:00+00:00")
+        .match_header("version", "9.9.9")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("")
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    let options = PublishOptions::default()
+        .with_override_timestamp("2025-01-01T00:00:00+00:00")
+        .with_override_version("9.9.9");
+    let _events = test_server
+        .client
+        .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+        .await
+        .unwrap();
+    upload.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_exists() {
+    let mut test_server = TestServer::new().await;
+    let _live = test_server
+        .server
+        .mock("HEAD", "/live.surge.sh/metadata.json")
+        .match_header("authorization", mockito::Matcher::Any)
+        .with_status(200)
+        .create_async()
+        .await;
+    let _missing = test_server
+        .server
+        .mock("HEAD", "/missing.surge.sh/metadata.json")
+        .with_status(404)
+        .create_async()
+        .await;
+    let _forbidden = test_server
+        .server
+        .mock("HEAD", "/other.surge.sh/metadata.json")
+        .with_status(403)
+        .create_async()
+        .await;
+    let auth = Auth::Token("abc123".to_string());
+
+    assert!(
+        test_server
+            .client
+            .exists("live.surge.sh", &auth)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !test_server
+            .client
+            .exists("missing.surge.sh", &auth)
+            .await
+            .unwrap()
+    );
+    let result = test_server.client.exists("other.surge.sh", &auth).await;
+    assert!(matches!(
+        result,
+        Err(SurgeError::Api {
+            status: Some(403),
+            ..
+        })
+    ));
+}
+
+#[tokio::test]
+async fn test_inspect_live_headers() {
+    let mut test_server = TestServer::new().await;
+    let _redirect = test_server
+        .server
+        .mock("HEAD", "/old")
+        .with_status(301)
+        .with_header("location", "/index.html")
+        .create_async()
+        .await;
+    let _page = test_server
+        .server
+        .mock("HEAD", "/index.html")
+        .match_header("authorization", mockito::Matcher::Missing)
+        .with_status(200)
+        .with_header("content-type", "text/html")
+        .with_header("strict-transport-security", "max-age=31536000")
+        .create_async()
+        .await;
+    let live = test_server.server.url();
+
+    let followed = test_server
+        .client
+        .inspect_live_headers(&live, "/old", true)
+        .await
+        .unwrap();
+    assert_eq!(followed.status, 200);
+    assert_eq!(followed.url.path(), "/index.html");
+    assert_eq!(followed.get("Content-Type"), Some("text/html"));
+    assert_eq!(
+        followed.get("strict-transport-security"),
+        Some("max-age=31536000")
+    );
+
+    let unfollowed = test_server
+        .client
+        .inspect_live_headers(&live, "/old", false)
+        .await
+        .unwrap();
+    assert_eq!(unfollowed.status, 301);
+    assert_eq!(unfollowed.get("location"), Some("/index.html"));
+}
+
+#[test]
+fn test_blocking_client_with_handle() {
+    use surge_sdk::{Config, blocking};
+
+    let mut server = mockito::Server::new();
+    let certs = server
+        .mock("GET", "/test.surge.sh/certs")
+        .with_status(200)
+        .with_body(json!({ "certs": [] }).to_string())
+        .expect(2)
+        .create();
+    let auth = Auth::Token("abc123".to_string());
+
+    let sdk = blocking::SurgeSdk::new(Config::new(server.url(), "0.1.0").unwrap()).unwrap();
+    assert!(sdk.certs("test.surge.sh", &auth).unwrap().certs.is_empty());
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let sdk = blocking::SurgeSdk::with_handle(
+        Config::new(server.url(), "0.1.0").unwrap(),
+        runtime.handle().clone(),
+    )
+    .unwrap();
+    assert!(sdk.certs("test.surge.sh", &auth).unwrap().certs.is_empty());
+    certs.assert();
+}
+
+#[tokio::test]
+async fn test_publish_reports_clock_skew() {
+    let mut test_server = TestServer::new().await;
+    let server_date = chrono::Utc::now() + chrono::Duration::hours(2);
+    let _upload = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .with_status(400)
+        .with_header(
+            "date",
+            &server_date.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+        )
+        .with_body("invalid timestamp")
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    let result = test_server
+        .client
+        .publish(dir.path(), "test.surge.sh", &auth, None, None)
+        .await;
+    match result {
+        Err(SurgeError::Config(message)) => {
+            assert!(message.contains("client clock skew detected"));
+            assert!(message.contains("behind"));
+        }
+        Err(other) => panic!("expected a clock skew error, got {}", other),
+        Ok(_) => panic!("expected a clock skew error"),
+    }
+}
+
+#[tokio::test]
+async fn test_publish_headers_from_map() {
+    use std::collections::HashMap;
+    use surge_sdk::PublishOptions;
+
+    let mut test_server = TestServer::new().await;
+    let upload = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .match_header("x-build-id", "42")
+        .match_header("x-team", "web")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("")
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    let headers = HashMap::from([("x-build-id", "42"), ("x-team", "web")]);
+    let options = PublishOptions::default().with_headers(headers);
+    let _events = test_server
+        .client
+        .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+        .await
+        .unwrap();
+    upload.assert_async().await;
+
+    let options = PublishOptions::default().with_headers([("x bad", "1")]);
+    let result = test_server
+        .client
+        .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+        .await;
+    assert!(matches!(result, Err(SurgeError::Config(_))));
+}
+
+#[tokio::test]
+async fn test_publish_file() {
+    use surge_sdk::PublishOptions;
+
+    let mut test_server = TestServer::new().await;
+    let upload = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .match_header("file-count", "1")
+        .match_header("project-size", "5")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("")
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    let page = dir.path().join("index.html");
+    fs::write(&page, "hello").await.unwrap();
+    fs::write(dir.path().join("other.html"), "not uploaded")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    let options = PublishOptions::default();
+    let _events = test_server
+        .client
+        .publish_file(&page, "test.surge.sh", &auth, &options)
+        .await
+        .unwrap();
+    upload.assert_async().await;
+
+    let result = test_server
+        .client
+        .publish_file(dir.path(), "test.surge.sh", &auth, &options)
+        .await;
+    assert!(matches!(result, Err(SurgeError::Io(_))));
+}
+
+#[tokio::test]
+async fn test_publish_multipart_upload_mode() {
+    let mut test_server =
+        TestServer::with_config(|config| config.with_upload_mode(surge_sdk::UploadMode::Multipart))
+            .await;
+    let upload = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .match_header(
+            "content-type",
+            mockito::Matcher::Regex("^multipart/form-data; boundary=".to_string()),
+        )
+        .match_header("file-count", "1")
+        .match_request(|request| {
+            let body = String::from_utf8_lossy(request.body().unwrap()).into_owned();
+            body.contains("name=\"file\"; filename=\"project.tar.gz\"")
+                && body.contains("Content-Type: application/gzip")
+        })
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("")
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    let _events = test_server
+        .client
+        .publish(dir.path(), "test.surge.sh", &auth, None, None)
+        .await
+        .unwrap();
+    upload.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_publish_webhook() {
+    use surge_sdk::PublishOptions;
+
+    let mut test_server = TestServer::new().await;
+    let _upload = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("{\"type\":\"progress\",\"id\":\"upload\",\"written\":5,\"total\":5}\n")
+        .create_async()
+        .await;
+    let _failed_upload = test_server
+        .server
+        .mock("PUT", "/failed.surge.sh")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("not json\n")
+        .create_async()
+        .await;
+    let webhook = test_server
+        .server
+        .mock("POST", "/hook")
+        .match_header("content-type", "application/json")
+        .match_body(mockito::Matcher::PartialJson(
+            json!({ "schema": 1, "domain": "test.surge.sh", "events": 1 }),
+        ))
+        // A failing webhook does not fail the deploy
+        .with_status(500)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+    let hook_url = format!("{}/hook", test_server.server.url())
+        .parse()
+        .unwrap();
+    let options = PublishOptions::default().with_webhook(hook_url);
+
+    let events = test_server
+        .client
+        .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+        .await
+        .unwrap();
+    let summary = surge_sdk::stream::summarize("test.surge.sh", events)
+        .await
+        .unwrap();
+    assert_eq!(summary.events, 1);
+
+    // A deploy whose stream fails is not reported
+    let events = test_server
+        .client
+        .publish_with(dir.path(), "failed.surge.sh", &auth, &options)
+        .await
+        .unwrap();
+    assert!(
+        surge_sdk::stream::summarize("failed.surge.sh", events)
+            .await
+            .is_err()
+    );
+
+    // The webhook is posted in the background
+    for _ in 0..50 {
+        if webhook.matched_async().await {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    webhook.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_publish_without_compression() {
+    let mut test_server =
+        TestServer::with_config(|config| config.with_compression_enabled(false)).await;
+    let upload = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .match_header("content-type", "application/x-tar")
+        .match_header("file-count", "1")
+        .match_header("project-size", "5")
+        .match_request(|request| {
+            let body = request.body().unwrap();
+            body.get(257..262) == Some(b"ustar".as_slice())
+        })
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("")
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    let _events = test_server
+        .client
+        .publish(dir.path(), "test.surge.sh", &auth, None, None)
+        .await
+        .unwrap();
+    upload.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_publish_retries_stale_upload() {
+    use surge_sdk::PublishOptions;
+
+    let mut test_server = TestServer::new().await;
+    let stale = |server: &mut mockito::ServerGuard| {
+        server
+            .mock("PUT", "/test.surge.sh")
+            .with_status(400)
+            .with_body("file-count mismatch: header says 1, tarball has 2")
+            .expect(1)
+    };
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    // Without the flag, the rejection is returned
+    let rejected = stale(&mut test_server.server).create_async().await;
+    let options = PublishOptions::default();
+    let result = test_server
+        .client
+        .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+        .await;
+    assert!(matches!(
+        result,
+        Err(SurgeError::Api {
+            status: Some(400),
+            ..
+        })
+    ));
+    rejected.assert_async().await;
+    rejected.remove_async().await;
+
+    // With it, the first rejection is followed by a single fresh upload
+    let rejected = stale(&mut test_server.server).create_async().await;
+    let accepted = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_body("")
+        .expect(1)
+        .create_async()
+        .await;
+    let options = PublishOptions::default().with_retry_stale_upload(true);
+    let _events = test_server
+        .client
+        .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+        .await
+        .unwrap();
+    rejected.assert_async().await;
+    accepted.assert_async().await;
+}
+
+#[test]
+fn test_concurrent_client_construction() {
+    use surge_sdk::{Config, SURGE_API, SurgeSdk};
+
+    let barrier = std::sync::Arc::new(std::sync::Barrier::new(8));
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let barrier = barrier.clone();
+            std::thread::spawn(move || {
+                barrier.wait();
+                SurgeSdk::new(Config::new(SURGE_API, "0.1.0").unwrap()).map(|_| ())
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().unwrap().unwrap();
+    }
+}
+
+#[test]
+fn test_auth_from_netrc() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(".netrc");
+    std::fs::write(
+        &path,
+        "# credentials\n\
+         machine github.com login octocat password gh-token\n\
+         machine surge.surge.sh\n  login user@example.com\n  password surge-token\n\
+         macdef init\ncd /pub\n\n\
+         default login anonymous password guest\n",
+    )
+    .unwrap();
+
+    match Auth::from_netrc_file(&path, "surge.surge.sh").unwrap() {
+        Auth::UserPass { username, password } => {
+            assert_eq!(username, "user@example.com");
+            assert_eq!(password, "surge-token");
+        }
+        other => panic!("expected user/pass credentials, got {:?}", other),
+    }
+    match Auth::from_netrc_file(&path, "example.com").unwrap() {
+        Auth::UserPass { username, .. } => assert_eq!(username, "anonymous"),
+        other => panic!("expected the default entry, got {:?}", other),
+    }
+
+    std::fs::write(&path, "machine surge.surge.sh login user@example.com\n").unwrap();
+    assert!(matches!(
+        Auth::from_netrc_file(&path, "example.com"),
+        Err(SurgeError::Auth(_))
+    ));
+    assert!(matches!(
+        Auth::from_netrc_file(&path, "surge.surge.sh"),
+        Err(SurgeError::Config(_))
+    ));
+
+    std::fs::write(&path, "machine surge.surge.sh login\n").unwrap();
+    assert!(matches!(
+        Auth::from_netrc_file(&path, "surge.surge.sh"),
+        Err(SurgeError::Config(_))
+    ));
+    assert!(matches!(
+        Auth::from_netrc_file(&dir.path().join("missing"), "surge.surge.sh"),
+        Err(SurgeError::Config(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_analytics_for_revision() {
+    let mut test_server = TestServer::new().await;
+    let _scoped = test_server
+        .server
+        .mock("GET", "/test.surge.sh/1700000000000/analytics")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(json!({ "domain": "test.surge.sh", "range": ["2024-01-01"] }).to_string())
+        .create_async()
+        .await;
+    let _rejected = test_server
+        .server
+        .mock("GET", "/test.surge.sh/1600000000000/analytics")
+        .with_status(404)
+        .with_body("Not Found")
+        .create_async()
+        .await;
+
+    let auth = Auth::Token("abc123".to_string());
+    let analytics = test_server
+        .client
+        .analytics_for_revision("test.surge.sh", "1700000000000", &auth)
+        .await
+        .unwrap();
+    assert_eq!(analytics.domain.as_deref(), Some("test.surge.sh"));
+    assert_eq!(analytics.range, vec!["2024-01-01"]);
+
+    let result = test_server
+        .client
+        .analytics_for_revision("test.surge.sh", "1600000000000", &auth)
+        .await;
+    match result {
+        Err(SurgeError::Api {
+            status, details, ..
+        }) => {
+            assert_eq!(status, Some(404));
+            assert_eq!(details, json!("Not Found"));
+        }
+        other => panic!("expected an Api error, got {:?}", other),
+    }
+
+    let result = test_server
+        .client
+        .analytics_for_revision("test.surge.sh", "", &auth)
+        .await;
+    assert!(matches!(result, Err(SurgeError::Config(_))));
+}
+
+#[tokio::test]
+async fn test_publish_read_timeout() {
+    use futures_util::StreamExt;
+    use std::time::Duration;
+    use surge_sdk::PublishOptions;
+
+    let mut test_server = TestServer::new().await;
+    let _upload = test_server
+        .server
+        .mock("PUT", "/test.surge.sh")
+        .with_status(200)
+        .with_header("content-type", "application/ndjson")
+        .with_chunked_body(|writer| {
+            std::thread::sleep(Duration::from_millis(1500));
+            writer.write_all(b"{\"type\":\"info\"}\n")
+        })
+        .create_async()
+        .await;
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    let options = PublishOptions::default()
+        .with_read_timeout(Duration::from_millis(200))
+        .with_connect_timeout(Duration::from_secs(5));
+    let result = match test_server
+        .client
+        .publish_with(dir.path(), "test.surge.sh", &auth, &options)
+        .await
+    {
+        Ok(events) => events
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .find_map(Result::err)
+            .map_or(Ok(()), Err),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Err(e) => assert!(e.to_string().contains("timeout"), "unexpected error: {}", e),
+        Ok(()) => panic!("expected the publish to time out"),
+    }
+}
+
+#[tokio::test]
+async fn test_publish_oversized_argv() {
+    use surge_sdk::stream::MAX_ARGV_HEADER_BYTES;
+
+    let test_server = TestServer::new().await;
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("index.html"), "hello")
+        .await
+        .unwrap();
+    let auth = Auth::Token("abc123".to_string());
+
+    let argv: Vec<String> = (0..MAX_ARGV_HEADER_BYTES / 8)
+        .map(|i| format!("file-{}.html", i))
+        .collect();
+    let result = test_server
+        .client
+        .publish(dir.path(), "test.surge.sh", &auth, None, Some(&argv))
+        .await;
+    assert!(
+        matches!(result, Err(SurgeError::Config(message)) if message.contains("argv too large"))
+    );
+}
+
+#[tokio::test]
+async fn test_metadata_many() {
+    let mut test_server = TestServer::new().await;
+    for (domain, rev) in [("a.surge.sh", 1), ("c.surge.sh", 3)] {
+        test_server
+            .server
+            .mock("GET", format!("/{}/metadata.json", domain).as_str())
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(metadata_body(rev, 0, 0).to_string())
+            .create_async()
+            .await;
+    }
+    let _failing = test_server
+        .server
+        .mock("GET", "/b.surge.sh/metadata.json")
+        .with_status(500)
+        .with_body("Internal Server Error")
+        .create_async()
+        .await;
+
+    let auth = Auth::Token("abc123".to_string());
+    let domains = ["a.surge.sh", "b.surge.sh", "c.surge.sh"];
+
+    let results = test_server
+        .client
+        .metadata_many_collect(&domains, &auth)
+        .await;
+    let names: Vec<_> = results.iter().map(|(domain, _)| domain.as_str()).collect();
+    assert_eq!(names, domains);
+    assert_eq!(results[0].1.as_ref().unwrap().rev, 1);
+    assert!(results[1].1.is_err());
+    assert_eq!(results[2].1.as_ref().unwrap().rev, 3);
+
+    let result = test_server.client.metadata_many(&domains, &auth).await;
+    assert!(result.is_err());
+
+    let results = test_server
+        .client
+        .metadata_many(&["a.surge.sh", "c.surge.sh"], &auth)
+        .await
+        .unwrap();
+    let revs: Vec<_> = results.iter().map(|(_, metadata)| metadata.rev).collect();
+    assert_eq!(revs, [1, 3]);
+}
+
+#[tokio::test]
+async fn test_metadata_many_fail_fast_cancels_in_flight() {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+    use std::time::Duration;
+
+    let mut test_server = TestServer::new().await;
+    let _failing = test_server
+        .server
+        .mock("GET", "/a.surge.sh/metadata.json")
+        .with_status(404)
+        .with_chunked_body(|writer| {
+            // Fail only once the other requests are under way
+            std::thread::sleep(Duration::from_millis(200));
+            writer.write_all(
+                json!({ "errors": ["not found"], "details": {}, "status": 404 })
+                    .to_string()
+                    .as_bytes(),
+            )
+        })
+        .create_async()
+        .await;
+
+    // The slow responses trickle whitespace until the client hangs up; a write only fails once
+    // the request has been abandoned
+    let completed = Arc::new(AtomicUsize::new(0));
+    let abandoned = Arc::new(AtomicUsize::new(0));
+    let mut slow_mocks = Vec::new();
+    for domain in ["b.surge.sh", "c.surge.sh"] {
+        let (completed, abandoned) = (completed.clone(), abandoned.clone());
+        let mock = test_server
+            .server
+            .mock("GET", format!("/{}/metadata.json", domain).as_str())
+            .with_status(200)
+            .with_chunked_body(move |writer| {
+                for _ in 0..1000 {
+                    if writer.write_all(b" ").is_err() {
+                        abandoned.fetch_add(1, Ordering::SeqCst);
+                        return Ok(());
+                    }
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+                completed.fetch_add(1, Ordering::SeqCst);
+                writer.write_all(metadata_body(2, 0, 0).to_string().as_bytes())
+            })
+            .create_async()
+            .await;
+        slow_mocks.push(mock);
+    }
+
+    let auth = Auth::Token("abc123".to_string());
+    let domains = ["a.surge.sh", "b.surge.sh", "c.surge.sh"];
+    let result = test_server.client.metadata_many(&domains, &auth).await;
+    assert!(matches!(result, Err(SurgeError::Json(_))));
+
+    // Both slow requests were started, then cancelled before their responses completed
+    for mock in &slow_mocks {
+        mock.assert_async().await;
+    }
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while abandoned.load(Ordering::SeqCst) < 2 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("in-flight requests were not cancelled");
+    assert_eq!(completed.load(Ordering::SeqCst), 0);
+}
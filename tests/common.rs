@@ -10,8 +10,12 @@ pub struct TestServer {
 
 impl TestServer {
     pub async fn new() -> Self {
+        Self::with_config(|config| config).await
+    }
+
+    pub async fn with_config(customize: impl FnOnce(Config) -> Config) -> Self {
         let server = mockito::Server::new_async().await;
-        let config = Config::new(server.url(), "0.1.0").unwrap();
+        let config = customize(Config::new(server.url(), "0.1.0").unwrap());
         let client = SurgeSdk::new(config).unwrap();
         Self { server, client }
     }